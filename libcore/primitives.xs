@@ -1,3 +1,14 @@
+operator == precedence 0 left;
+operator != precedence 0 left;
+operator < precedence 0 left;
+operator <= precedence 0 left;
+operator > precedence 0 left;
+operator >= precedence 0 left;
+operator + precedence 1 left;
+operator - precedence 1 left;
+operator * precedence 2 left;
+operator / precedence 2 left;
+
 primitive type bool;
 
 primitive type f32;
@@ -8,6 +19,7 @@ operator / (lhs: f32, rhs: f32) -> f32;
 implicit cast f32 -> f64;
 explicit cast f32 -> i32;
 explicit cast f32 -> i64;
+explicit cast f32 -> u32;
 
 primitive type f64;
 operator + (lhs: f64, rhs: f64) -> f64;
@@ -26,6 +38,7 @@ operator / (lhs: i32, rhs: i32) -> i32;
 implicit cast i32 -> i64;
 explicit cast i32 -> f32;
 explicit cast i32 -> f64;
+explicit cast i32 -> u32;
 
 primitive type i64;
 operator + (lhs: i64, rhs: i64) -> i64;
@@ -35,7 +48,23 @@ operator / (lhs: i64, rhs: i64) -> i64;
 explicit cast i64 -> f32;
 explicit cast i64 -> i32;
 explicit cast i64 -> i64;
+explicit cast i64 -> u32;
+
+primitive type u32;
+operator + (lhs: u32, rhs: u32) -> u32;
+operator - (lhs: u32, rhs: u32) -> u32;
+operator * (lhs: u32, rhs: u32) -> u32;
+operator / (lhs: u32, rhs: u32) -> u32;
+implicit cast u32 -> i64;
+explicit cast u32 -> f32;
+explicit cast u32 -> f64;
+explicit cast u32 -> i32;
 
+primitive type vec2;
 primitive type vec3;
 primitive type vec4;
 primitive type mat4x4;
+
+primitive type bvec2;
+primitive type bvec3;
+primitive type bvec4;
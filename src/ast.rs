@@ -1,5 +1,8 @@
 use ::std::fmt;
 use ::std::str;
+use ::std::mem;
+use ::std::hash::{ Hash, Hasher };
+use ::std::collections::HashSet;
 use ::nom_locate::LocatedSpan;
 use ::type_system::type_environment::TypeReference;
 
@@ -68,13 +71,18 @@ impl Span {
     }
 }
 
+/// spans don't take part in hashing, so reformatted source hashes like the original
+impl Hash for Span {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
 impl fmt::Debug for Span {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Offset {} Line {} Column {} Lenght {}", self.offset, self.line, self.column, self.length)
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct Identifier {
     pub span: Span,
     pub name: String,
@@ -100,15 +108,15 @@ impl Identifier {
 
 type TypeIdentifier = Identifier;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum ImportItem {
     Named(Identifier),
     All
 }
 
-type ExportItem = ImportItem;
+pub type ExportItem = ImportItem;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct ImportDefinition {
     pub span: Span,
     pub items: Vec<ImportItem>,
@@ -117,7 +125,7 @@ pub struct ImportDefinition {
 
 impl_spanned!(ImportDefinition);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct ExportDefinition {
     pub span: Span,
     pub items: Vec<ExportItem>,
@@ -125,24 +133,26 @@ pub struct ExportDefinition {
 
 impl_spanned!(ExportDefinition);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum ConstantVariant {
     Constant,
     Sampler,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct ConstantDefinition {
     pub span: Span,
     pub constant_name: Identifier,
     pub constant_variant: ConstantVariant,
     pub constant_type_name: TypeIdentifier,
     pub constant_type: Option<TypeReference>,
+    /// compile time value, constants without one are set by the application
+    pub expression: Option<ExpressionStatement>,
 }
 
 impl_spanned!(ConstantDefinition);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct ProgramDefinition {
     pub span: Span,
     pub program_name: Identifier,
@@ -151,39 +161,82 @@ pub struct ProgramDefinition {
 
 impl_spanned!(ProgramDefinition);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct ProgramStageDefinition {
     pub span: Span,
     pub stage_name: Identifier,
     pub function: FunctionDeclaration,
     pub declaring_type: Option<TypeReference>,
+    /// threads per workgroup of a compute stage, from `[workgroup_size(x, y, z)]`
+    pub workgroup_size: Option<[u32; 3]>,
+    /// fragment stage requested depth and stencil tests before it runs, from `[early_fragment_tests]`
+    pub early_fragment_tests: bool,
 }
 
 impl_spanned!(ProgramStageDefinition);
 
-#[derive(Debug, Eq, PartialEq)]
+/// stages a program can declare, e.g. `stage fragment(...)`
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum ShaderStage {
+    Vertex,
+    Fragment,
+    Compute,
+}
+
+impl ShaderStage {
+    pub fn get_name(&self) -> &'static str {
+        match *self {
+            ShaderStage::Vertex => "vertex",
+            ShaderStage::Fragment => "fragment",
+            ShaderStage::Compute => "compute",
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct StructDefinition {
     pub span: Span,
+    /// e.g. `[push_constant]`
+    pub attributes: Vec<AttributeDeclaration>,
     pub struct_name: Identifier,
+    /// e.g. `Base` in `struct Derived : Base`, its members come before the struct's own
+    pub base_struct_name: Option<Identifier>,
     pub struct_member: Vec<StructMemberDefinition>,
+    pub base_struct_type: Option<TypeReference>,
     pub declaring_type: Option<TypeReference>,
 }
 
 impl_spanned!(StructDefinition);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct EnumDefinition {
+    pub span: Span,
+    pub enum_name: Identifier,
+    pub variants: Vec<Identifier>,
+    pub declaring_type: Option<TypeReference>,
+}
+
+impl_spanned!(EnumDefinition);
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct StructMemberDefinition {
     pub span: Span,
+    pub attributes: Vec<AttributeDeclaration>,
     pub struct_member_name: Identifier,
     pub struct_member_type_name: TypeIdentifier,
+    /// e.g. `roughness: f32 = 0.5`, used by `Material::default()`
+    pub default_value: Option<ExpressionStatement>,
     pub struct_member_type: Option<TypeReference>,
 }
 
 impl_spanned!(StructMemberDefinition);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct FunctionArgumentDeclaration {
     pub span: Span,
+    pub attributes: Vec<AttributeDeclaration>,
+    /// `out` parameters are written by the function instead of read, e.g. `out normal: vec3`
+    pub is_out: bool,
     pub argument_name: Identifier,
     pub argument_type_name: TypeIdentifier,
     pub argument_type: Option<TypeReference>,
@@ -191,20 +244,57 @@ pub struct FunctionArgumentDeclaration {
 
 impl_spanned!(FunctionArgumentDeclaration);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct AttributeDeclaration {
+    pub span: Span,
+    pub attribute_name: Identifier,
+    /// e.g. `[location(0)]`, empty for attributes without parentheses
+    pub arguments: Vec<ExpressionStatement>,
+}
+
+impl_spanned!(AttributeDeclaration);
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum InlineHint {
+    /// `[inline]`
+    Inline,
+    /// `[noinline]`
+    NoInline,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct FunctionDeclaration {
     pub span: Span,
+    pub attributes: Vec<AttributeDeclaration>,
     pub function_name: Identifier,
     pub arguments: Vec<FunctionArgumentDeclaration>,
     pub block: BlockDeclaration,
     pub return_type_name: TypeIdentifier,
     pub return_type: Option<TypeReference>,
     pub declaring_type: Option<TypeReference>,
+    pub inline_hint: Option<InlineHint>,
+    /// `const fn`, calls can be evaluated at compile time, e.g. in loop bounds
+    pub is_const: bool,
+    /// target extensions from `[extension("GL_EXT_...")]` attributes, filled in by the attribute pass
+    pub required_extensions: Vec<String>,
+    /// folded name of `[debug_name("mesh_" + 2)]` for debug markers, filled in by the attribute pass
+    pub debug_name: Option<String>,
 }
 
 impl_spanned!(FunctionDeclaration);
 
-#[derive(Debug, Eq, PartialEq)]
+/// `fn fastnormalize = normalize;`, calls to the alias resolve to the target function
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct FunctionAliasDeclaration {
+    pub span: Span,
+    pub alias_name: Identifier,
+    pub target_name: Identifier,
+    pub alias_type: Option<TypeReference>,
+}
+
+impl_spanned!(FunctionAliasDeclaration);
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct StructFieldInitializerExpression {
     pub span: Span,
     pub struct_field_name: Identifier,
@@ -214,23 +304,31 @@ pub struct StructFieldInitializerExpression {
 
 impl_spanned!(StructFieldInitializerExpression);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct StructInstantiationExpression {
     pub span: Span,
     pub struct_type_name: TypeIdentifier,
     pub struct_field_initializer: Vec<StructFieldInitializerExpression>,
+    /// `Material::default()`, every field takes its declared default value
+    pub uses_defaults: bool,
+    /// `Material { roughness: 0.5, ..base }`, fields without initializer are taken from `base`
+    pub base: Option<Box<ExpressionStatement>>,
     pub struct_type: Option<TypeReference>,
 }
 
 impl_spanned!(StructInstantiationExpression);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum LiteralType {
     Int,
     Float,
+    /// `true` or `false`
+    Bool,
+    /// only valid as attribute argument, e.g. `[semantic("SV_Position")]`
+    String,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct LiteralExpression {
     pub span: Span,
     pub value: String,
@@ -240,7 +338,7 @@ pub struct LiteralExpression {
 
 impl_spanned!(LiteralExpression);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct InfixExpression {
     pub span: Span,
     pub operator: Operator,
@@ -251,7 +349,18 @@ pub struct InfixExpression {
 
 impl_spanned!(InfixExpression);
 
-#[derive(Debug, Eq, PartialEq)]
+/// e.g. `-a` or `!flag`
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct PrefixExpression {
+    pub span: Span,
+    pub operator: PrefixOperator,
+    pub expression: Box<ExpressionStatement>,
+    pub prefix_type: Option<TypeReference>,
+}
+
+impl_spanned!(PrefixExpression);
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct VariableExpression {
     pub span: Span,
     pub variable_name: Identifier,
@@ -260,7 +369,7 @@ pub struct VariableExpression {
 
 impl_spanned!(VariableExpression);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct FieldAccessorExpression {
     pub span: Span,
     pub variable_name: Identifier,
@@ -270,54 +379,249 @@ pub struct FieldAccessorExpression {
 
 impl_spanned!(FieldAccessorExpression);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct IndexAccesorExpression {
     pub span: Span,
     pub variable_name: Identifier,
     pub access_expression: Box<ExpressionStatement>,
+    pub element_type: Option<TypeReference>,
 }
 
 impl_spanned!(IndexAccesorExpression);
 
+/// `condition ? true_expression : false_expression`, only the chosen branch is evaluated
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct TernaryExpression {
+    pub span: Span,
+    pub condition: Box<ExpressionStatement>,
+    pub true_expression: Box<ExpressionStatement>,
+    pub false_expression: Box<ExpressionStatement>,
+    pub ternary_type: Option<TypeReference>,
+}
+
+impl_spanned!(TernaryExpression);
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct CastExpression {
+    pub span: Span,
+    pub cast_type: CastType,
+    pub expression: Box<ExpressionStatement>,
+    /// target of `x as f32`, `None` for casts inserted by the type checker
+    pub cast_target_type_name: Option<TypeIdentifier>,
+    pub cast_target_type: Option<TypeReference>,
+}
+
+impl_spanned!(CastExpression);
+
+/// e.g. `BlendMode::Add`
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct EnumVariantExpression {
+    pub span: Span,
+    pub enum_name: TypeIdentifier,
+    pub variant_name: Identifier,
+    pub variant_type: Option<TypeReference>,
+}
+
+impl_spanned!(EnumVariantExpression);
+
 // TODO rename to Expression, make new struct ExpressionStatement like other BlockStatements
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum ExpressionStatement {
     Infix(InfixExpression),
+    Prefix(PrefixExpression),
     Literal(LiteralExpression),
     Call(CallExpression),
     StructInstantiation(StructInstantiationExpression),
     FieldAccessor(FieldAccessorExpression),
     IndexAccessor(IndexAccesorExpression),
     Variable(VariableExpression),
+    Cast(CastExpression),
+    EnumVariant(EnumVariantExpression),
+    Ternary(TernaryExpression),
 }
 
 impl Spanned for ExpressionStatement {
     fn get_span(&self) -> Span {
         match *self {
             ExpressionStatement::Infix(ref expression) => expression.span,
+            ExpressionStatement::Prefix(ref expression) => expression.span,
             ExpressionStatement::Literal(ref expression) => expression.span,
             ExpressionStatement::Call(ref expression) => expression.span,
             ExpressionStatement::StructInstantiation(ref expression) => expression.span,
             ExpressionStatement::FieldAccessor(ref expression) => expression.span,
             ExpressionStatement::IndexAccessor(ref expression) => expression.span,
             ExpressionStatement::Variable(ref expression) => expression.span,
+            ExpressionStatement::Cast(ref expression) => expression.span,
+            ExpressionStatement::EnumVariant(ref expression) => expression.span,
+            ExpressionStatement::Ternary(ref expression) => expression.span,
         }
     }
 }
 
+impl ExpressionStatement {
+    pub fn get_type(&self) -> Option<TypeReference> {
+        match *self {
+            ExpressionStatement::Infix(ref expression) => expression.infix_type,
+            ExpressionStatement::Prefix(ref expression) => expression.prefix_type,
+            ExpressionStatement::Literal(ref expression) => expression.literal_type,
+            ExpressionStatement::Call(ref expression) => expression.function_type,
+            ExpressionStatement::StructInstantiation(ref expression) => expression.struct_type,
+            ExpressionStatement::FieldAccessor(ref expression) => expression.field_type,
+            ExpressionStatement::IndexAccessor(ref expression) => expression.element_type,
+            ExpressionStatement::Variable(ref expression) => expression.variable_type,
+            ExpressionStatement::Cast(ref expression) => expression.cast_target_type,
+            ExpressionStatement::EnumVariant(ref expression) => expression.variant_type,
+            ExpressionStatement::Ternary(ref expression) => expression.ternary_type,
+        }
+    }
+
+    /// whether the expression can be evaluated at compile time
+    /// only literals and expressions built from them qualify, variables and calls are never constant
+    pub fn is_const(&self) -> bool {
+        match *self {
+            ExpressionStatement::Infix(ref expression) => expression.left_hand.is_const() && expression.right_hand.is_const(),
+            ExpressionStatement::Prefix(ref expression) => expression.expression.is_const(),
+            ExpressionStatement::Literal(_) => true,
+            ExpressionStatement::Call(_) => false,
+            ExpressionStatement::StructInstantiation(ref expression) => {
+                expression.struct_field_initializer.iter().all(|f| f.initializer.is_const()) &&
+                expression.base.iter().all(|b| b.is_const())
+            },
+            ExpressionStatement::FieldAccessor(_) => false,
+            ExpressionStatement::IndexAccessor(_) => false,
+            ExpressionStatement::Variable(_) => false,
+            ExpressionStatement::Cast(ref expression) => expression.expression.is_const(),
+            ExpressionStatement::EnumVariant(_) => true,
+            ExpressionStatement::Ternary(ref expression) => {
+                expression.condition.is_const() && expression.true_expression.is_const() && expression.false_expression.is_const()
+            },
+        }
+    }
+
+    /// wraps this expression into an implicit cast to `target_type`
+    pub fn insert_implicit_cast(&mut self, target_type: TypeReference) {
+        let span = self.get_span();
+        let placeholder = ExpressionStatement::Literal(LiteralExpression {
+            span: span,
+            value: String::new(),
+            literal_expression_type: LiteralType::Int,
+            literal_type: None,
+        });
+
+        let expression = mem::replace(self, placeholder);
+        *self = ExpressionStatement::Cast(CastExpression {
+            span: span,
+            cast_type: CastType::Implicit,
+            expression: Box::new(expression),
+            cast_target_type_name: None,
+            cast_target_type: Some(target_type),
+        });
+    }
+
+    /// turns a call of a type with a single argument, e.g. `f32(x)`, into an explicit cast to `target_type`
+    pub fn convert_call_to_explicit_cast(&mut self, target_type: TypeReference) {
+        let span = self.get_span();
+        let (argument, target_type_name) = match *self {
+            ExpressionStatement::Call(ref mut call) if call.arguments.len() == 1 => (call.arguments.remove(0), call.function_name.clone()),
+            _ => return,
+        };
+
+        *self = ExpressionStatement::Cast(CastExpression {
+            span: span,
+            cast_type: CastType::Explicit,
+            expression: Box::new(argument),
+            cast_target_type_name: Some(target_type_name),
+            cast_target_type: Some(target_type),
+        });
+    }
+}
+
+/// Collects the names of all variables referenced in an expression
+/// expressions cannot bind variables, so every referenced variable is free
+pub fn free_variables(expression: &ExpressionStatement) -> HashSet<String> {
+    let mut variables = HashSet::new();
+    collect_free_variables(expression, &mut variables);
+    variables
+}
+
+fn collect_free_variables(expression: &ExpressionStatement, variables: &mut HashSet<String>) {
+    match *expression {
+        ExpressionStatement::Infix(ref e) => {
+            collect_free_variables(&e.left_hand, variables);
+            collect_free_variables(&e.right_hand, variables);
+        },
+        ExpressionStatement::Prefix(ref e) => collect_free_variables(&e.expression, variables),
+        ExpressionStatement::Literal(_) => (),
+        ExpressionStatement::Call(ref e) => {
+            for argument in &e.arguments {
+                collect_free_variables(argument, variables);
+            }
+        },
+        ExpressionStatement::StructInstantiation(ref e) => {
+            for field in &e.struct_field_initializer {
+                collect_free_variables(&field.initializer, variables);
+            }
+            if let Some(ref base) = e.base {
+                collect_free_variables(base, variables);
+            }
+        },
+        ExpressionStatement::FieldAccessor(ref e) => {
+            variables.insert(e.variable_name.name.clone());
+        },
+        ExpressionStatement::IndexAccessor(ref e) => {
+            variables.insert(e.variable_name.name.clone());
+            collect_free_variables(&e.access_expression, variables);
+        },
+        ExpressionStatement::Variable(ref e) => {
+            variables.insert(e.variable_name.name.clone());
+        },
+        ExpressionStatement::Cast(ref e) => collect_free_variables(&e.expression, variables),
+        ExpressionStatement::EnumVariant(_) => (),
+        ExpressionStatement::Ternary(ref e) => {
+            collect_free_variables(&e.condition, variables);
+            collect_free_variables(&e.true_expression, variables);
+            collect_free_variables(&e.false_expression, variables);
+        },
+    }
+}
+
 // TODO rename to LocalStatement
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct LocalDeclaration {
     pub span: Span,
     pub symbol_name: Identifier,
-    pub expression: ExpressionStatement,
+    /// optional type annotation, e.g. `let a: f32 = 1;`
+    pub local_type_name: Option<TypeIdentifier>,
+    /// `None` for locals declared without initializer, e.g. `let a;`
+    pub expression: Option<ExpressionStatement>,
     pub local_type: Option<TypeReference>,
 }
 
 impl_spanned!(LocalDeclaration);
 
+/// block scoped constant e.g. `const THRESHOLD: f32 = 0.5;`
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct LocalConstantDeclaration {
+    pub span: Span,
+    pub constant_name: Identifier,
+    pub constant_type_name: TypeIdentifier,
+    pub expression: ExpressionStatement,
+    pub constant_type: Option<TypeReference>,
+}
+
+impl_spanned!(LocalConstantDeclaration);
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct AssignmentStatement {
+    pub span: Span,
+    pub symbol_name: Identifier,
+    pub expression: ExpressionStatement,
+}
+
+impl_spanned!(AssignmentStatement);
+
 // TODO rename to ReturnStatement
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct ReturnDeclaration {
     pub span: Span,
     pub expression: ExpressionStatement,
@@ -326,24 +630,148 @@ pub struct ReturnDeclaration {
 
 impl_spanned!(ReturnDeclaration);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct WhileStatement {
+    pub span: Span,
+    pub condition: ExpressionStatement,
+    pub block: BlockDeclaration,
+}
+
+impl_spanned!(WhileStatement);
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct IfStatement {
+    pub span: Span,
+    pub condition: ExpressionStatement,
+    pub block: BlockDeclaration,
+    /// `None` for an `if` without `else`
+    pub else_block: Option<BlockDeclaration>,
+}
+
+impl_spanned!(IfStatement);
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct ForStatement {
+    pub span: Span,
+    /// `[dynamic]` allows bounds that are only known at runtime
+    pub attributes: Vec<AttributeDeclaration>,
+    pub loop_variable: Identifier,
+    /// inclusive lower bound, e.g. `0` in `for i in 0..4`
+    pub from: ExpressionStatement,
+    /// exclusive upper bound, e.g. `4` in `for i in 0..4`
+    pub to: ExpressionStatement,
+    pub block: BlockDeclaration,
+}
+
+impl ForStatement {
+    pub fn is_dynamic(&self) -> bool {
+        self.attributes.iter().any(|a| a.attribute_name.name == "dynamic")
+    }
+}
+
+impl_spanned!(ForStatement);
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum LoopControl {
+    Break,
+    Continue,
+}
+
+impl LoopControl {
+    pub fn get_keyword(&self) -> &'static str {
+        match *self {
+            LoopControl::Break => "break",
+            LoopControl::Continue => "continue",
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct LoopControlStatement {
+    pub span: Span,
+    pub loop_control: LoopControl,
+}
+
+impl_spanned!(LoopControlStatement);
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum MatchPattern {
+    /// integer literal
+    Literal(LiteralExpression),
+    EnumVariant(EnumVariantExpression),
+    /// `_`
+    Wildcard(Span),
+}
+
+impl Spanned for MatchPattern {
+    fn get_span(&self) -> Span {
+        match *self {
+            MatchPattern::Literal(ref pattern) => pattern.span,
+            MatchPattern::EnumVariant(ref pattern) => pattern.span,
+            MatchPattern::Wildcard(span) => span,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct MatchArm {
+    pub span: Span,
+    pub pattern: MatchPattern,
+    pub block: BlockDeclaration,
+}
+
+impl_spanned!(MatchArm);
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct MatchStatement {
+    pub span: Span,
+    pub scrutinee: ExpressionStatement,
+    pub arms: Vec<MatchArm>,
+}
+
+impl_spanned!(MatchStatement);
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct CallExpression {
     pub span: Span,
     pub function_name: Identifier,
+    /// explicit type arguments of builtins e.g. `bitcast<u32>(x)`
+    pub type_arguments: Vec<TypeIdentifier>,
     pub arguments: Vec<ExpressionStatement>,
     pub function_type: Option<TypeReference>,
 }
 
 impl_spanned!(CallExpression);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum BlockStatement {
     /// e.g. a `let` statement
     Local(LocalDeclaration),
 
+    /// block scoped constant e.g. `const a: f32 = 0.5;`
+    Const(LocalConstantDeclaration),
+
+    /// assignment to a local e.g. `a = 1;`
+    Assignment(AssignmentStatement),
+
     /// return statement
     Return(ReturnDeclaration),
 
+    /// branch e.g. `if a == b { ... } else { ... }`
+    If(IfStatement),
+
+    /// loop e.g. `while a == b { ... }`
+    While(WhileStatement),
+
+    /// loop over a range e.g. `for i in 0..4 { ... }`
+    For(ForStatement),
+
+    /// `break;` or `continue;` inside a loop
+    LoopControl(LoopControlStatement),
+
+    /// e.g. `match mode { BlendMode::Add => { ... } _ => { ... } }`
+    Match(MatchStatement),
+
     /// statement with only expressions e.g. `my_fn();`
     Expression(ExpressionStatement),
 }
@@ -352,13 +780,20 @@ impl Spanned for BlockStatement {
     fn get_span(&self) -> Span {
         match *self {
             BlockStatement::Local(ref statement) => statement.span,
+            BlockStatement::Const(ref statement) => statement.span,
+            BlockStatement::Assignment(ref statement) => statement.span,
             BlockStatement::Return(ref statement) => statement.span,
+            BlockStatement::If(ref statement) => statement.span,
+            BlockStatement::While(ref statement) => statement.span,
+            BlockStatement::For(ref statement) => statement.span,
+            BlockStatement::LoopControl(ref statement) => statement.span,
+            BlockStatement::Match(ref statement) => statement.span,
             BlockStatement::Expression(ref statement) => statement.get_span(),
         }
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct BlockDeclaration {
     pub span: Span,
     pub statements: Vec<BlockStatement>,
@@ -366,7 +801,7 @@ pub struct BlockDeclaration {
 
 impl_spanned!(BlockDeclaration);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct PrimitiveDeclaration {
     pub span: Span,
     pub type_name: Identifier,
@@ -375,33 +810,115 @@ pub struct PrimitiveDeclaration {
 
 impl_spanned!(PrimitiveDeclaration);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
 pub enum Operator {
     Plus,
     Minus,
     Multiply,
     Divide,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
 }
 
-// TODO type check
-#[derive(Debug, Eq, PartialEq)]
+impl Operator {
+    /// binding strength, higher binds tighter
+    pub fn get_precedence(&self) -> usize {
+        match *self {
+            Operator::Equal | Operator::NotEqual | Operator::Less | Operator::LessEqual | Operator::Greater | Operator::GreaterEqual => 0,
+            Operator::Plus | Operator::Minus => 1,
+            Operator::Multiply | Operator::Divide => 2,
+        }
+    }
+
+    pub fn get_symbol(&self) -> &'static str {
+        match *self {
+            Operator::Plus => "+",
+            Operator::Minus => "-",
+            Operator::Multiply => "*",
+            Operator::Divide => "/",
+            Operator::Equal => "==",
+            Operator::NotEqual => "!=",
+            Operator::Less => "<",
+            Operator::LessEqual => "<=",
+            Operator::Greater => ">",
+            Operator::GreaterEqual => ">=",
+        }
+    }
+
+    pub fn is_comparison(&self) -> bool {
+        match *self {
+            Operator::Equal | Operator::NotEqual | Operator::Less | Operator::LessEqual | Operator::Greater | Operator::GreaterEqual => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_equality(&self) -> bool {
+        match *self {
+            Operator::Equal | Operator::NotEqual => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum PrefixOperator {
+    /// arithmetic negation `-`
+    Negate,
+    /// logical not `!`
+    Not,
+}
+
+impl PrefixOperator {
+    pub fn get_symbol(&self) -> &'static str {
+        match *self {
+            PrefixOperator::Negate => "-",
+            PrefixOperator::Not => "!",
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct OperatorDeclaration {
     pub span: Span,
     pub operator: Operator,
     pub arguments: Vec<FunctionArgumentDeclaration>,
     pub return_type: TypeIdentifier, 
+    /// `None` for operators provided by the core module, e.g. `operator + (lhs: f32, rhs: f32) -> f32;`
+    pub block: Option<BlockDeclaration>,
+    pub operator_type: Option<TypeReference>,
 }
 
 impl_spanned!(OperatorDeclaration);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+/// `operator + precedence 1 left;`, only read from core modules
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct OperatorPrecedenceDeclaration {
+    pub span: Span,
+    pub operator: Operator,
+    pub precedence: usize,
+    pub associativity: Associativity,
+}
+
+impl_spanned!(OperatorPrecedenceDeclaration);
+
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum CastType {
     Implicit,
     Explicit
 }
 
 // TODO type check
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct CastDeclaration {
     pub span: Span,
     pub cast_type: CastType,
@@ -411,17 +928,20 @@ pub struct CastDeclaration {
 
 impl_spanned!(CastDeclaration);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum ItemKind {
     Import(ImportDefinition),
     Export(ExportDefinition),
     Struct(StructDefinition),
+    Enum(EnumDefinition),
     Program(ProgramDefinition),
     Constant(ConstantDefinition),
     Function(FunctionDeclaration),
+    FunctionAlias(FunctionAliasDeclaration),
     Block(BlockDeclaration),
     Primitive(PrimitiveDeclaration),
     Operator(OperatorDeclaration),
+    OperatorPrecedence(OperatorPrecedenceDeclaration),
     Cast(CastDeclaration),
 }
 
@@ -431,13 +951,57 @@ impl Spanned for ItemKind {
             ItemKind::Import(ref item) => item.span,
             ItemKind::Export(ref item) => item.span,
             ItemKind::Struct(ref item) => item.span,
+            ItemKind::Enum(ref item) => item.span,
             ItemKind::Program(ref item) => item.span,
             ItemKind::Constant(ref item) => item.span,
             ItemKind::Function(ref item) => item.span,
+            ItemKind::FunctionAlias(ref item) => item.span,
             ItemKind::Block(ref item) => item.span,
             ItemKind::Primitive(ref item) => item.span,
             ItemKind::Operator(ref item) => item.span,
+            ItemKind::OperatorPrecedence(ref item) => item.span,
             ItemKind::Cast(ref item) => item.span,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+
+    fn return_expression(ast: &Ast) -> &ExpressionStatement {
+        match ast[0] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => &r.expression,
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn literal_expression_is_const() {
+        let ast = compile_ast("fn main() -> f32 { return (1 + 2) * 3.0; }");
+
+        assert!(return_expression(&ast).is_const());
+    }
+
+    #[test]
+    fn expression_with_variable_is_not_const() {
+        let ast = compile_ast("fn main() -> f32 { return 1 + a; }");
+
+        assert!(!return_expression(&ast).is_const());
+    }
+
+    #[test]
+    fn free_variables_of_an_expression() {
+        let ast = compile_ast("fn main() -> f32 { return f(a.x, 2) * b + 1; }");
+
+        let variables = free_variables(return_expression(&ast));
+
+        assert_eq!(variables.len(), 2);
+        assert!(variables.contains("a"));
+        assert!(variables.contains("b"));
+    }
+}
@@ -0,0 +1,547 @@
+use ::std::collections::HashMap;
+use ::ast::*;
+use ::module::Module;
+use ::string_builder::StringBuilder;
+use ::type_system::symbol_table::SymbolTable;
+use ::type_system::type_environment::TypeReference;
+use ::codegen::{ CodegenError, CodegenResult, declaration_name, function_aliases, resolve_alias };
+use ::renaming::rename_reserved;
+
+/// primitives named differently in GLSL, all other types keep their name
+const TYPE_NAMES: &[(&str, &str)] = &[
+    ("f16", "float16_t"),
+    ("f32", "float"),
+    ("f64", "double"),
+    ("i32", "int"),
+    ("i64", "int64_t"),
+    ("u32", "uint"),
+    ("u64", "uint64_t"),
+];
+
+/// GLSL integer literals without suffix are `int`
+const INTEGER_SUFFIXES: &[(&str, &str)] = &[("u32", "u"), ("i64", "l"), ("u64", "ul")];
+
+/// source type, target type and the GLSL function reinterpreting the bits of `bitcast<target>(source)`
+const BITCASTS: &[(&str, &str, &str)] = &[
+    ("f32", "i32", "floatBitsToInt"),
+    ("f32", "u32", "floatBitsToUint"),
+    ("i32", "f32", "intBitsToFloat"),
+    ("u32", "f32", "uintBitsToFloat"),
+    ("i32", "u32", "uint"),
+    ("u32", "i32", "int"),
+];
+
+/// keywords and words reserved for future use, declarations named like one are renamed, see `glsl_renames`
+const KEYWORDS: &[&str] = &[
+    "attribute", "const", "uniform", "varying", "buffer", "shared", "coherent", "volatile", "restrict", "readonly", "writeonly",
+    "layout", "centroid", "flat", "smooth", "noperspective", "patch", "sample", "subroutine", "in", "out", "inout", "invariant", "precise",
+    "break", "continue", "do", "for", "while", "switch", "case", "default", "if", "else", "discard", "return", "struct", "true", "false",
+    "lowp", "mediump", "highp", "precision", "void", "bool", "int", "uint", "float", "double",
+    "bvec2", "bvec3", "bvec4", "ivec2", "ivec3", "ivec4", "uvec2", "uvec3", "uvec4", "vec2", "vec3", "vec4", "dvec2", "dvec3", "dvec4",
+    "mat2", "mat3", "mat4", "dmat2", "dmat3", "dmat4", "sampler1D", "sampler2D", "sampler3D", "samplerCube", "image1D", "image2D", "image3D",
+    "common", "partition", "active", "asm", "class", "union", "enum", "typedef", "template", "this", "resource", "goto", "inline", "noinline",
+    "public", "static", "extern", "external", "interface", "long", "short", "half", "fixed", "unsigned", "superp", "input", "output",
+    "hvec2", "hvec3", "hvec4", "fvec2", "fvec3", "fvec4", "sampler3DRect", "filter", "sizeof", "cast", "namespace", "using",
+];
+
+/// declarations of the module named like a GLSL keyword and the name `generate_glsl` emits them as, e.g. `input` as `input_`
+/// applications use it to look up renamed constants, which are uniforms set by name
+pub fn glsl_renames(module: &Module) -> HashMap<String, String> {
+    rename_reserved(module, KEYWORDS)
+}
+
+/// Generates GLSL for the structs, enums, constants and functions of a type checked module
+/// declarations are emitted in dependency order, since GLSL requires declaring before use
+/// program stages need a translation unit with a `main` each and are not emitted
+pub fn generate_glsl(module: &Module) -> CodegenResult<String> {
+    let symbol_table = match module.get_symbol_table() {
+        Some(symbol_table) if module.is_checked() => symbol_table.borrow(),
+        _ => return Err(CodegenError::NotChecked),
+    };
+
+    let order = match module.topological_order() {
+        Ok(order) => order,
+        Err(error) => return Err(CodegenError::Unsupported(error.get_span(), "Cyclic declaration".to_owned())),
+    };
+
+    let mut generator = GlslGenerator {
+        module: module,
+        symbol_table: &symbol_table,
+        aliases: function_aliases(module),
+        renames: glsl_renames(module),
+        sb: StringBuilder::new(1024),
+        indent: 0,
+        in_switch: false,
+    };
+
+    for name in order.iter() {
+        if let Some(item) = module.get_ast().iter().find(|item| declaration_name(item) == Some(name)) {
+            generator.generate_item(item)?;
+        }
+    }
+
+    Ok(generator.sb.to_string().unwrap_or_default())
+}
+
+struct GlslGenerator<'a> {
+    module: &'a Module,
+    symbol_table: &'a SymbolTable,
+    /// function aliases and the function they name
+    aliases: HashMap<&'a str, &'a str>,
+    /// declarations named like a keyword and their new name
+    renames: HashMap<String, String>,
+    sb: StringBuilder,
+    indent: usize,
+    /// whether the current statement is inside a `switch` but not inside a loop nested in it
+    in_switch: bool,
+}
+
+impl<'a> GlslGenerator<'a> {
+    fn line(&mut self, line: &str) {
+        for _ in 0..self.indent {
+            self.sb.append("    ");
+        }
+        self.sb.append(line);
+        self.sb.append("\n");
+    }
+
+    /// the name a declaration is emitted as
+    fn name<'b>(&'b self, name: &'b str) -> &'b str {
+        self.renames.get(name).map_or(name, |renamed| renamed.as_str())
+    }
+
+    fn generate_item(&mut self, item: &ItemKind) -> CodegenResult<()> {
+        match *item {
+            ItemKind::Struct(ref s) => {
+                self.line(&format!("struct {} {{", self.name(&s.struct_name.name)));
+                self.indent += 1;
+                for member in self.struct_members(s)? {
+                    let member_type = self.type_name(member.struct_member_type, member.span)?;
+                    self.line(&format!("{} {};", member_type, self.name(&member.struct_member_name.name)));
+                }
+                self.indent -= 1;
+                self.line("};");
+            },
+            // GLSL has no enums, variants become integer constants
+            ItemKind::Enum(ref e) => {
+                for (value, variant) in e.variants.iter().enumerate() {
+                    self.line(&format!("const int {}_{} = {};", e.enum_name.name, variant.name, value));
+                }
+            },
+            // module constants without initializer are set by the application
+            ItemKind::Constant(ref c) => {
+                let constant_type = self.type_name(c.constant_type, c.span)?;
+                match c.expression {
+                    Some(ref expression) => self.line(&format!("const {} {} = {};", constant_type, self.name(&c.constant_name.name), self.expression(expression)?)),
+                    None => self.line(&format!("uniform {} {};", constant_type, self.name(&c.constant_name.name))),
+                }
+            },
+            ItemKind::Function(ref f) => {
+                let return_type = if f.return_type_name.name == "void" {
+                    "void".to_owned()
+                } else {
+                    self.type_name(f.return_type, f.return_type_name.span)?
+                };
+
+                let mut arguments = Vec::new();
+                for argument in f.arguments.iter() {
+                    let argument_type = self.type_name(argument.argument_type, argument.span)?;
+                    let qualifier = if argument.is_out { "out " } else { "" };
+                    arguments.push(format!("{}{} {}", qualifier, argument_type, self.name(&argument.argument_name.name)));
+                }
+
+                let signature = format!("{} {}({})", return_type, self.name(&f.function_name.name), arguments.join(", "));
+                self.generate_block(&signature, &f.block)?;
+            },
+            _ => return Ok(()),
+        }
+
+        self.sb.append("\n");
+        Ok(())
+    }
+
+    fn generate_block(&mut self, header: &str, block: &BlockDeclaration) -> CodegenResult<()> {
+        self.line(&format!("{} {{", header));
+        self.indent += 1;
+        for statement in block.statements.iter() {
+            self.generate_statement(statement)?;
+        }
+        self.indent -= 1;
+        self.line("}");
+        Ok(())
+    }
+
+    fn generate_loop_block(&mut self, header: &str, block: &BlockDeclaration) -> CodegenResult<()> {
+        let in_switch = self.in_switch;
+        self.in_switch = false;
+        let result = self.generate_block(header, block);
+        self.in_switch = in_switch;
+        result
+    }
+
+    fn generate_statement(&mut self, statement: &BlockStatement) -> CodegenResult<()> {
+        match *statement {
+            BlockStatement::Local(ref s) => {
+                let local_type = self.type_name(s.local_type, s.span)?;
+                match s.expression {
+                    Some(ref expression) => {
+                        let expression = self.expression(expression)?;
+                        self.line(&format!("{} {} = {};", local_type, self.name(&s.symbol_name.name), expression));
+                    },
+                    None => self.line(&format!("{} {};", local_type, self.name(&s.symbol_name.name))),
+                }
+            },
+            BlockStatement::Const(ref s) => {
+                let constant_type = self.type_name(s.constant_type, s.span)?;
+                let expression = self.expression(&s.expression)?;
+                self.line(&format!("const {} {} = {};", constant_type, self.name(&s.constant_name.name), expression));
+            },
+            BlockStatement::Assignment(ref s) => {
+                let expression = self.expression(&s.expression)?;
+                self.line(&format!("{} = {};", self.name(&s.symbol_name.name), expression));
+            },
+            BlockStatement::Return(ref s) => {
+                let expression = self.expression(&s.expression)?;
+                self.line(&format!("return {};", expression));
+            },
+            BlockStatement::If(ref s) => {
+                let condition = self.expression(&s.condition)?;
+                self.generate_block(&format!("if ({})", condition), &s.block)?;
+                if let Some(ref else_block) = s.else_block {
+                    self.generate_block("else", else_block)?;
+                }
+            },
+            BlockStatement::While(ref s) => {
+                let condition = self.expression(&s.condition)?;
+                self.generate_loop_block(&format!("while ({})", condition), &s.block)?;
+            },
+            BlockStatement::For(ref s) => {
+                let loop_type = self.type_name(s.from.get_type(), s.span)?;
+                let from = self.expression(&s.from)?;
+                let to = self.expression(&s.to)?;
+                let name = self.name(&s.loop_variable.name);
+                self.generate_loop_block(&format!("for ({} {} = {}; {} < {}; {}++)", loop_type, name, from, name, to, name), &s.block)?;
+            },
+            BlockStatement::LoopControl(ref s) => {
+                // `break` inside a `switch` leaves the switch instead of the enclosing loop
+                if s.loop_control == LoopControl::Break && self.in_switch {
+                    return Err(CodegenError::Unsupported(s.span, "Break out of a loop from a match arm".to_owned()));
+                }
+                self.line(&format!("{};", s.loop_control.get_keyword()));
+            },
+            BlockStatement::Match(ref s) => {
+                let scrutinee = self.expression(&s.scrutinee)?;
+                self.line(&format!("switch ({}) {{", scrutinee));
+                self.indent += 1;
+
+                let in_switch = self.in_switch;
+                self.in_switch = true;
+                for arm in s.arms.iter() {
+                    let label = match arm.pattern {
+                        MatchPattern::Literal(ref literal) => format!("case {}:", self.literal(literal)?),
+                        MatchPattern::EnumVariant(ref variant) => format!("case {}_{}:", variant.enum_name.name, variant.variant_name.name),
+                        MatchPattern::Wildcard(_) => "default:".to_owned(),
+                    };
+
+                    self.line(&format!("{} {{", label));
+                    self.indent += 1;
+                    for statement in arm.block.statements.iter() {
+                        self.generate_statement(statement)?;
+                    }
+                    self.line("break;");
+                    self.indent -= 1;
+                    self.line("}");
+                }
+                self.in_switch = in_switch;
+
+                self.indent -= 1;
+                self.line("}");
+            },
+            BlockStatement::Expression(ref s) => {
+                let expression = self.expression(s)?;
+                self.line(&format!("{};", expression));
+            },
+        }
+
+        Ok(())
+    }
+
+    fn expression(&self, expression: &ExpressionStatement) -> CodegenResult<String> {
+        match *expression {
+            ExpressionStatement::Infix(ref e) => {
+                // GLSL has no operator overloading
+                if self.is_struct(e.left_hand.get_type()) || self.is_struct(e.right_hand.get_type()) {
+                    return Err(CodegenError::Unsupported(e.span, format!("Operator \"{}\" on structs", e.operator.get_symbol())));
+                }
+                Ok(format!("{} {} {}", self.operand(&e.left_hand)?, e.operator.get_symbol(), self.operand(&e.right_hand)?))
+            },
+            ExpressionStatement::Prefix(ref e) => Ok(format!("{}{}", e.operator.get_symbol(), self.operand(&e.expression)?)),
+            ExpressionStatement::Literal(ref e) => self.literal(e),
+            ExpressionStatement::Call(ref e) => self.call(e),
+            ExpressionStatement::StructInstantiation(ref e) => self.struct_instantiation(e),
+            ExpressionStatement::FieldAccessor(ref e) => Ok(format!("{}.{}", self.name(&e.variable_name.name), self.name(&e.field_name.name))),
+            ExpressionStatement::IndexAccessor(ref e) => Ok(format!("{}[{}]", self.name(&e.variable_name.name), self.expression(&e.access_expression)?)),
+            ExpressionStatement::Variable(ref e) => Ok(self.name(&e.variable_name.name).to_owned()),
+            // GLSL converts with constructors, e.g. `float(x)`
+            ExpressionStatement::Cast(ref e) => Ok(format!("{}({})", self.type_name(e.cast_target_type, e.span)?, self.expression(&e.expression)?)),
+            ExpressionStatement::EnumVariant(ref e) => Ok(format!("{}_{}", e.enum_name.name, e.variant_name.name)),
+            ExpressionStatement::Ternary(ref e) => Ok(format!("{} ? {} : {}", self.operand(&e.condition)?, self.operand(&e.true_expression)?, self.operand(&e.false_expression)?)),
+        }
+    }
+
+    /// operator operands are parenthesized, so the grouping of the tree survives without comparing precedences
+    fn operand(&self, operand: &ExpressionStatement) -> CodegenResult<String> {
+        match *operand {
+            ExpressionStatement::Infix(_) | ExpressionStatement::Prefix(_) | ExpressionStatement::Ternary(_) => Ok(format!("({})", self.expression(operand)?)),
+            _ => self.expression(operand),
+        }
+    }
+
+    fn literal(&self, literal: &LiteralExpression) -> CodegenResult<String> {
+        match literal.literal_expression_type {
+            LiteralType::Int => {
+                let type_name = self.primitive_name(literal.literal_type);
+                let suffix = INTEGER_SUFFIXES.iter().find(|&&(name, _)| Some(name) == type_name).map_or("", |&(_, suffix)| suffix);
+                Ok(format!("{}{}", literal.value, suffix))
+            },
+            LiteralType::Float | LiteralType::Bool => Ok(literal.value.to_owned()),
+            LiteralType::String => Err(CodegenError::Unsupported(literal.span, "String literal".to_owned())),
+        }
+    }
+
+    fn call(&self, call: &CallExpression) -> CodegenResult<String> {
+        let mut arguments = Vec::new();
+        for argument in call.arguments.iter() {
+            arguments.push(self.expression(argument)?);
+        }
+
+        let function_name = match call.function_name.name.as_str() {
+            "bitcast" => self.bitcast_function(call)?,
+            "debugPrintf" => return Err(CodegenError::Unsupported(call.span, "debugPrintf".to_owned())),
+            name => {
+                // builtins like `vec4` are keywords themselves and keep their name
+                let function_name = resolve_alias(&self.aliases, name);
+                if self.module.find_functions().iter().any(|f| f.function_name.name == function_name) {
+                    self.name(function_name).to_owned()
+                } else {
+                    function_name.to_owned()
+                }
+            },
+        };
+
+        Ok(format!("{}({})", function_name, arguments.join(", ")))
+    }
+
+    fn bitcast_function(&self, call: &CallExpression) -> CodegenResult<String> {
+        let source = self.primitive_name(call.arguments.first().and_then(|a| a.get_type()));
+        let target = self.primitive_name(call.function_type);
+
+        match BITCASTS.iter().find(|&&(s, t, _)| Some(s) == source && Some(t) == target) {
+            Some(&(_, _, function)) => Ok(function.to_owned()),
+            None => Err(CodegenError::Unsupported(call.span, format!("bitcast to \"{}\"", target.unwrap_or_default()))),
+        }
+    }
+
+    /// GLSL structs are constructed from all fields in declaration order
+    fn struct_instantiation(&self, instantiation: &StructInstantiationExpression) -> CodegenResult<String> {
+        let struct_name = &instantiation.struct_type_name.name;
+        let definition = match self.module.find_structs().into_iter().find(|s| s.struct_name.name == *struct_name) {
+            Some(definition) => definition,
+            None => return Err(CodegenError::Unsupported(instantiation.span, format!("Struct \"{}\" of another module", struct_name))),
+        };
+
+        let mut fields = Vec::new();
+        for member in self.struct_members(definition)? {
+            let member_name = &member.struct_member_name.name;
+            let value = match instantiation.struct_field_initializer.iter().find(|f| f.struct_field_name.name == *member_name) {
+                Some(field) => self.expression(&field.initializer)?,
+                None => match (instantiation.base.as_ref(), member.default_value.as_ref()) {
+                    (Some(base), _) => format!("{}.{}", self.operand(base)?, self.name(member_name)),
+                    (None, Some(default_value)) => self.expression(default_value)?,
+                    (None, None) => return Err(CodegenError::Unsupported(instantiation.span, format!("Missing field \"{}\"", member_name))),
+                },
+            };
+            fields.push(value);
+        }
+
+        Ok(format!("{}({})", self.name(struct_name), fields.join(", ")))
+    }
+
+    /// GLSL structs can't inherit, the members of bases are declared in the struct itself
+    fn struct_members(&self, struct_definition: &'a StructDefinition) -> CodegenResult<Vec<&'a StructMemberDefinition>> {
+        let mut chain = vec![struct_definition];
+        while let Some(ref base_name) = chain[chain.len() - 1].base_struct_name {
+            match self.module.find_structs().into_iter().find(|s| s.struct_name.name == base_name.name) {
+                Some(base) if chain.len() <= self.module.find_structs().len() => chain.push(base),
+                _ => return Err(CodegenError::Unsupported(base_name.span, format!("Base struct \"{}\" of another module", base_name.name))),
+            }
+        }
+
+        Ok(chain.iter().rev().flat_map(|s| s.struct_member.iter()).collect())
+    }
+
+    fn primitive_name(&self, type_reference: Option<TypeReference>) -> Option<&str> {
+        type_reference.and_then(|t| self.symbol_table.find_type(t)).map(|t| t.get_name())
+    }
+
+    fn is_struct(&self, type_reference: Option<TypeReference>) -> bool {
+        type_reference.and_then(|t| self.symbol_table.find_type(t)).is_some_and(|t| t.is_struct())
+    }
+
+    fn type_name(&self, type_reference: Option<TypeReference>, span: Span) -> CodegenResult<String> {
+        let definition = match type_reference.and_then(|t| self.symbol_table.find_type(t)) {
+            Some(definition) => definition,
+            None => return Err(CodegenError::UnresolvedType(span)),
+        };
+
+        // enum variants are emitted as integer constants
+        if definition.is_enum() {
+            return Ok("int".to_owned());
+        }
+
+        let name = definition.get_name();
+        if definition.is_struct() {
+            return Ok(self.name(name).to_owned());
+        }
+
+        Ok(TYPE_NAMES.iter().find(|&&(xshade, _)| xshade == name).map_or(name, |&(_, glsl)| glsl).to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::compiler::parse_core_module;
+    use ::passes::results::{ PassResult, PassResultReference };
+    use ::type_system::symbol_table::SymbolTableReference;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn generate(code: &str) -> CodegenResult<String> {
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut core = parse_core_module();
+        core.type_check(symbol_table.clone(), result.clone());
+
+        let mut module = Module::new("test".to_owned(), code.to_owned(), compile_ast(code), false);
+        module.type_check(symbol_table.clone(), result.clone());
+
+        generate_glsl(&module)
+    }
+
+    #[test]
+    fn vec4_constructor() {
+        let glsl = generate("
+            struct VertexInput {
+                position: vec3,
+            }
+
+            fn toClip(input: VertexInput, w: f32) -> vec4 {
+                return vec4(input.position, w * 2.0);
+            }
+        ").unwrap();
+
+        assert!(glsl.contains("struct VertexInput {\n    vec3 position;\n};"));
+        assert!(glsl.contains("vec4 toClip(VertexInput input_, float w) {"));
+        assert!(glsl.contains("    return vec4(input_.position, w * 2.0);"));
+    }
+
+    #[test]
+    fn statements_and_casts() {
+        let glsl = generate("
+            fn sum(n: i32) -> f32 {
+                let total = 0.0;
+                for i in 0..4 {
+                    total = total + i as f32;
+                }
+                return -total;
+            }
+        ").unwrap();
+
+        assert!(glsl.contains("    float total = 0.0;\n    for (int i = 0; i < 4; i++) {\n        total = total + float(i);\n    }\n    return -total;"));
+    }
+
+    #[test]
+    fn ternary_operands_are_parenthesized() {
+        let glsl = generate("
+            fn clampedScale(x: f32, limit: f32) -> f32 {
+                return x > limit ? limit : x * 2.0;
+            }
+        ").unwrap();
+
+        assert!(glsl.contains("    return (x > limit) ? limit : (x * 2.0);"));
+    }
+
+    #[test]
+    fn inherited_members_are_declared() {
+        let glsl = generate("
+            struct Base {
+                position: vec3,
+            }
+
+            struct Derived : Base {
+                uv: vec2,
+            }
+
+            fn make(position: vec3) -> Derived {
+                return Derived { position: position, uv: vec2(0.0, 0.0) };
+            }
+        ").unwrap();
+
+        assert!(glsl.contains("struct Derived {\n    vec3 position;\n    vec2 uv;\n};"));
+        assert!(glsl.contains("return Derived(position, vec2(0.0, 0.0));"));
+    }
+
+    #[test]
+    fn keywords_are_renamed_consistently() {
+        let code = "
+            struct Light {
+                active: f32,
+            }
+
+            const sample: f32;
+
+            fn output(light: Light, input: f32, input_: f32) -> f32 {
+                let filter = light.active * input + input_;
+                return filter * 2.0;
+            }
+
+            fn shade(light: Light) -> f32 {
+                return output(Light { active: 1.0 }, light.active, 2.0);
+            }
+        ";
+        let glsl = generate(code).unwrap();
+
+        assert!(glsl.contains("struct Light {\n    float active_;\n};"));
+        assert!(glsl.contains("uniform float sample_;"));
+        assert!(glsl.contains("float output_(Light light, float input__, float input_) {"));
+        assert!(glsl.contains("    float filter_ = (light.active_ * input__) + input_;\n    return filter_ * 2.0;"));
+        assert!(glsl.contains("    return output_(Light(1.0), light.active_, 2.0);"));
+
+        let renames = glsl_renames(&Module::new("test".to_owned(), code.to_owned(), compile_ast(code), false));
+        assert_eq!(renames.get("input").map(String::as_str), Some("input__"));
+        assert_eq!(renames.len(), 5);
+    }
+
+    #[test]
+    fn constants_are_uniforms() {
+        let glsl = generate("const scale: f32;").unwrap();
+
+        assert!(glsl.contains("uniform float scale;"));
+    }
+
+    #[test]
+    fn initialized_constants_are_glsl_constants() {
+        let glsl = generate("const scale: f32 = 2.0;").unwrap();
+
+        assert!(glsl.contains("const float scale = 2.0;"));
+    }
+
+    #[test]
+    fn unchecked_module_produces_an_error() {
+        let code = "fn f(a: f32) -> f32 { return a; }";
+        let module = Module::new("test".to_owned(), code.to_owned(), compile_ast(code), false);
+
+        assert_eq!(generate_glsl(&module), Err(CodegenError::NotChecked));
+    }
+}
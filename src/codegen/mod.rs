@@ -0,0 +1,72 @@
+use ::std::collections::HashMap;
+use ::std::error::Error;
+use ::std::fmt;
+use ::ast::{ Span, ItemKind };
+use ::module::Module;
+
+pub mod glsl;
+pub mod spirv;
+
+pub type CodegenResult<T> = Result<T, CodegenError>;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum CodegenError {
+    /// the module was not type checked successfully, so the AST lacks resolved types
+    NotChecked,
+    /// a type reference of the AST is missing or doesn't name a type of the symbol table
+    UnresolvedType(Span),
+    /// construct the target language has no equivalent for, e.g. operators declared on structs
+    Unsupported(Span, String /* Construct */),
+}
+
+impl fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CodegenError::NotChecked => write!(f, "Module has to be type checked before generating code."),
+            CodegenError::UnresolvedType(_) => write!(f, "Type is not resolved."),
+            CodegenError::Unsupported(_, ref construct) => write!(f, "{} is not supported by the target language.", construct),
+        }
+    }
+}
+
+impl Error for CodegenError {
+    fn description(&self) -> &str {
+        match *self {
+            CodegenError::NotChecked => "Module not type checked.",
+            CodegenError::UnresolvedType(_) => "Unresolved type.",
+            CodegenError::Unsupported(_, _) => "Unsupported construct.",
+        }
+    }
+}
+
+/// name of the declarations backends emit, in the order of `Module::topological_order`
+fn declaration_name(item: &ItemKind) -> Option<&String> {
+    match *item {
+        ItemKind::Struct(ref s) => Some(&s.struct_name.name),
+        ItemKind::Enum(ref e) => Some(&e.enum_name.name),
+        ItemKind::Constant(ref c) => Some(&c.constant_name.name),
+        ItemKind::Function(ref f) => Some(&f.function_name.name),
+        _ => None,
+    }
+}
+
+/// function aliases of the module and the function they name
+fn function_aliases(module: &Module) -> HashMap<&str, &str> {
+    let mut aliases = HashMap::new();
+    for alias in module.find_function_aliases() {
+        aliases.insert(alias.alias_name.name.as_str(), alias.target_name.name.as_str());
+    }
+    aliases
+}
+
+/// aliases may name other aliases, following at most one step per alias avoids looping on cycles
+fn resolve_alias<'a>(aliases: &HashMap<&'a str, &'a str>, name: &'a str) -> &'a str {
+    let mut name = name;
+    for _ in 0..aliases.len() {
+        match aliases.get(name) {
+            Some(target) => name = target,
+            None => break,
+        }
+    }
+    name
+}
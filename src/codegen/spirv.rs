@@ -0,0 +1,547 @@
+use ::std::collections::HashMap;
+use ::rspirv::mr::{ self, Builder, Operand };
+use ::rspirv::binary::Disassemble;
+use ::spirv::{ self, Word };
+use ::ast::*;
+use ::module::Module;
+use ::type_system::symbol_table::SymbolTable;
+use ::type_system::type_environment::TypeReference;
+use ::type_system::const_eval::{ self, ConstValue };
+use ::codegen::{ CodegenError, CodegenResult, declaration_name, function_aliases, resolve_alias };
+
+/// how the bits of a scalar, or of the components of a vector, are interpreted
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum ScalarKind {
+    Float,
+    Signed,
+    Unsigned,
+    Bool,
+}
+
+/// scalar types with a SPIR-V equivalent
+const SCALAR_TYPES: &[(&str, ScalarKind)] = &[
+    ("f32", ScalarKind::Float),
+    ("i32", ScalarKind::Signed),
+    ("u32", ScalarKind::Unsigned),
+    ("bool", ScalarKind::Bool),
+];
+
+/// vector type name, component type name and dimension
+const VECTOR_TYPES: &[(&str, &str, u32)] = &[
+    ("vec2", "f32", 2), ("vec3", "f32", 3), ("vec4", "f32", 4),
+    ("bvec2", "bool", 2), ("bvec3", "bool", 3), ("bvec4", "bool", 4),
+];
+
+/// channel names selecting a single vector component
+const SWIZZLE_CHANNELS: &[&str] = &["xyzw", "rgba", "stpq"];
+
+/// Generates SPIR-V assembly for the structs, scalar constants and functions of a type checked module
+/// function bodies have to be a single `return`, other statements need variables and control flow the backend doesn't emit yet
+/// module constants with an initializer become constants of their folded value, like GLSL `const`
+/// constants without initializer are set by the application, they become specialization constants defaulting to zero
+pub fn generate_spirv_text(module: &Module) -> CodegenResult<String> {
+    let symbol_table = match module.get_symbol_table() {
+        Some(symbol_table) if module.is_checked() => symbol_table.borrow(),
+        _ => return Err(CodegenError::NotChecked),
+    };
+
+    let order = match module.topological_order() {
+        Ok(order) => order,
+        Err(error) => return Err(CodegenError::Unsupported(error.get_span(), "Cyclic declaration".to_owned())),
+    };
+
+    let mut generator = SpirvGenerator {
+        symbol_table: &symbol_table,
+        aliases: function_aliases(module),
+        constant_values: const_eval::evaluate_constants(module.get_ast()),
+        builder: Builder::new(),
+        types: HashMap::new(),
+        void_type: None,
+        function_types: HashMap::new(),
+        constants: HashMap::new(),
+        functions: HashMap::new(),
+        parameters: HashMap::new(),
+        spec_ids: 0,
+    };
+
+    generator.builder.capability(spirv::Capability::Shader);
+    generator.builder.memory_model(spirv::AddressingModel::Logical, spirv::MemoryModel::GLSL450);
+
+    for name in order.iter() {
+        if let Some(item) = module.get_ast().iter().find(|item| declaration_name(item) == Some(name)) {
+            generator.generate_item(item)?;
+        }
+    }
+
+    Ok(generator.builder.module().disassemble())
+}
+
+/// builder errors mean the generator emitted instructions outside of a function or block
+fn build<T>(result: Result<T, mr::Error>, span: Span) -> CodegenResult<T> {
+    result.map_err(|error| CodegenError::Unsupported(span, format!("{:?}", error)))
+}
+
+struct SpirvGenerator<'a> {
+    symbol_table: &'a SymbolTable,
+    aliases: HashMap<&'a str, &'a str>,
+    /// folded values of the module constants with an initializer
+    constant_values: HashMap<String, ConstValue>,
+    builder: Builder,
+    types: HashMap<TypeReference, Word>,
+    void_type: Option<Word>,
+    /// return type and parameter types of emitted function types
+    function_types: HashMap<(Word, Vec<Word>), Word>,
+    /// type and bits of emitted `OpConstant`s
+    constants: HashMap<(Word, u32), Word>,
+    /// emitted functions by name, with their return type
+    functions: HashMap<&'a str, (Word, Word)>,
+    /// parameters of the current function with their type
+    parameters: HashMap<&'a str, (Word, TypeReference)>,
+    spec_ids: u32,
+}
+
+impl<'a> SpirvGenerator<'a> {
+    fn generate_item(&mut self, item: &'a ItemKind) -> CodegenResult<()> {
+        match *item {
+            ItemKind::Struct(ref s) => {
+                self.type_id(s.declaring_type, s.span)?;
+            },
+            ItemKind::Constant(ref c) => {
+                let constant_type_ref = c.constant_type;
+                let constant_type = self.type_id(constant_type_ref, c.span)?;
+                let kind = match self.scalar_kind(constant_type_ref) {
+                    Some(kind) => kind,
+                    None => return Err(CodegenError::Unsupported(c.span, format!("Constant \"{}\" of a non-scalar type", c.constant_name.name))),
+                };
+
+                if c.expression.is_none() {
+                    let id = match kind {
+                        ScalarKind::Float => self.builder.spec_constant_f32(constant_type, 0.0),
+                        ScalarKind::Signed | ScalarKind::Unsigned => self.builder.spec_constant_u32(constant_type, 0),
+                        ScalarKind::Bool => self.builder.spec_constant_false(constant_type),
+                    };
+
+                    self.builder.name(id, c.constant_name.name.to_owned());
+                    self.builder.decorate(id, spirv::Decoration::SpecId, vec![Operand::LiteralInt32(self.spec_ids)]);
+                    self.spec_ids += 1;
+                    return Ok(());
+                }
+
+                // not shared with literals of the same value, the constant carries its name
+                let id = match (kind, self.constant_values.get(&c.constant_name.name).cloned()) {
+                    (ScalarKind::Float, Some(ConstValue::Float(value))) => self.builder.constant_f32(constant_type, value as f32),
+                    (ScalarKind::Float, Some(ConstValue::Int(value))) => self.builder.constant_f32(constant_type, value as f32),
+                    (ScalarKind::Signed, Some(ConstValue::Int(value))) | (ScalarKind::Unsigned, Some(ConstValue::Int(value))) => self.builder.constant_u32(constant_type, value as u32),
+                    (ScalarKind::Bool, Some(ConstValue::Bool(true))) => self.builder.constant_true(constant_type),
+                    (ScalarKind::Bool, Some(ConstValue::Bool(false))) => self.builder.constant_false(constant_type),
+                    _ => return Err(CodegenError::Unsupported(c.span, format!("Initializer of constant \"{}\" which doesn't fold", c.constant_name.name))),
+                };
+
+                self.builder.name(id, c.constant_name.name.to_owned());
+            },
+            ItemKind::Function(ref f) => self.generate_function(f)?,
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn generate_function(&mut self, function: &'a FunctionDeclaration) -> CodegenResult<()> {
+        let is_void = function.return_type_name.name == "void";
+        let return_type = if is_void {
+            self.void_type()
+        } else {
+            self.type_id(function.return_type, function.return_type_name.span)?
+        };
+
+        let mut parameter_types = Vec::new();
+        for argument in function.arguments.iter() {
+            if argument.is_out {
+                return Err(CodegenError::Unsupported(argument.span, "Out parameter".to_owned()));
+            }
+            parameter_types.push(self.type_id(argument.argument_type, argument.span)?);
+        }
+
+        let function_type = self.function_type(return_type, parameter_types.clone());
+        let function_id = build(self.builder.begin_function(return_type, None, spirv::FunctionControl::NONE, function_type), function.span)?;
+        self.builder.name(function_id, function.function_name.name.to_owned());
+
+        self.parameters.clear();
+        for (argument, parameter_type) in function.arguments.iter().zip(parameter_types) {
+            let id = build(self.builder.function_parameter(parameter_type), argument.span)?;
+            self.builder.name(id, argument.argument_name.name.to_owned());
+            self.parameters.insert(&argument.argument_name.name, (id, argument.argument_type.unwrap()));
+        }
+
+        build(self.builder.begin_basic_block(None), function.block.span)?;
+        match *function.block.statements.as_slice() {
+            [BlockStatement::Return(ref return_statement)] => {
+                let value = self.expression(&return_statement.expression)?;
+                build(self.builder.ret_value(value), return_statement.span)?;
+            },
+            [] if is_void => build(self.builder.ret(), function.block.span)?,
+            _ => return Err(CodegenError::Unsupported(function.block.span, "Function body other than a single return".to_owned())),
+        }
+        build(self.builder.end_function(), function.span)?;
+
+        self.functions.insert(&function.function_name.name, (function_id, return_type));
+        Ok(())
+    }
+
+    fn expression(&mut self, expression: &ExpressionStatement) -> CodegenResult<Word> {
+        match *expression {
+            ExpressionStatement::Literal(ref e) => {
+                let literal_type = self.type_id(e.literal_type, e.span)?;
+                match const_eval::evaluate(expression) {
+                    Ok(ConstValue::String(_)) | Err(_) => Err(CodegenError::Unsupported(e.span, format!("Literal \"{}\"", e.value))),
+                    Ok(value) => Ok(self.constant(literal_type, value)),
+                }
+            },
+            ExpressionStatement::Variable(ref e) => match self.parameters.get(e.variable_name.name.as_str()).cloned() {
+                Some((id, _)) => Ok(id),
+                None => Err(CodegenError::Unsupported(e.span, format!("Variable \"{}\"", e.variable_name.name))),
+            },
+            ExpressionStatement::Infix(ref e) => self.infix(e),
+            ExpressionStatement::Prefix(ref e) => {
+                let operand = self.expression(&e.expression)?;
+                let result_type = self.type_id(e.prefix_type, e.span)?;
+                let result = match (e.operator, self.scalar_kind(e.expression.get_type())) {
+                    (PrefixOperator::Negate, Some(ScalarKind::Float)) => self.builder.fnegate(result_type, None, operand),
+                    (PrefixOperator::Negate, Some(ScalarKind::Signed)) => self.builder.snegate(result_type, None, operand),
+                    (PrefixOperator::Not, Some(ScalarKind::Bool)) => self.builder.logical_not(result_type, None, operand),
+                    _ => return Err(CodegenError::Unsupported(e.span, format!("Operator \"{}\"", e.operator.get_symbol()))),
+                };
+                build(result, e.span)
+            },
+            ExpressionStatement::Call(ref e) => self.call(e),
+            ExpressionStatement::Cast(ref e) => {
+                let value = self.expression(&e.expression)?;
+                let target_type = self.type_id(e.cast_target_type, e.span)?;
+                let result = match (self.scalar_kind(e.expression.get_type()), self.scalar_kind(e.cast_target_type)) {
+                    (Some(source), Some(target)) if source == target => return Ok(value),
+                    (Some(ScalarKind::Signed), Some(ScalarKind::Float)) => self.builder.convert_sto_f(target_type, None, value),
+                    (Some(ScalarKind::Unsigned), Some(ScalarKind::Float)) => self.builder.convert_uto_f(target_type, None, value),
+                    (Some(ScalarKind::Float), Some(ScalarKind::Signed)) => self.builder.convert_fto_s(target_type, None, value),
+                    (Some(ScalarKind::Float), Some(ScalarKind::Unsigned)) => self.builder.convert_fto_u(target_type, None, value),
+                    (Some(ScalarKind::Signed), Some(ScalarKind::Unsigned)) | (Some(ScalarKind::Unsigned), Some(ScalarKind::Signed)) => self.builder.bitcast(target_type, None, value),
+                    _ => return Err(CodegenError::Unsupported(e.span, "Cast".to_owned())),
+                };
+                build(result, e.span)
+            },
+            ExpressionStatement::FieldAccessor(ref e) => self.field_accessor(e),
+            ExpressionStatement::EnumVariant(ref e) => {
+                let variant_type = e.variant_type.and_then(|t| self.symbol_table.find_type(t));
+                let index = variant_type.and_then(|t| t.get_variants()).and_then(|v| v.iter().position(|v| *v == e.variant_name.name));
+                let int_type = self.symbol_table.find_type_ref("i32");
+                match index {
+                    Some(index) => {
+                        let int_type = self.type_id(int_type, e.span)?;
+                        Ok(self.constant(int_type, ConstValue::Int(index as i64)))
+                    },
+                    None => Err(CodegenError::UnresolvedType(e.span)),
+                }
+            },
+            // both branches are evaluated, expressions have no side effects
+            ExpressionStatement::Ternary(ref e) => {
+                let condition = self.expression(&e.condition)?;
+                let true_value = self.expression(&e.true_expression)?;
+                let false_value = self.expression(&e.false_expression)?;
+                let result_type = self.type_id(e.ternary_type, e.span)?;
+                build(self.builder.select(result_type, None, condition, true_value, false_value), e.span)
+            },
+            ExpressionStatement::StructInstantiation(ref e) => Err(CodegenError::Unsupported(e.span, "Struct instantiation".to_owned())),
+            ExpressionStatement::IndexAccessor(ref e) => Err(CodegenError::Unsupported(e.span, "Index accessor".to_owned())),
+        }
+    }
+
+    /// the instruction is picked by the component kind of the operands, the type checker made them the same type
+    fn infix(&mut self, infix: &InfixExpression) -> CodegenResult<Word> {
+        if infix.left_hand.get_type() != infix.right_hand.get_type() {
+            return Err(CodegenError::Unsupported(infix.span, format!("Operator \"{}\" on operands of different types", infix.operator.get_symbol())));
+        }
+
+        let kind = match self.component_kind(infix.left_hand.get_type()) {
+            Some(kind) => kind,
+            None => return Err(CodegenError::Unsupported(infix.span, format!("Operator \"{}\" on structs", infix.operator.get_symbol()))),
+        };
+
+        let left = self.expression(&infix.left_hand)?;
+        let right = self.expression(&infix.right_hand)?;
+        let t = self.type_id(infix.infix_type, infix.span)?;
+        let b = &mut self.builder;
+
+        let result = match (infix.operator, kind) {
+            (Operator::Plus, ScalarKind::Float) => b.fadd(t, None, left, right),
+            (Operator::Plus, ScalarKind::Signed) | (Operator::Plus, ScalarKind::Unsigned) => b.iadd(t, None, left, right),
+            (Operator::Minus, ScalarKind::Float) => b.fsub(t, None, left, right),
+            (Operator::Minus, ScalarKind::Signed) | (Operator::Minus, ScalarKind::Unsigned) => b.isub(t, None, left, right),
+            (Operator::Multiply, ScalarKind::Float) => b.fmul(t, None, left, right),
+            (Operator::Multiply, ScalarKind::Signed) | (Operator::Multiply, ScalarKind::Unsigned) => b.imul(t, None, left, right),
+            (Operator::Divide, ScalarKind::Float) => b.fdiv(t, None, left, right),
+            (Operator::Divide, ScalarKind::Signed) => b.sdiv(t, None, left, right),
+            (Operator::Divide, ScalarKind::Unsigned) => b.udiv(t, None, left, right),
+            (Operator::Equal, ScalarKind::Float) => b.ford_equal(t, None, left, right),
+            (Operator::Equal, ScalarKind::Bool) => b.logical_equal(t, None, left, right),
+            (Operator::Equal, _) => b.iequal(t, None, left, right),
+            (Operator::NotEqual, ScalarKind::Float) => b.ford_not_equal(t, None, left, right),
+            (Operator::NotEqual, ScalarKind::Bool) => b.logical_not_equal(t, None, left, right),
+            (Operator::NotEqual, _) => b.inot_equal(t, None, left, right),
+            (Operator::Less, ScalarKind::Float) => b.ford_less_than(t, None, left, right),
+            (Operator::Less, ScalarKind::Signed) => b.sless_than(t, None, left, right),
+            (Operator::Less, ScalarKind::Unsigned) => b.uless_than(t, None, left, right),
+            (Operator::LessEqual, ScalarKind::Float) => b.ford_less_than_equal(t, None, left, right),
+            (Operator::LessEqual, ScalarKind::Signed) => b.sless_than_equal(t, None, left, right),
+            (Operator::LessEqual, ScalarKind::Unsigned) => b.uless_than_equal(t, None, left, right),
+            (Operator::Greater, ScalarKind::Float) => b.ford_greater_than(t, None, left, right),
+            (Operator::Greater, ScalarKind::Signed) => b.sgreater_than(t, None, left, right),
+            (Operator::Greater, ScalarKind::Unsigned) => b.ugreater_than(t, None, left, right),
+            (Operator::GreaterEqual, ScalarKind::Float) => b.ford_greater_than_equal(t, None, left, right),
+            (Operator::GreaterEqual, ScalarKind::Signed) => b.sgreater_than_equal(t, None, left, right),
+            (Operator::GreaterEqual, ScalarKind::Unsigned) => b.ugreater_than_equal(t, None, left, right),
+            (operator, ScalarKind::Bool) => return Err(CodegenError::Unsupported(infix.span, format!("Operator \"{}\" on bool", operator.get_symbol()))),
+        };
+
+        build(result, infix.span)
+    }
+
+    fn call(&mut self, call: &CallExpression) -> CodegenResult<Word> {
+        let mut arguments = Vec::new();
+        for argument in call.arguments.iter() {
+            arguments.push(self.expression(argument)?);
+        }
+
+        let function_name = call.function_name.name.as_str();
+        if VECTOR_TYPES.iter().any(|&(name, _, _)| name == function_name) {
+            let vector_type = self.type_id(call.function_type, call.span)?;
+            return build(self.builder.composite_construct(vector_type, None, arguments), call.span);
+        }
+
+        match self.functions.get(resolve_alias(&self.aliases, function_name)) {
+            Some(&(function, return_type)) => build(self.builder.function_call(return_type, None, function, arguments), call.span),
+            None => Err(CodegenError::Unsupported(call.span, format!("Call to \"{}\"", function_name))),
+        }
+    }
+
+    /// struct members by index, single vector components by channel
+    fn field_accessor(&mut self, accessor: &FieldAccessorExpression) -> CodegenResult<Word> {
+        let (composite, composite_type) = match self.parameters.get(accessor.variable_name.name.as_str()).cloned() {
+            Some(value) => value,
+            None => return Err(CodegenError::Unsupported(accessor.span, format!("Variable \"{}\"", accessor.variable_name.name))),
+        };
+
+        let field_name = &accessor.field_name.name;
+        let definition = self.symbol_table.find_type(composite_type);
+        let index = match definition.and_then(|t| t.get_member()) {
+            Some(members) => members.iter().position(|m| m.member_name == *field_name),
+            None if field_name.len() == 1 => SWIZZLE_CHANNELS.iter().filter_map(|channels| channels.find(field_name.as_str())).next(),
+            None => None,
+        };
+
+        match index {
+            Some(index) => {
+                let field_type = self.type_id(accessor.field_type, accessor.span)?;
+                build(self.builder.composite_extract(field_type, None, composite, vec![index as u32]), accessor.span)
+            },
+            None => Err(CodegenError::Unsupported(accessor.span, format!("Field \"{}\"", field_name))),
+        }
+    }
+
+    /// strings have no SPIR-V constant, literals reject them before
+    fn constant(&mut self, constant_type: Word, value: ConstValue) -> Word {
+        let bits = match value {
+            ConstValue::Float(value) => (value as f32).to_bits(),
+            ConstValue::Int(value) => value as u32,
+            ConstValue::Bool(value) => value as u32,
+            ConstValue::String(_) => unreachable!("string constant"),
+        };
+
+        if let Some(&id) = self.constants.get(&(constant_type, bits)) {
+            return id;
+        }
+
+        let id = match value {
+            ConstValue::Float(value) => self.builder.constant_f32(constant_type, value as f32),
+            ConstValue::Int(_) => self.builder.constant_u32(constant_type, bits),
+            ConstValue::Bool(true) => self.builder.constant_true(constant_type),
+            ConstValue::Bool(false) => self.builder.constant_false(constant_type),
+            ConstValue::String(_) => unreachable!("string constant"),
+        };
+        self.constants.insert((constant_type, bits), id);
+        id
+    }
+
+    fn scalar_kind(&self, type_reference: Option<TypeReference>) -> Option<ScalarKind> {
+        let name = type_reference.and_then(|t| self.symbol_table.find_type(t)).map(|t| t.get_name())?;
+        SCALAR_TYPES.iter().find(|&&(scalar, _)| scalar == name).map(|&(_, kind)| kind)
+    }
+
+    /// kind of scalars and of vector components
+    fn component_kind(&self, type_reference: Option<TypeReference>) -> Option<ScalarKind> {
+        let name = type_reference.and_then(|t| self.symbol_table.find_type(t)).map(|t| t.get_name())?;
+        match VECTOR_TYPES.iter().find(|&&(vector, _, _)| vector == name) {
+            Some(&(_, component, _)) => self.scalar_kind(self.symbol_table.find_type_ref(component)),
+            None => self.scalar_kind(type_reference),
+        }
+    }
+
+    fn void_type(&mut self) -> Word {
+        match self.void_type {
+            Some(id) => id,
+            None => {
+                let id = self.builder.type_void();
+                self.void_type = Some(id);
+                id
+            },
+        }
+    }
+
+    fn function_type(&mut self, return_type: Word, parameter_types: Vec<Word>) -> Word {
+        if let Some(&id) = self.function_types.get(&(return_type, parameter_types.clone())) {
+            return id;
+        }
+
+        let id = self.builder.type_function(return_type, parameter_types.clone());
+        self.function_types.insert((return_type, parameter_types), id);
+        id
+    }
+
+    /// emits the type and the types it consists of once, enums are represented as `i32`
+    fn type_id(&mut self, type_reference: Option<TypeReference>, span: Span) -> CodegenResult<Word> {
+        let type_reference = match type_reference {
+            Some(type_reference) => type_reference,
+            None => return Err(CodegenError::UnresolvedType(span)),
+        };
+
+        if let Some(&id) = self.types.get(&type_reference) {
+            return Ok(id);
+        }
+
+        let definition = match self.symbol_table.find_type(type_reference) {
+            Some(definition) => definition,
+            None => return Err(CodegenError::UnresolvedType(span)),
+        };
+
+        let name = definition.get_name();
+        let id = if let Some(kind) = self.scalar_kind(Some(type_reference)) {
+            match kind {
+                ScalarKind::Float => self.builder.type_float(32),
+                ScalarKind::Signed => self.builder.type_int(32, 1),
+                ScalarKind::Unsigned => self.builder.type_int(32, 0),
+                ScalarKind::Bool => self.builder.type_bool(),
+            }
+        } else if let Some(&(_, component, dimension)) = VECTOR_TYPES.iter().find(|&&(vector, _, _)| vector == name) {
+            let component_type = self.symbol_table.find_type_ref(component);
+            let component_type = self.type_id(component_type, span)?;
+            self.builder.type_vector(component_type, dimension)
+        } else if definition.is_enum() {
+            let int_type = self.symbol_table.find_type_ref("i32");
+            self.type_id(int_type, span)?
+        } else if let Some(members) = definition.get_member() {
+            let mut member_types = Vec::new();
+            for member in members.iter() {
+                member_types.push(self.type_id(Some(member.member_type), span)?);
+            }
+
+            let id = self.builder.type_struct(member_types);
+            self.builder.name(id, name.to_owned());
+            for (index, member) in members.iter().enumerate() {
+                self.builder.member_name(id, index as u32, member.member_name.to_owned());
+            }
+            id
+        } else {
+            return Err(CodegenError::Unsupported(span, format!("Type \"{}\"", name)));
+        };
+
+        self.types.insert(type_reference, id);
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::compiler::parse_core_module;
+    use ::passes::results::{ PassResult, PassResultReference };
+    use ::type_system::symbol_table::SymbolTableReference;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn generate(code: &str) -> CodegenResult<String> {
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut core = parse_core_module();
+        core.type_check(symbol_table.clone(), result.clone());
+
+        let mut module = Module::new("test".to_owned(), code.to_owned(), compile_ast(code), false);
+        module.type_check(symbol_table.clone(), result.clone());
+
+        generate_spirv_text(&module)
+    }
+
+    #[test]
+    fn add() {
+        let spirv = generate("fn add(a: f32, b: f32) -> f32 { return a + b; }").unwrap();
+
+        assert!(spirv.contains("OpName %3 \"add\""));
+        assert!(spirv.contains("%2 = OpTypeFunction %1 %1 %1"));
+        assert!(spirv.contains("%3 = OpFunction  %1  None %2"));
+        assert!(spirv.contains("%4 = OpFunctionParameter  %1"));
+        assert!(spirv.contains("%7 = OpFAdd  %1  %4 %5"));
+        assert!(spirv.contains("OpReturnValue %7"));
+    }
+
+    #[test]
+    fn structs_and_literals() {
+        let spirv = generate("
+            struct Light {
+                intensity: f32,
+                color: vec3,
+            }
+
+            fn scale(light: Light) -> f32 {
+                return light.intensity * 2.0;
+            }
+        ").unwrap();
+
+        assert!(spirv.contains("OpTypeStruct %1 %2"));
+        assert!(spirv.contains("OpMemberName %3 1 \"color\""));
+        assert!(spirv.contains("%9 = OpConstant  %1  2.0"));
+        assert!(spirv.contains("%8 = OpCompositeExtract  %1  %6 0"));
+        assert!(spirv.contains("%10 = OpFMul  %1  %8 %9"));
+    }
+
+    #[test]
+    fn module_constants_are_specialization_constants() {
+        let spirv = generate("const scale: f32; const enabled: bool;").unwrap();
+
+        assert!(spirv.contains("OpName %2 \"scale\""));
+        assert!(spirv.contains("OpDecorate %2 SpecId 0"));
+        assert!(spirv.contains("OpDecorate %4 SpecId 1"));
+        assert!(spirv.contains("%2 = OpSpecConstant  %1  0.0"));
+        assert!(spirv.contains("%4 = OpSpecConstantFalse  %3"));
+    }
+
+    #[test]
+    fn initialized_module_constants_are_folded() {
+        let spirv = generate("const a: f32 = 1.5; const b: f32 = a * 2.0; const count: i32 = 2 + 3; const enabled: bool = true;").unwrap();
+
+        assert!(spirv.contains("OpName %2 \"a\""));
+        assert!(spirv.contains("OpName %3 \"b\""));
+        assert!(spirv.contains("%2 = OpConstant  %1  1.5"));
+        assert!(spirv.contains("%3 = OpConstant  %1  3.0"));
+        assert!(spirv.contains("%5 = OpConstant  %4  5"));
+        assert!(spirv.contains("%7 = OpConstantTrue  %6"));
+        assert!(!spirv.contains("OpSpecConstant"));
+        assert!(!spirv.contains("SpecId"));
+    }
+
+    #[test]
+    fn statements_are_unsupported() {
+        let result = generate("fn f(a: f32) -> f32 { let b = a; return b; }");
+
+        match result {
+            Err(CodegenError::Unsupported(_, _)) => (),
+            _ => panic!("{:?}", result),
+        }
+    }
+}
@@ -10,6 +10,8 @@ pub enum ErrorKind {
     Unknown,
     ParseError,
     TypeError(TypeError),
+    /// every error of a type check that found more than one, each with its own span
+    Multiple(Vec<CompileError>),
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -40,6 +42,14 @@ impl CompileError {
     pub fn get_kind(&self) -> &ErrorKind {
         &self.kind
     }
+
+    /// the individual errors, only `Multiple` has more than one
+    pub fn get_errors(&self) -> Vec<&CompileError> {
+        match self.kind {
+            ErrorKind::Multiple(ref errors) => errors.iter().collect(),
+            _ => vec![self],
+        }
+    }
 }
 
 impl fmt::Display for CompileError {
@@ -58,6 +68,7 @@ impl Error for CompileError {
             ErrorKind::Unknown => None,
             ErrorKind::TypeError(ref t) => Some(t),
             ErrorKind::ParseError => None,
+            ErrorKind::Multiple(ref errors) => errors.first().map(|e| e as &Error),
         }
     }
 }
@@ -3,17 +3,195 @@ use ::std::mem;
 use ::std::collections::HashMap;
 use ::compile_error::{ CompileError, CompileResult, ErrorKind };
 use ::module::Module;
-use ::parser::parse_str;
-use ::type_system::symbol_table::SymbolTable;
-use ::type_system::type_environment::TypeEnvironment;
-use ::ast::{ ItemKind, Span };
+use ::parser::{ parse_str, parse_str_with_precedences, PrecedenceTable };
+use ::type_system::symbol_table::{ SymbolTable, SymbolTableReference };
+use ::type_system::type_environment::{ TypeEnvironment, TypeReference };
+use ::type_system::error::{ TypeError, ErrorKind as TypeErrorKind };
+use ::ast::{ ItemKind, Span, ImportDefinition, ImportItem, ShaderStage };
+use ::passes::results::{ PassResult, PassResultReference };
 use ::passes::ast::AstWalker;
+use ::passes::ast::type_checking::TypeCheckerOptions;
+use ::transform;
+use ::codegen::{ self, CodegenResult };
+use ::type_system::layout::{ self, Layout, LayoutRules };
 
-fn parse_core_modules(symbols: &mut SymbolTable) -> Result<Module, Box<Error>> {
+/// the core module declaring the builtin primitive types, casts and operators, parsed but not type checked
+pub fn parse_core_module() -> Module {
     let primitives = include_str!("../libcore/primitives.xs");
-    let ast = parse_str(primitives)?;
-    let mut module = Module::new("".to_owned(), primitives.to_owned(), ast, true);
-    Ok(module)
+    let ast = parse_str(primitives).expect("core module failed to parse");
+    Module::new("".to_owned(), primitives.to_owned(), ast, true)
+}
+
+/// the operator precedences declared by the core module, used to parse all other modules
+fn core_precedences(core_module: &Module) -> CompileResult<PrecedenceTable> {
+    match PrecedenceTable::from_declarations(&core_module.find_operator_precedences()) {
+        Ok(precedences) => Ok(precedences),
+        Err(error) => {
+            let span = error.get_span();
+            Err(CompileError::new(ErrorKind::TypeError(error), span))
+        },
+    }
+}
+
+/// validates the items every loaded module imports against the exports of the imported module
+fn check_imports(modules: &HashMap<String, Module>) -> CompileResult<()> {
+    for module in modules.values() {
+        for import in module.find_imports() {
+            let imported_module = match modules.get(&import.module_id) {
+                Some(m) => m,
+                None => {
+                    let error = TypeError::new(import.span, TypeErrorKind::ModuleNotFound(import.module_id.to_owned()));
+                    return Err(CompileError::new(ErrorKind::TypeError(error), import.span));
+                },
+            };
+
+            if let Err(error) = imported_module.resolve_import(import) {
+                let span = error.get_span();
+                return Err(CompileError::new(ErrorKind::TypeError(error), span));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn type_error(error: TypeError) -> CompileError {
+    let span = error.get_span();
+    CompileError::new(ErrorKind::TypeError(error), span)
+}
+
+/// fails with the errors reported to `result`, several are returned as `ErrorKind::Multiple`
+fn check_result(result: &PassResultReference) -> CompileResult<()> {
+    let mut errors: Vec<CompileError> = result.borrow_mut().take_errors().into_iter()
+        .map(|error| match error.downcast::<TypeError>() {
+            Ok(error) => type_error(*error),
+            Err(_) => CompileError::unknown(),
+        })
+        .collect();
+
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0)),
+        _ => {
+            let span = errors[0].get_span();
+            Err(CompileError::new(ErrorKind::Multiple(errors), span))
+        },
+    }
+}
+
+/// references to declarations an imported module doesn't export are reported as such, rather than as unknown names
+/// `unexported` maps the names to the path of the module declaring them
+fn report_unexported_references(result: &PassResultReference, unexported: &HashMap<String, String>) {
+    let mut result = result.borrow_mut();
+    for error in result.take_errors() {
+        let error = match error.downcast::<TypeError>() {
+            Ok(error) => error,
+            Err(error) => {
+                result.add_error(error);
+                continue;
+            },
+        };
+
+        let module_path = match *error.get_kind() {
+            TypeErrorKind::TypeNotFound(ref name) | TypeErrorKind::VariableNotFound(ref name) => unexported.get(name).map(|m| (name.to_owned(), m.to_owned())),
+            _ => None,
+        };
+        match module_path {
+            Some((name, module_path)) => result.add_error(Box::new(TypeError::new(error.get_span(), TypeErrorKind::UnknownImportedSymbol(name, module_path)))),
+            None => result.add_error(error),
+        }
+    }
+}
+
+/// module paths ordered so every module comes after the modules it imports
+fn dependency_order(module_path: &str, modules: &HashMap<String, Module>, order: &mut Vec<String>, path: &mut Vec<String>) -> CompileResult<()> {
+    if order.iter().any(|m| m == module_path) {
+        return Ok(());
+    }
+
+    if let Some(start) = path.iter().position(|m| m == module_path) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(module_path.to_owned());
+        return Err(type_error(TypeError::new(Span::empty(), TypeErrorKind::CyclicDependency(cycle))));
+    }
+
+    path.push(module_path.to_owned());
+    for import in modules[module_path].find_imports() {
+        dependency_order(&import.module_id, modules, order, path)?;
+    }
+    path.pop();
+
+    order.push(module_path.to_owned());
+    Ok(())
+}
+
+/// type and symbol type of an exported declaration, functions have both
+type ExportedSymbol = (Option<TypeReference>, Option<TypeReference>);
+
+/// type checks the loaded modules, every module after the modules it imports
+/// each module is checked in its own scope that sees the core module and the declarations it imports
+/// the scope of the root module stays open, so its declarations can be looked up after checking
+fn type_check_modules(root_path: &str, modules: &mut HashMap<String, Module>, symbol_table: &SymbolTableReference, options: &TypeCheckerOptions, result: &PassResultReference) -> CompileResult<()> {
+    let mut order = Vec::new();
+    dependency_order(root_path, modules, &mut order, &mut Vec::new())?;
+
+    let mut exports: HashMap<String, HashMap<String, ExportedSymbol>> = HashMap::new();
+    for module_path in order {
+        let mut imported = Vec::new();
+        let mut unexported = HashMap::new();
+        for import in modules[&module_path].find_imports() {
+            for name in modules[&import.module_id].unexported_names() {
+                unexported.insert(name, import.module_id.to_owned());
+            }
+
+            let names = modules[&import.module_id].resolve_import(import).map_err(type_error)?;
+            for name in names {
+                if let Some(&symbol) = exports.get(&import.module_id).and_then(|e| e.get(&name)) {
+                    imported.push((name, symbol));
+                }
+            }
+        }
+
+        {
+            let mut symbol_table = symbol_table.borrow_mut();
+            symbol_table.enter_scope();
+            for (name, (type_reference, symbol_type)) in imported {
+                if let Some(type_reference) = type_reference {
+                    symbol_table.add_type(&name, type_reference).map_err(type_error)?;
+                }
+                if let Some(symbol_type) = symbol_type {
+                    symbol_table.add_symbol_with_type(&name, symbol_type).map_err(type_error)?;
+                }
+            }
+        }
+
+        let module = modules.get_mut(&module_path).unwrap();
+        module.type_check_with_options(symbol_table.clone(), result.clone(), options);
+        report_unexported_references(result, &unexported);
+        check_result(result)?;
+
+        let export_all = ImportDefinition {
+            span: Span::empty(),
+            items: vec![ImportItem::All],
+            module_id: module_path.to_owned(),
+        };
+        let names = module.resolve_import(&export_all).map_err(type_error)?;
+
+        let mut symbol_table = symbol_table.borrow_mut();
+        let mut symbols = HashMap::new();
+        for name in names {
+            let type_reference = symbol_table.find_type_ref(&name);
+            let symbol_type = symbol_table.find_symbol(&name).and_then(|s| s.get_type());
+            symbols.insert(name, (type_reference, symbol_type));
+        }
+        exports.insert(module_path.to_owned(), symbols);
+
+        if module_path != root_path {
+            symbol_table.leave_scope();
+        }
+    }
+
+    Ok(())
 }
 
 pub trait ModuleResolver {
@@ -21,74 +199,148 @@ pub trait ModuleResolver {
 }
 
 pub struct Compilation {
-    symbol_table: Option<SymbolTable>,
-    module: Module
+    module: Module,
+    /// warnings of the compiled modules, the core module's are left out
+    warnings: Vec<Box<Error>>,
+    infos: Vec<Box<Error>>,
 }
 
 impl Compilation {
-    fn new(symbol_table: SymbolTable, module: Module) -> Compilation {
+    fn new(module: Module, result: &PassResultReference) -> Compilation {
+        let mut result = result.borrow_mut();
         Compilation {
-            symbol_table: Some(symbol_table),
             module: module,
+            warnings: result.take_warnings(),
+            infos: result.take_infos(),
         }
     }
 
+    /// e.g. unused locals, dead stores and unreachable statements, they don't fail the compilation
+    pub fn warnings(&self) -> &[Box<Error>] {
+        &self.warnings
+    }
+
+    /// informational entries, e.g. the constant types reported with `TypeCheckerOptions::report_constant_types`
+    pub fn infos(&self) -> &[Box<Error>] {
+        &self.infos
+    }
+
+    /// the checked root module, e.g. to pass it to the functions of `transform` and `codegen`
+    pub fn get_module(&self) -> &Module {
+        &self.module
+    }
+
     pub fn get_ast_mut(&mut self) -> &mut Vec<ItemKind> {
         self.module.get_ast_mut()
     }
+
+    /// merges chains of implicit casts in the typed AST, see `transform::collapse_casts`
+    pub fn collapse_casts(&mut self) {
+        transform::collapse_casts(&mut self.module);
+    }
+
+    /// the compiled module reduced to a single stage, see `transform::extract_stage`
+    pub fn extract_stage(&self, stage: ShaderStage) -> Module {
+        transform::extract_stage(&self.module, stage)
+    }
+
+    /// GLSL for the compiled module, see `codegen::glsl::generate_glsl`
+    pub fn generate_glsl(&self) -> CodegenResult<String> {
+        codegen::glsl::generate_glsl(&self.module)
+    }
+
+    /// SPIR-V assembly for the compiled module, see `codegen::spirv::generate_spirv_text`
+    pub fn generate_spirv_text(&self) -> CodegenResult<String> {
+        codegen::spirv::generate_spirv_text(&self.module)
+    }
+
+    /// size and alignment of the type named `type_name`, `None` for unknown types and types without a known size
+    pub fn layout_of(&self, type_name: &str, rules: LayoutRules) -> Option<Layout> {
+        let symbol_table = self.module.get_symbol_table()?.borrow();
+        layout::layout_of(&symbol_table, symbol_table.find_type_ref(type_name)?, rules)
+    }
 }
 
 pub struct Compiler {
     resolver: Box<ModuleResolver>,
+    options: TypeCheckerOptions,
+    core_module: Module,
 }
 
 impl Compiler {
     
     pub fn new(resolver: Box<ModuleResolver>) -> Compiler {
+        Compiler::with_options(resolver, TypeCheckerOptions::new())
+    }
+
+    /// compiler checking the core and every module with `options`, e.g. to configure lints or layout rules
+    pub fn with_options(resolver: Box<ModuleResolver>, options: TypeCheckerOptions) -> Compiler {
         Compiler {
             resolver: resolver,
+            options: options,
+            core_module: parse_core_module(),
         }
     }
 
-    pub fn compile_module(&mut self, module_path: &str) -> CompileResult<Compilation> {
-        let source = match self.resolver.resolve(module_path) {
-            Ok(source) => source,
-            Err(_) => return Err(CompileError::unknown()),
-        };
+    /// the core module declaring the builtin primitive types, casts and operators
+    /// it is parsed once but not type checked, so tools can inspect the declarations
+    pub fn core_module(&self) -> &Module {
+        &self.core_module
+    }
 
-        let ast = parse_str(&source)?;
+    pub fn compile_module(&mut self, module_path: &str) -> CompileResult<Compilation> {
+        if self.resolver.resolve(module_path).is_err() {
+            return Err(CompileError::unknown());
+        }
 
-        let mut symbol_table = SymbolTable::new(TypeEnvironment::new());
-        parse_core_modules(&mut symbol_table).unwrap();
+        let symbol_table = SymbolTable::new(TypeEnvironment::new());
+        let mut core_module = self.core_module.with_ast(self.core_module.get_ast().to_vec());
+        let precedences = core_precedences(&core_module)?;
 
         let mut modules = HashMap::new();
-        self.load_modules(module_path, &mut modules)?;
+        self.load_modules(module_path, None, &precedences, &mut modules)?;
+        check_imports(&modules)?;
 
-        let mut module = Module::new(module_path.to_owned(), source, ast, false);
+        // the core module is checked in the root scope, so every module sees its primitives
+        let symbol_table = SymbolTableReference::new(symbol_table);
+        let result = PassResultReference::new(PassResult::new());
+        core_module.type_check_with_options(symbol_table.clone(), result.clone(), &self.options);
+        check_result(&result)?;
 
-        // TODO insert pass system here
+        let result = PassResultReference::new(PassResult::new());
+        type_check_modules(module_path, &mut modules, &symbol_table, &self.options, &result)?;
 
-        Ok(Compilation::new(symbol_table, module))
+        let module = modules.remove(module_path).unwrap();
+        Ok(Compilation::new(module, &result))
     }
 
-    fn load_modules(&mut self, module_path: &str, modules: &mut HashMap<String, Module>) -> CompileResult<()> {
+    /// `import` is the import that loads the module, `None` for the root module
+    fn load_modules(&mut self, module_path: &str, import: Option<&ImportDefinition>, precedences: &PrecedenceTable, modules: &mut HashMap<String, Module>) -> CompileResult<()> {
+        // the root module was resolved before, so this fails for imported modules only
         let source = match self.resolver.resolve(module_path) {
             Ok(source) => source,
-            Err(_) => return Err(CompileError::unknown()),
+            Err(_) => {
+                let error = match import {
+                    Some(import) if import.items == [ImportItem::All] => TypeError::new(import.span, TypeErrorKind::UnresolvedImport(module_path.to_owned())),
+                    Some(import) => TypeError::new(import.span, TypeErrorKind::ModuleNotFound(module_path.to_owned())),
+                    None => TypeError::new(Span::empty(), TypeErrorKind::ModuleNotFound(module_path.to_owned())),
+                };
+                return Err(type_error(error));
+            },
         };
 
-        let ast = parse_str(&source)?;
+        let ast = parse_str_with_precedences(&source, precedences)?;
         let module = Module::new(module_path.to_owned(), source, ast, false);
 
-        let imports: Vec<String> = module.find_imports().iter().map(|&i| i.module_id.to_owned()).collect();
+        let imports: Vec<ImportDefinition> = module.find_imports().into_iter().cloned().collect();
         modules.insert(module_path.to_owned(), module);
 
-        for import in imports {
-            if modules.contains_key(&import) {
+        for import in imports.iter() {
+            if modules.contains_key(&import.module_id) {
                 continue;
             }
 
-            self.load_modules(&import, modules)?;
+            self.load_modules(&import.module_id, Some(import), precedences, modules)?;
         }
 
         Ok(())
@@ -99,6 +351,8 @@ impl Compiler {
 mod tests {
     use std::collections::HashMap;
     use super::*;
+    use ::passes::results::LintLevel;
+    use ::type_system::const_eval::ConstValue;
 
     struct TestResolver {
         map: HashMap<String, String>,
@@ -146,4 +400,276 @@ mod tests {
 
         assert!(compiler.compile_module("a").is_ok());
     }
+
+    #[test]
+    fn test_compile_constants() {
+        let mut map = HashMap::new();
+        map.insert("test".to_string(), "
+            const SIZE: i32 = 16;
+            const OK: bool = SIZE >= 16;
+            fn sum(x: f32) -> f32 {
+                let total = 0.0;
+                for i in 0..SIZE {
+                    total = total + x;
+                }
+                return OK ? total : 0.0;
+            }
+        ".to_string());
+        let resolver = Box::new(TestResolver::new(map));
+        let mut compiler = Compiler::new(resolver);
+
+        assert!(compiler.compile_module("test").is_ok());
+    }
+
+    #[test]
+    fn test_compile_u32() {
+        let mut map = HashMap::new();
+        map.insert("test".to_string(), "
+            fn size() -> u32 {
+                return sizeof(vec4);
+            }
+
+            fn bits(x: f32) -> u32 {
+                let max: u32 = 4294967295;
+                return bitcast<u32>(x) + max;
+            }
+        ".to_string());
+        let resolver = Box::new(TestResolver::new(map));
+        let mut compiler = Compiler::new(resolver);
+
+        assert!(compiler.compile_module("test").is_ok());
+    }
+
+    #[test]
+    fn test_compile_with_options() {
+        let mut map = HashMap::new();
+        map.insert("test".to_string(), "fn same(a: f32, b: f32) -> bool { return a == b; }".to_string());
+
+        let resolver = Box::new(TestResolver::new(map.clone()));
+        assert!(Compiler::new(resolver).compile_module("test").is_ok());
+
+        let mut options = TypeCheckerOptions::new();
+        options.lints.float_equality = LintLevel::Error;
+        let resolver = Box::new(TestResolver::new(map));
+        assert!(Compiler::with_options(resolver, options).compile_module("test").is_err());
+    }
+
+    #[test]
+    fn test_generate_code() {
+        let mut map = HashMap::new();
+        map.insert("test".to_string(), "
+            struct Light {
+                color: vec4,
+                intensity: f32,
+            }
+
+            fn scale(x: f32) -> f32 {
+                return x * 2.0;
+            }
+        ".to_string());
+        let resolver = Box::new(TestResolver::new(map));
+        let compilation = Compiler::new(resolver).compile_module("test").unwrap();
+
+        assert!(compilation.generate_glsl().unwrap().contains("float scale(float x) {"));
+        assert!(compilation.generate_spirv_text().unwrap().contains("OpFunction"));
+        assert_eq!(compilation.layout_of("Light", LayoutRules::Std140), Some(Layout { size: 32, alignment: 16 }));
+        assert_eq!(compilation.layout_of("Unknown", LayoutRules::Std140), None);
+    }
+
+    #[test]
+    fn test_compiled_module_keeps_symbol_table() {
+        let mut map = HashMap::new();
+        map.insert("test".to_string(), "fn scale(x: f32) -> f32 { return x * 2.0; }".to_string());
+        let resolver = Box::new(TestResolver::new(map));
+        let compilation = Compiler::new(resolver).compile_module("test").unwrap();
+        let module = compilation.get_module();
+
+        assert!(transform::extract_stage(module, ShaderStage::Vertex).get_ast().is_empty());
+        assert!(codegen::glsl::generate_glsl(module).unwrap().contains("float scale(float x) {"));
+        let dot = module.get_symbol_table().unwrap().borrow().get_type_environment().to_dot();
+        assert!(dot.contains("[label=\"f32\"]"));
+    }
+
+    #[test]
+    fn test_compile_reports_every_error() {
+        let mut map = HashMap::new();
+        map.insert("test".to_string(), "
+            struct Light {
+                color: Color,
+            }
+
+            fn scale(x: Scalar) -> f32 {
+                return 2.0;
+            }
+        ".to_string());
+        let resolver = Box::new(TestResolver::new(map));
+        let error = match Compiler::new(resolver).compile_module("test") {
+            Err(error) => error,
+            Ok(_) => panic!("expected errors"),
+        };
+
+        let kinds: Vec<&TypeErrorKind> = error.get_errors().iter().filter_map(|e| match *e.get_kind() {
+            ErrorKind::TypeError(ref error) => Some(error.get_kind()),
+            _ => None,
+        }).collect();
+        assert_eq!(kinds, vec![&TypeErrorKind::TypeNotFound("Color".to_owned()), &TypeErrorKind::TypeNotFound("Scalar".to_owned())]);
+    }
+
+    #[test]
+    fn test_compile_reports_warnings_and_infos() {
+        let mut map = HashMap::new();
+        map.insert("test".to_string(), "
+            const scale: f32 = 2.0;
+
+            fn f(x: f32) -> f32 {
+                let unused = x;
+                return x * scale;
+                let late = x;
+            }
+        ".to_string());
+        let mut options = TypeCheckerOptions::new();
+        options.report_constant_types = true;
+        let resolver = Box::new(TestResolver::new(map));
+        let compilation = Compiler::with_options(resolver, options).compile_module("test").unwrap();
+
+        let warnings: Vec<&TypeErrorKind> = compilation.warnings().iter().filter_map(|w| w.downcast_ref::<TypeError>()).map(|w| w.get_kind()).collect();
+        assert!(warnings.contains(&&TypeErrorKind::UnusedLocal("unused".to_owned())));
+        assert!(warnings.contains(&&TypeErrorKind::UnreachableStatement));
+
+        let infos: Vec<&TypeErrorKind> = compilation.infos().iter().filter_map(|i| i.downcast_ref::<TypeError>()).map(|i| i.get_kind()).collect();
+        assert_eq!(infos, vec![&TypeErrorKind::ConstantType("scale".to_owned(), "f32".to_owned(), Some(ConstValue::Float(2.0)))]);
+    }
+
+    #[test]
+    fn test_core_module() {
+        let compiler = Compiler::new(Box::new(TestResolver::new(HashMap::new())));
+        let module = compiler.core_module();
+        let primitives: Vec<&str> = module.find_primitives().iter().map(|p| p.type_name.name.as_str()).collect();
+
+        assert!(module.is_core());
+        assert!(["bool", "f32", "f64", "i32", "i64", "u32"].iter().all(|name| primitives.contains(name)));
+        assert!(!module.find_casts().is_empty());
+    }
+
+    fn compile_math_import(import: &str) -> CompileResult<Compilation> {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), import.to_string());
+        map.insert("math".to_string(), "
+            fn sin(x: f32) -> f32 { return x; }
+            fn cos(x: f32) -> f32 { return x; }
+            fn reduce(x: f32) -> f32 { return x; }
+            export { sin, cos };
+        ".to_string());
+        let resolver = Box::new(TestResolver::new(map));
+        let mut compiler = Compiler::new(resolver);
+
+        compiler.compile_module("a")
+    }
+
+    fn assert_type_error(result: CompileResult<Compilation>, expected: TypeErrorKind) {
+        match result {
+            Err(ref error) => match *error.get_kind() {
+                ErrorKind::TypeError(ref error) => assert_eq!(*error.get_kind(), expected),
+                ref kind => panic!("expected type error, got {:?}", kind),
+            },
+            Ok(_) => panic!("expected type error"),
+        }
+    }
+
+    #[test]
+    fn test_import_specific_symbols() {
+        assert!(compile_math_import("import { sin, cos } from 'math';").is_ok());
+    }
+
+    #[test]
+    fn test_import_unknown_symbol() {
+        assert_type_error(compile_math_import("import { sin, tan } from 'math';"), TypeErrorKind::UnresolvedPath("tan".to_owned()));
+    }
+
+    #[test]
+    fn test_import_from_missing_module() {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), "import { sin } from 'trig';".to_string());
+        let resolver = Box::new(TestResolver::new(map));
+        let mut compiler = Compiler::new(resolver);
+
+        assert_type_error(compiler.compile_module("a"), TypeErrorKind::ModuleNotFound("trig".to_owned()));
+    }
+
+    fn compile_shapes_import(code: &str) -> CompileResult<Compilation> {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), code.to_string());
+        map.insert("shapes".to_string(), "
+            struct Circle { radius: f32, }
+            struct Square { side: f32, }
+            fn area(circle: Circle) -> f32 { return circle.radius * circle.radius * 3.14; }
+        ".to_string());
+        let resolver = Box::new(TestResolver::new(map));
+        let mut compiler = Compiler::new(resolver);
+
+        compiler.compile_module("a")
+    }
+
+    #[test]
+    fn test_instantiate_imported_struct() {
+        let result = compile_shapes_import("
+            import { Circle, area } from 'shapes';
+            fn unit_area() -> f32 { let circle = Circle { radius: 1.0, }; return area(circle); }
+        ");
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_declarations_not_imported_are_unknown() {
+        let result = compile_shapes_import("
+            import { Circle } from 'shapes';
+            fn side() -> f32 { let square = Square { side: 1.0, }; return square.side; }
+        ");
+
+        assert_type_error(result, TypeErrorKind::TypeNotFound("Square".to_owned()));
+    }
+
+    #[test]
+    fn test_import_whole_module() {
+        let result = compile_shapes_import("
+            import \"shapes\";
+            fn unit_area() -> f32 { let circle = Circle { radius: 1.0, }; return area(circle); }
+        ");
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_whole_module_import_from_missing_module() {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), "import \"trig\";".to_string());
+        let resolver = Box::new(TestResolver::new(map));
+        let mut compiler = Compiler::new(resolver);
+
+        assert_type_error(compiler.compile_module("a"), TypeErrorKind::UnresolvedImport("trig".to_owned()));
+    }
+
+    #[test]
+    fn test_reference_to_unexported_declaration() {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), "
+            import \"shapes\";
+            fn side() -> f32 { let square = Square { side: 1.0, }; return square.side; }
+        ".to_string());
+        map.insert("shapes".to_string(), "
+            export { Circle };
+            struct Circle { radius: f32, }
+            struct Square { side: f32, }
+        ".to_string());
+        let resolver = Box::new(TestResolver::new(map));
+        let mut compiler = Compiler::new(resolver);
+
+        assert_type_error(compiler.compile_module("a"), TypeErrorKind::UnknownImportedSymbol("Square".to_owned(), "shapes".to_owned()));
+    }
+
+    #[test]
+    fn test_import_private_symbol() {
+        assert_type_error(compile_math_import("import { reduce } from 'math';"), TypeErrorKind::SymbolNotVisible("reduce".to_owned()));
+    }
 }
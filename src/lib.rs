@@ -16,6 +16,10 @@ mod compile_error;
 mod ast;
 mod parser;
 mod string_builder;
+mod pretty_printer;
+mod renaming;
+pub mod transform;
+pub mod codegen;
 
 #[cfg(test)]
 mod testing;
@@ -24,4 +28,15 @@ pub use ::ast::*;
 
 pub use compile_error::{ CompileError, ErrorKind as CompileErrorKind };
 pub use type_system::error::{ TypeError, ErrorKind as TypeErrorKind };
-pub use compiler::{ Compiler, ModuleResolver };
+pub use compiler::{ Compiler, Compilation, ModuleResolver };
+pub use module::{ Module, CheckState, Manifest, SamplerBinding };
+pub use passes::ast::type_checking::{ TypeCheckerOptions, LintOptions, PrimitiveValidator };
+pub use passes::results::LintLevel;
+pub use parser::PrecedenceTable;
+pub use pretty_printer::print_expression;
+pub use renaming::rename_reserved;
+pub use type_system::const_eval::{ ConstValue, eval_const, evaluate_constants };
+pub use type_system::layout::{ LayoutRules, Layout };
+pub use type_system::type_environment::TypeEnvironment;
+pub use codegen::CodegenError;
+pub use codegen::glsl::glsl_renames;
@@ -1,5 +1,39 @@
+use ::std::hash::{ Hash, Hasher };
 use ::ast::*;
 use ::compile_error::CompileError;
+use ::passes::Pass;
+use ::passes::ast::AstWalker;
+use ::passes::results::PassResultReference;
+use ::passes::ast::type_checking::{ TypeChecker, TypeCheckerOptions };
+use ::type_system::symbol_table::SymbolTableReference;
+use ::type_system::error::{ TypeError, ErrorKind };
+
+/// progress of type checking a module
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum CheckState {
+    /// parsed only, type references in the AST are `None`
+    Unchecked,
+    /// type checking reported errors, type references in the AST may be unresolved
+    Failed,
+    /// type checking succeeded, all type references in the AST are resolved
+    Checked,
+}
+
+/// sampler a module declares, samplers are bound in declaration order
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SamplerBinding {
+    pub name: String,
+    pub type_name: String,
+    pub binding: usize,
+}
+
+/// external dependencies of a module, so build systems can prepare them before compiling
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Manifest {
+    /// ids of the imported modules, in import order without duplicates
+    pub imports: Vec<String>,
+    pub samplers: Vec<SamplerBinding>,
+}
 
 #[derive(Debug)]
 pub struct Module {
@@ -7,7 +41,14 @@ pub struct Module {
     source: String,
     ast: Vec<ItemKind>,
     is_core_module: bool,
+    /// signatures only, function bodies are empty and not type checked
+    is_interface: bool,
     error: Option<CompileError>,
+    check_state: CheckState,
+    /// names type checking failed to resolve, with the span of their use
+    unresolved_names: Vec<(String, Span)>,
+    /// the symbol table of a successful type check, the type references in the AST point into it
+    symbol_table: Option<SymbolTableReference>,
 }
 
 impl Module {
@@ -18,10 +59,18 @@ impl Module {
             source: source,
             ast: ast,
             is_core_module: is_core_module,
+            is_interface: false,
             error: None,
+            check_state: CheckState::Unchecked,
+            unresolved_names: Vec::new(),
+            symbol_table: None,
         }
     }
 
+    pub fn get_ast(&self) -> &[ItemKind] {
+        &self.ast
+    }
+
     pub fn get_ast_mut(&mut self) -> &mut Vec<ItemKind> {
         &mut self.ast
     }
@@ -53,6 +102,167 @@ impl Module {
         self.is_core_module
     }
 
+    pub fn is_interface(&self) -> bool {
+        self.is_interface
+    }
+
+    pub fn check_state(&self) -> CheckState {
+        self.check_state
+    }
+
+    /// whether type checking succeeded, only then the type references in the AST may be read
+    pub fn is_checked(&self) -> bool {
+        self.check_state == CheckState::Checked
+    }
+
+    /// the symbol table the module was checked with, `None` unless type checking succeeded
+    /// transforms and backends look up the types of the AST in it
+    pub fn get_symbol_table(&self) -> Option<&SymbolTableReference> {
+        self.symbol_table.as_ref()
+    }
+
+    /// runs the type checker with the default options over the AST, errors are reported to `result`
+    pub fn type_check(&mut self, symbol_table: SymbolTableReference, result: PassResultReference) {
+        self.type_check_with_options(symbol_table, result, &TypeCheckerOptions::new());
+    }
+
+    /// runs the type checker over the AST, errors are reported to `result`
+    /// function bodies of interface modules are never checked
+    pub fn type_check_with_options(&mut self, symbol_table: SymbolTableReference, result: PassResultReference, options: &TypeCheckerOptions) {
+        let error_count = result.borrow().get_errors().len();
+
+        let mut options = options.clone();
+        if self.is_interface {
+            options.check_function_bodies = false;
+        }
+        let mut type_checker = TypeChecker::with_options(symbol_table.clone(), result.clone(), self.is_core_module, options);
+        type_checker.execute(&mut self.ast);
+
+        self.unresolved_names = result.borrow().get_errors()[error_count..].iter()
+            .filter_map(|e| e.downcast_ref::<TypeError>())
+            .filter_map(|e| match *e.get_kind() {
+                ErrorKind::TypeNotFound(ref name) | ErrorKind::VariableNotFound(ref name) => Some((name.to_owned(), e.get_span())),
+                _ => None,
+            })
+            .collect();
+
+        if result.borrow().get_errors().len() > error_count {
+            self.check_state = CheckState::Failed;
+            self.symbol_table = None;
+        } else {
+            self.check_state = CheckState::Checked;
+            self.symbol_table = Some(symbol_table);
+        }
+    }
+
+    /// unknown types, variables and functions found by the last type check, e.g. for editors to underline them all
+    pub fn unresolved_names(&self) -> &[(String, Span)] {
+        &self.unresolved_names
+    }
+
+    pub fn manifest(&self) -> Manifest {
+        let mut imports: Vec<String> = Vec::new();
+        for import in self.find_imports() {
+            if !imports.contains(&import.module_id) {
+                imports.push(import.module_id.to_owned());
+            }
+        }
+
+        let samplers = self.find_constants().iter()
+            .filter(|c| c.constant_variant == ConstantVariant::Sampler)
+            .enumerate()
+            .map(|(binding, c)| SamplerBinding {
+                name: c.constant_name.name.to_owned(),
+                type_name: c.constant_type_name.name.to_owned(),
+                binding: binding,
+            })
+            .collect();
+
+        Manifest {
+            imports: imports,
+            samplers: samplers,
+        }
+    }
+
+    /// names of the declarations the `stage_name` stages of the programs use, directly or through other declarations
+    pub fn stage_dependencies(&self, stage_name: &str) -> Vec<String> {
+        let declarations: Vec<(&Identifier, Vec<String>)> = self.ast.iter().filter_map(declaration_dependencies).collect();
+        let mut pending: Vec<String> = self.find_programs().iter()
+            .flat_map(|p| p.program_stages.iter())
+            .filter(|s| s.stage_name.name == stage_name)
+            .flat_map(|s| function_dependencies(&s.function))
+            .collect();
+
+        let mut used: Vec<String> = Vec::new();
+        while let Some(name) = pending.pop() {
+            if used.contains(&name) {
+                continue;
+            }
+
+            // names not declared here are primitives or imported
+            if let Some((_, dependencies)) = declarations.iter().find(|(declaration, _)| declaration.name == name) {
+                pending.extend(dependencies.iter().cloned());
+                used.push(name);
+            }
+        }
+
+        used
+    }
+
+    /// copy of this module with `ast` as its AST, which has to be a subset of this module's AST
+    /// the check state is kept, so transformations of checked modules can be passed to the backends
+    pub fn with_ast(&self, ast: Vec<ItemKind>) -> Module {
+        Module {
+            path: self.path.to_owned(),
+            source: self.source.to_owned(),
+            ast: ast,
+            is_core_module: self.is_core_module,
+            is_interface: self.is_interface,
+            error: None,
+            check_state: self.check_state,
+            unresolved_names: Vec::new(),
+            symbol_table: self.symbol_table.as_ref().map(|symbol_table| symbol_table.clone()),
+        }
+    }
+
+    /// target extensions required by `entry` and every function of this module it calls, in call order
+    /// backends emit them e.g. as `#extension` lines
+    pub fn required_extensions(&self, entry: &str) -> Vec<String> {
+        let functions = self.find_functions();
+        let mut visited: Vec<&str> = Vec::new();
+        let mut pending = vec![entry.to_owned()];
+        let mut extensions: Vec<String> = Vec::new();
+
+        let aliases = self.find_function_aliases();
+
+        while let Some(name) = pending.pop() {
+            if let Some(alias) = aliases.iter().find(|a| a.alias_name.name == name) {
+                pending.push(alias.target_name.name.to_owned());
+                continue;
+            }
+
+            let function = match functions.iter().find(|f| f.function_name.name == name) {
+                Some(f) => f,
+                None => continue,
+            };
+            if visited.contains(&function.function_name.name.as_str()) {
+                continue;
+            }
+            visited.push(&function.function_name.name);
+
+            for extension in &function.required_extensions {
+                if !extensions.contains(extension) {
+                    extensions.push(extension.to_owned());
+                }
+            }
+
+            // reversed so callees are visited in the order they are called
+            pending.extend(function_dependencies(function).into_iter().rev());
+        }
+
+        extensions
+    }
+
     pub fn find_imports(&self) -> Vec<&ImportDefinition> {
         let mut imports = Vec::new();
         for item in &self.ast {
@@ -163,6 +373,17 @@ impl Module {
         functions
     }
 
+    pub fn find_function_aliases(&self) -> Vec<&FunctionAliasDeclaration> {
+        let mut aliases = Vec::new();
+        for item in &self.ast {
+            match item {
+                &ItemKind::FunctionAlias(ref a) => aliases.push(a),
+                _ => (),
+            }
+        }
+        aliases
+    }
+
     pub fn find_primitives(&self) -> Vec<&PrimitiveDeclaration> {
         let mut primitives = Vec::new();
         for item in &self.ast {
@@ -228,4 +449,540 @@ impl Module {
         }
         casts
     }
+
+    pub fn find_operator_precedences(&self) -> Vec<&OperatorPrecedenceDeclaration> {
+        let mut precedences = Vec::new();
+        for item in &self.ast {
+            match item {
+                &ItemKind::OperatorPrecedence(ref p) => precedences.push(p),
+                _ => (),
+            }
+        }
+        precedences
+    }
+
+    /// names brought into scope by `import_definition`, which has to import from this module
+    /// named items have to be exported, `*` imports everything exported
+    /// modules without `export` declarations export all their declarations
+    pub fn resolve_import(&self, import_definition: &ImportDefinition) -> Result<Vec<String>, TypeError> {
+        let exports: Vec<&ExportItem> = self.find_exports().into_iter().flat_map(|e| e.items.iter()).collect();
+        let exports_all = exports.is_empty() || exports.iter().any(|&e| *e == ImportItem::All);
+        let declarations: Vec<&Identifier> = self.ast.iter().filter_map(|item| declaration_dependencies(item).map(|(name, _)| name)).collect();
+
+        let mut names = Vec::new();
+        for item in &import_definition.items {
+            match *item {
+                ImportItem::All if exports_all => names.extend(declarations.iter().map(|d| d.name.to_owned())),
+                ImportItem::All => names.extend(exports.iter().filter_map(|&e| match *e {
+                    ImportItem::Named(ref name) => Some(name.name.to_owned()),
+                    ImportItem::All => None,
+                })),
+                ImportItem::Named(ref name) => {
+                    let is_exported = exports_all || exports.iter().any(|&e| match *e {
+                        ImportItem::Named(ref export) => export.name == name.name,
+                        ImportItem::All => false,
+                    });
+
+                    if !declarations.iter().any(|d| d.name == name.name) {
+                        return Err(TypeError::new(name.span, ErrorKind::UnresolvedPath(name.name.to_owned())));
+                    }
+                    if !is_exported {
+                        return Err(TypeError::new(name.span, ErrorKind::SymbolNotVisible(name.name.to_owned())));
+                    }
+                    names.push(name.name.to_owned());
+                },
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// names of the declarations importers of this module can't use, because its `export` declarations leave them out
+    pub fn unexported_names(&self) -> Vec<String> {
+        let export_all = ImportDefinition {
+            span: Span::empty(),
+            items: vec![ImportItem::All],
+            module_id: self.path.to_owned(),
+        };
+        let exported = self.resolve_import(&export_all).unwrap_or_default();
+
+        self.ast.iter()
+            .filter_map(|item| declaration_dependencies(item).map(|(name, _)| name.name.to_owned()))
+            .filter(|name| !exported.contains(name))
+            .collect()
+    }
+
+    /// interface of this module for separate compilation, importers can be type checked against it without the implementation
+    /// keeps declarations other than functions and programs, exported functions are kept with empty bodies
+    pub fn to_interface(&self) -> Module {
+        let export_all = ImportDefinition {
+            span: Span::empty(),
+            items: vec![ImportItem::All],
+            module_id: self.path.to_owned(),
+        };
+        // importing everything can't name an unresolved or hidden item
+        let exported = self.resolve_import(&export_all).unwrap_or_default();
+
+        let ast = self.ast.iter().filter_map(|item| match *item {
+            ItemKind::Function(ref f) if exported.contains(&f.function_name.name) => {
+                let mut f = f.clone();
+                f.block.statements.clear();
+                Some(ItemKind::Function(f))
+            },
+            ItemKind::Function(_) | ItemKind::Program(_) => None,
+            ref item => Some(item.clone()),
+        }).collect();
+
+        Module {
+            path: self.path.to_owned(),
+            source: self.source.to_owned(),
+            ast: ast,
+            is_core_module: self.is_core_module,
+            is_interface: true,
+            error: None,
+            check_state: CheckState::Unchecked,
+            unresolved_names: Vec::new(),
+            symbol_table: None,
+        }
+    }
+
+    /// names of all declarations, ordered so every declaration comes after the declarations it references
+    /// declarations keep their source order where no dependency requires otherwise
+    pub fn topological_order(&self) -> Result<Vec<String>, TypeError> {
+        let declarations: Vec<(&Identifier, Vec<String>)> = self.ast.iter().filter_map(declaration_dependencies).collect();
+        let mut order = Vec::new();
+        let mut path = Vec::new();
+
+        for &(name, _) in &declarations {
+            visit_declaration(&name.name, &declarations, &mut path, &mut order)?;
+        }
+
+        Ok(order)
+    }
+
+    /// hash of the declarations, types and bodies of this module, independent of formatting and source positions
+    /// stable across runs and platforms, so build systems can persist it to skip unchanged modules
+    /// it follows the derived `Hash` of the AST, so compiler releases changing the AST may change it, which only costs a rebuild
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = StableHasher::new();
+        self.is_core_module.hash(&mut hasher);
+        self.is_interface.hash(&mut hasher);
+        self.ast.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// 64 bit FNV-1a, unlike `DefaultHasher` its output is specified and won't change between releases
+/// integers are written little endian and sizes as 64 bit, so the hash doesn't depend on the platform
+struct StableHasher {
+    state: u64,
+}
+
+impl StableHasher {
+    fn new() -> StableHasher {
+        StableHasher {
+            state: 0xcbf2_9ce4_8422_2325,
+        }
+    }
+}
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= u64::from(*byte);
+            self.state = self.state.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_i16(&mut self, value: i16) {
+        self.write_u16(value as u16);
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write_u32(value as u32);
+    }
+
+    fn write_i64(&mut self, value: i64) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_isize(&mut self, value: isize) {
+        self.write_i64(value as i64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+fn visit_declaration(name: &str, declarations: &[(&Identifier, Vec<String>)], path: &mut Vec<String>, order: &mut Vec<String>) -> Result<(), TypeError> {
+    if order.iter().any(|n| n == name) {
+        return Ok(());
+    }
+
+    // references to names not declared in this module, e.g. imports, impose no order
+    let &(identifier, ref dependencies) = match declarations.iter().find(|&&(n, _)| n.name == name) {
+        Some(declaration) => declaration,
+        None => return Ok(()),
+    };
+
+    if let Some(position) = path.iter().position(|n| n == name) {
+        let mut cycle = path[position..].to_vec();
+        cycle.push(name.to_owned());
+        return Err(TypeError::new(identifier.span, ErrorKind::CyclicDependency(cycle)));
+    }
+
+    path.push(name.to_owned());
+    for dependency in dependencies {
+        visit_declaration(dependency, declarations, path, order)?;
+    }
+    path.pop();
+
+    order.push(name.to_owned());
+    Ok(())
+}
+
+fn declaration_dependencies(item: &ItemKind) -> Option<(&Identifier, Vec<String>)> {
+    match *item {
+        ItemKind::Struct(ref s) => Some((&s.struct_name, s.base_struct_name.iter().chain(s.struct_member.iter().map(|m| &m.struct_member_type_name)).map(|n| n.name.to_owned()).collect())),
+        ItemKind::Constant(ref c) => {
+            let mut collector = DependencyCollector::new();
+            collector.dependencies.push(c.constant_type_name.name.to_owned());
+            collector.visit_constant(&mut c.clone());
+            Some((&c.constant_name, collector.dependencies))
+        },
+        ItemKind::Primitive(ref p) => Some((&p.type_name, Vec::new())),
+        ItemKind::Enum(ref e) => Some((&e.enum_name, Vec::new())),
+        ItemKind::Function(ref f) => Some((&f.function_name, function_dependencies(f))),
+        ItemKind::FunctionAlias(ref a) => Some((&a.alias_name, vec![a.target_name.name.to_owned()])),
+        ItemKind::Program(ref p) => {
+            let mut dependencies = Vec::new();
+            for stage in &p.program_stages {
+                dependencies.extend(function_dependencies(&stage.function));
+            }
+            Some((&p.program_name, dependencies))
+        },
+        _ => None,
+    }
+}
+
+fn function_dependencies(function_declaration: &FunctionDeclaration) -> Vec<String> {
+    let mut collector = DependencyCollector::new();
+    collector.visit_function(&mut function_declaration.clone());
+
+    // arguments and locals shadow module level declarations
+    let DependencyCollector { mut dependencies, locals } = collector;
+    dependencies.retain(|d| !locals.contains(d));
+    dependencies
+}
+
+/// collects the names a function or constant references, in the order they appear
+struct DependencyCollector {
+    dependencies: Vec<String>,
+    /// arguments and locals declared by the visited function
+    locals: Vec<String>,
+}
+
+impl DependencyCollector {
+    fn new() -> DependencyCollector {
+        DependencyCollector {
+            dependencies: Vec::new(),
+            locals: Vec::new(),
+        }
+    }
+}
+
+impl AstWalker for DependencyCollector {
+    fn visit_function(&mut self, function_definition: &mut FunctionDeclaration) {
+        for argument in function_definition.arguments.iter() {
+            self.dependencies.push(argument.argument_type_name.name.to_owned());
+            self.locals.push(argument.argument_name.name.to_owned());
+        }
+        self.dependencies.push(function_definition.return_type_name.name.to_owned());
+
+        self.visit_block(&mut function_definition.block);
+    }
+
+    fn visit_local_statement(&mut self, local_statement: &mut LocalDeclaration) {
+        if let Some(ref type_name) = local_statement.local_type_name {
+            self.dependencies.push(type_name.name.to_owned());
+        }
+        self.walk_local_statement(local_statement);
+        self.locals.push(local_statement.symbol_name.name.to_owned());
+    }
+
+    fn visit_const_statement(&mut self, const_statement: &mut LocalConstantDeclaration) {
+        self.dependencies.push(const_statement.constant_type_name.name.to_owned());
+        self.walk_const_statement(const_statement);
+        self.locals.push(const_statement.constant_name.name.to_owned());
+    }
+
+    fn visit_for_statement(&mut self, for_statement: &mut ForStatement) {
+        self.locals.push(for_statement.loop_variable.name.to_owned());
+        self.walk_for_statement(for_statement);
+    }
+
+    fn visit_match_statement(&mut self, match_statement: &mut MatchStatement) {
+        self.visit_expression(&mut match_statement.scrutinee);
+        for arm in match_statement.arms.iter_mut() {
+            if let MatchPattern::EnumVariant(ref v) = arm.pattern {
+                self.dependencies.push(v.enum_name.name.to_owned());
+            }
+            self.visit_block(&mut arm.block);
+        }
+    }
+
+    fn visit_call_expression(&mut self, call_expression: &mut CallExpression) {
+        self.dependencies.push(call_expression.function_name.name.to_owned());
+        self.dependencies.extend(call_expression.type_arguments.iter().map(|t| t.name.to_owned()));
+        self.walk_call_expression(call_expression);
+    }
+
+    fn visit_struct_instantiation_expression(&mut self, struct_instantiation_expression: &mut StructInstantiationExpression) {
+        self.dependencies.push(struct_instantiation_expression.struct_type_name.name.to_owned());
+        self.walk_struct_instantiation_expression(struct_instantiation_expression);
+    }
+
+    fn visit_enum_variant_expression(&mut self, enum_variant_expression: &mut EnumVariantExpression) {
+        self.dependencies.push(enum_variant_expression.enum_name.name.to_owned());
+    }
+
+    // variables may name module constants
+    fn visit_variable_expression(&mut self, variable_expression: &mut VariableExpression) {
+        self.dependencies.push(variable_expression.variable_name.name.to_owned());
+    }
+
+    fn visit_field_accessor_expression(&mut self, field_accessor_expression: &mut FieldAccessorExpression) {
+        self.dependencies.push(field_accessor_expression.variable_name.name.to_owned());
+    }
+
+    fn visit_index_accessor_expression(&mut self, index_accessor_expression: &mut IndexAccesorExpression) {
+        self.dependencies.push(index_accessor_expression.variable_name.name.to_owned());
+        self.walk_index_accessor_expression(index_accessor_expression);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::compiler::parse_core_module;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn module(code: &str) -> Module {
+        Module::new("test".to_owned(), code.to_owned(), compile_ast(code), false)
+    }
+
+    #[test]
+    fn dependencies_come_first() {
+        let module = module("struct B { a: A, } struct A { x: f32, } fn f(b: B) -> A { return g(b); } fn g(b: B) -> A { return A { x: 1.0, }; }");
+
+        assert_eq!(module.topological_order().unwrap(), vec!["A", "B", "g", "f"]);
+    }
+
+    #[test]
+    fn calls_within_operators_are_dependencies() {
+        let module = module("fn f(x: f32) -> f32 { return x * g(x); } fn g(x: f32) -> f32 { return x; }");
+
+        assert_eq!(module.topological_order().unwrap(), vec!["g", "f"]);
+    }
+
+    #[test]
+    fn mutually_recursive_structs_produce_an_error() {
+        let module = module("struct A { b: B, } struct B { a: A, }");
+
+        let error = module.topological_order().unwrap_err();
+        assert_eq!(*error.get_kind(), ErrorKind::CyclicDependency(vec!["A".to_owned(), "B".to_owned(), "A".to_owned()]));
+    }
+
+    #[test]
+    fn check_state() {
+        let code = "primitive type i32; fn f(a: i32) -> i32 { return a; }";
+        let mut module = Module::new("test".to_owned(), code.to_owned(), compile_ast(code), true);
+        assert_eq!(module.check_state(), CheckState::Unchecked);
+        assert!(!module.is_checked());
+
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        module.type_check(symbol_table, result);
+
+        assert_eq!(module.check_state(), CheckState::Checked);
+        assert!(module.is_checked());
+    }
+
+    #[test]
+    fn core_module_declares_bool() {
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut core = parse_core_module();
+        core.type_check(symbol_table.clone(), result.clone());
+
+        let mut module = module("fn f() -> bool { let a = false; return a; }");
+        module.type_check(symbol_table.clone(), result.clone());
+
+        assert!(module.is_checked());
+        let bool_type = symbol_table.borrow().find_type_ref("bool").unwrap();
+        assert!(symbol_table.borrow().find_type_or_err(bool_type).is_ok());
+    }
+
+    #[test]
+    fn core_module_declares_explicit_casts() {
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut core = parse_core_module();
+        core.type_check(symbol_table.clone(), result.clone());
+
+        let mut module = module("fn f(x: f32) -> i32 { return x as i32 + 1; }");
+        module.type_check(symbol_table.clone(), result.clone());
+
+        assert!(module.is_checked());
+    }
+
+    #[test]
+    fn check_state_with_errors() {
+        let mut module = module("fn f(a: Unknown) -> Unknown { return a; }");
+
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        module.type_check(symbol_table, result);
+
+        assert_eq!(module.check_state(), CheckState::Failed);
+        assert!(!module.is_checked());
+    }
+
+    #[test]
+    fn independent_errors_are_all_reported() {
+        let mut module = module("primitive type f32; struct A { a: Foo, b: Bar, } fn f(x: f32) -> Baz { return x; }");
+
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        module.type_check(symbol_table, result.clone());
+
+        assert_eq!(module.check_state(), CheckState::Failed);
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![
+            &ErrorKind::TypeNotFound("Foo".to_owned()),
+            &ErrorKind::TypeNotFound("Bar".to_owned()),
+            &ErrorKind::TypeNotFound("Baz".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn importers_type_check_against_the_interface() {
+        let shapes = module("export { Circle, area }; \
+            struct Circle { radius: f32, } \
+            fn square(x: f32) -> f32 { return x * x; } \
+            fn area(circle: Circle) -> f32 { return square(circle.radius) * 3.14; }");
+        let mut interface = shapes.to_interface();
+
+        assert!(interface.is_interface());
+        let functions = interface.find_functions();
+        assert_eq!(functions.iter().map(|f| f.function_name.name.as_str()).collect::<Vec<_>>(), vec!["area"]);
+        assert!(functions[0].block.statements.is_empty());
+
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut core = parse_core_module();
+        core.type_check(symbol_table.clone(), result.clone());
+        interface.type_check(symbol_table.clone(), result.clone());
+        assert!(interface.is_checked());
+
+        let mut main = module("import { Circle, area } from 'shapes'; fn main() -> f32 { return area(Circle { radius: 2.0, }); }");
+        main.type_check(symbol_table.clone(), result.clone());
+
+        assert!(main.is_checked());
+    }
+
+    #[test]
+    fn unresolved_names_are_reported_with_their_spans() {
+        let mut module = module("primitive type f32; fn f(a: f32) -> f32 { let b = a * scale; let c = Unit { x: b, }; return b; }");
+
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        module.type_check(symbol_table, result);
+
+        assert_eq!(module.unresolved_names(), &[
+            ("scale".to_owned(), Span::new(54, 5, 1, 55)),
+            ("Unit".to_owned(), Span::new(69, 4, 1, 70)),
+        ][..]);
+    }
+
+    #[test]
+    fn manifest_lists_imports_and_samplers() {
+        let module = module("import Light from 'lighting'; sampler albedo: Texture2D; fn f(a: f32) -> f32 { return a; }");
+
+        assert_eq!(module.manifest(), Manifest {
+            imports: vec!["lighting".to_owned()],
+            samplers: vec![
+                SamplerBinding {
+                    name: "albedo".to_owned(),
+                    type_name: "Texture2D".to_owned(),
+                    binding: 0,
+                },
+            ],
+        });
+    }
+
+    #[test]
+    fn required_extensions_follow_the_call_chain() {
+        let code = "primitive type i32; \
+            [extension(\"GL_EXT_a\")] fn leaf(a: i32) -> i32 { return a; } \
+            [extension(\"GL_EXT_b\", \"GL_EXT_a\")] fn middle(a: i32) -> i32 { return leaf(a); } \
+            [extension(\"GL_EXT_unused\")] fn unused(a: i32) -> i32 { return a; } \
+            fn main(a: i32) -> i32 { return middle(a); }";
+        let mut module = Module::new("test".to_owned(), code.to_owned(), compile_ast(code), true);
+
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        module.type_check(symbol_table, result);
+
+        assert!(module.is_checked());
+        assert_eq!(module.required_extensions("main"), vec!["GL_EXT_b", "GL_EXT_a"]);
+        assert_eq!(module.required_extensions("leaf"), vec!["GL_EXT_a"]);
+    }
+
+    #[test]
+    fn content_hash_ignores_formatting() {
+        let code = "primitive type i32; fn add(a: i32, b: i32) -> i32 { return a + b; }";
+        let reformatted = "primitive type i32;\n\nfn add(a: i32, b: i32) -> i32 {\n    return a + b;\n}\n";
+        let changed = "primitive type i32; fn add(a: i32, b: i32) -> i32 { return a - b; }";
+
+        let module = Module::new("test".to_owned(), code.to_owned(), compile_ast(code), true);
+        let reformatted_module = Module::new("test".to_owned(), reformatted.to_owned(), compile_ast(reformatted), true);
+        let changed_module = Module::new("test".to_owned(), changed.to_owned(), compile_ast(changed), true);
+
+        assert_eq!(module.content_hash(), reformatted_module.content_hash());
+        assert_ne!(module.content_hash(), changed_module.content_hash());
+    }
+
+    #[test]
+    fn stable_hasher_writes_fixed_width_integers() {
+        let mut size_hasher = StableHasher::new();
+        size_hasher.write_usize(1);
+        size_hasher.write_isize(-1);
+        let mut bytes_hasher = StableHasher::new();
+        bytes_hasher.write(&[1, 0, 0, 0, 0, 0, 0, 0]);
+        bytes_hasher.write(&[0xff; 8]);
+
+        assert_eq!(size_hasher.finish(), bytes_hasher.finish());
+    }
 }
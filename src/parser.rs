@@ -1,11 +1,74 @@
+use ::std::cell::RefCell;
+use ::std::collections::HashMap;
 use ::nom::*;
 use ::nom_locate::LocatedSpan;
 use ::ast::*;
 use ::compile_error::{ CompileError, CompileResult };
 use ::compile_error::ErrorKind as CompileErrorKind;
+use ::type_system::error::{ TypeError, ErrorKind, TypeCheckResult };
 
 type NomSpan<'a> = LocatedSpan<&'a str>;
 
+/// Binding strength and associativity of the infix operators
+/// defaults to `Operator::get_precedence` and left associativity, core modules can declare their own
+#[derive(Debug, Clone)]
+pub struct PrecedenceTable {
+    declarations: HashMap<Operator, (usize, Associativity)>,
+}
+
+impl PrecedenceTable {
+    pub fn new() -> PrecedenceTable {
+        PrecedenceTable {
+            declarations: HashMap::new(),
+        }
+    }
+
+    /// declaring an operator again with a different precedence or associativity is an error
+    pub fn from_declarations(declarations: &[&OperatorPrecedenceDeclaration]) -> TypeCheckResult<PrecedenceTable> {
+        let mut table = PrecedenceTable::new();
+        for declaration in declarations {
+            let declared = (declaration.precedence, declaration.associativity);
+            match table.declarations.insert(declaration.operator, declared) {
+                Some(previous) if previous != declared => {
+                    let symbol = declaration.operator.get_symbol().to_owned();
+                    return Err(TypeError::new(declaration.span, ErrorKind::ConflictingOperatorPrecedence(symbol)));
+                },
+                _ => (),
+            }
+        }
+
+        Ok(table)
+    }
+
+    pub fn get_precedence(&self, operator: Operator) -> usize {
+        self.declarations.get(&operator).map_or_else(|| operator.get_precedence(), |&(precedence, _)| precedence)
+    }
+
+    pub fn get_associativity(&self, operator: Operator) -> Associativity {
+        self.declarations.get(&operator).map_or(Associativity::Left, |&(_, associativity)| associativity)
+    }
+
+    /// whether the pending operator `left` has to be applied before `right` following it
+    pub fn binds_before(&self, left: Operator, right: Operator) -> bool {
+        let (left_precedence, right_precedence) = (self.get_precedence(left), self.get_precedence(right));
+        match self.get_associativity(right) {
+            Associativity::Left => left_precedence >= right_precedence,
+            Associativity::Right => left_precedence > right_precedence,
+        }
+    }
+}
+
+impl Default for PrecedenceTable {
+    fn default() -> PrecedenceTable {
+        PrecedenceTable::new()
+    }
+}
+
+thread_local! {
+    // the nom parsers can't carry state, so the table is swapped in for the duration of a parse
+    static PRECEDENCES: RefCell<PrecedenceTable> = RefCell::new(PrecedenceTable::new());
+}
+
 named!(parse_identifier<NomSpan, NomSpan>,
     recognize!(
         do_parse!(
@@ -31,6 +94,7 @@ named!(parse_constant<NomSpan, ItemKind>,
         constant_name: parse_symbol_declaration >>
         ws!(tag!(":")) >>
         constant_type_name: parse_type_declaration >>
+        expression: opt!(preceded!(ws!(tag!("=")), parse_expression)) >>
         to: ws!(tag!(";")) >>
         (ItemKind::Constant(ConstantDefinition{
             span: Span::from_to(Span::from_nom_span(&from), Span::from_nom_span(&to)),
@@ -38,6 +102,7 @@ named!(parse_constant<NomSpan, ItemKind>,
             constant_variant: ConstantVariant::Constant,
             constant_type_name: constant_type_name,
             constant_type: None,
+            expression: expression,
         }))
     )
 );
@@ -115,6 +180,24 @@ named!(parse_import<NomSpan, ItemKind>,
     )
 );
 
+// `import "path";` imports everything the module exports
+named!(parse_module_import<NomSpan, ItemKind>,
+    do_parse!(
+        from: ws!(tag!("import")) >>
+        tag!("\"") >>
+        module_id: parse_path >>
+        tag!("\"") >>
+        to: ws!(tag!(";")) >>
+        (ItemKind::Import(
+            ImportDefinition {
+                span: Span::from_to(Span::from_nom_span(&from), Span::from_nom_span(&to)),
+                items: vec![ImportItem::All],
+                module_id: module_id.fragment.to_owned(),
+            }
+        ))
+    )
+);
+
 named!(parse_export<NomSpan, ItemKind>,
     do_parse!(
         from: ws!(tag!("export")) >>
@@ -142,6 +225,7 @@ named!(parse_sampler<NomSpan, ItemKind>,
             constant_variant: ConstantVariant::Sampler,
             constant_type_name: sampler_type_name,
             constant_type: None,
+            expression: None,
         }))
     )
 );
@@ -164,8 +248,9 @@ named!(parse_program<NomSpan, ItemKind>,
 
 named!(parse_stage<NomSpan, ProgramStageDefinition>,    
     do_parse!(
+        attributes: many0!(parse_attribute) >>
         from: ws!(tag!("stage")) >>
-        stage_name: ws!(alt!(tag!("vertex") | tag!("fragment"))) >> 
+        stage_name: ws!(alt!(tag!("vertex") | tag!("fragment") | tag!("compute"))) >> 
         ws!(tag!("(")) >>
         arguments: ws!(separated_list!(tag!(","), parse_function_argument)) >>
         ws!(tag!(")")) >>
@@ -177,27 +262,38 @@ named!(parse_stage<NomSpan, ProgramStageDefinition>,
             stage_name: Identifier::from_nom_span(stage_name),
             function: FunctionDeclaration {
                 span: Span::from_to(Span::from_nom_span(&from), block.span),
+                attributes: attributes,
                 function_name: Identifier::from_nom_span(stage_name),
                 arguments: arguments,
                 block: block,
                 return_type_name: return_type_name,
                 return_type: None,
                 declaring_type: None,
+                inline_hint: None,
+                is_const: false,
+                required_extensions: Vec::new(),
+                debug_name: None,
             },
             declaring_type: None,
+            workgroup_size: None,
+            early_fragment_tests: false,
         })       
     )
 );
 
 named!(parse_struct_member<NomSpan, StructMemberDefinition>,
     do_parse!(
+        attributes: many0!(parse_attribute) >>
         struct_member_name: parse_symbol_declaration >>
         ws!(tag!(":")) >>
         struct_member_type_name: parse_type_declaration >>
+        default_value: opt!(preceded!(ws!(tag!("=")), parse_expression)) >>
         (StructMemberDefinition{
             span: Span::from_to(struct_member_name.span, struct_member_type_name.span),
+            attributes: attributes,
             struct_member_name: struct_member_name,
             struct_member_type_name: struct_member_type_name,
+            default_value: default_value,
             struct_member_type: None,
         })
     )
@@ -205,16 +301,38 @@ named!(parse_struct_member<NomSpan, StructMemberDefinition>,
 
 named!(parse_struct<NomSpan, ItemKind>,
     do_parse!(
+        attributes: many0!(parse_attribute) >>
         from: ws!(tag!("struct")) >>
         struct_name: parse_symbol_declaration >>
+        base_struct_name: opt!(preceded!(ws!(tag!(":")), parse_type_declaration)) >>
         ws!(tag!("{")) >>
         member: ws!(separated_list!(tag!(","), parse_struct_member)) >>
         opt!(ws!(tag!(","))) >>
         to: ws!(tag!("}")) >>
         (ItemKind::Struct(StructDefinition{
             span: Span::from_to(Span::from_nom_span(&from), Span::from_nom_span(&to)),
+            attributes: attributes,
             struct_name: struct_name,
+            base_struct_name: base_struct_name,
             struct_member: member,
+            base_struct_type: None,
+            declaring_type: None,
+        }))
+    )
+);
+
+named!(parse_enum<NomSpan, ItemKind>,
+    do_parse!(
+        from: ws!(tag!("enum")) >>
+        enum_name: parse_symbol_declaration >>
+        ws!(tag!("{")) >>
+        variants: ws!(separated_list!(tag!(","), parse_symbol_declaration)) >>
+        opt!(ws!(tag!(","))) >>
+        to: ws!(tag!("}")) >>
+        (ItemKind::Enum(EnumDefinition{
+            span: Span::from_to(Span::from_nom_span(&from), Span::from_nom_span(&to)),
+            enum_name: enum_name,
+            variants: variants,
             declaring_type: None,
         }))
     )
@@ -222,11 +340,16 @@ named!(parse_struct<NomSpan, ItemKind>,
 
 named!(parse_function_argument<NomSpan, FunctionArgumentDeclaration>,
     do_parse!(
+        attributes: many0!(parse_attribute) >>
+        // the required whitespace keeps names starting with `out` e.g. `output` from being read as the qualifier
+        is_out: opt!(preceded!(opt!(multispace), terminated!(tag!("out"), multispace))) >>
         argument_name: parse_symbol_declaration >>
         ws!(tag!(":")) >>
         argument_type_name: parse_type_declaration >>
         (FunctionArgumentDeclaration{
             span: Span::from_to(argument_name.span, argument_type_name.span),
+            attributes: attributes,
+            is_out: is_out.is_some(),
             argument_name: argument_name,
             argument_type_name: argument_type_name,
             argument_type: None,
@@ -268,11 +391,33 @@ named!(parse_struct_instantiation<NomSpan, ExpressionStatement>,
         ws!(tag!("{")) >>
         struct_field_initializer: ws!(separated_list!(tag!(","), parse_struct_instantiation_field_initializer)) >>
         opt!(ws!(tag!(","))) >>
+        base: opt!(do_parse!(ws!(tag!("..")) >> base: parse_expression >> (base))) >>
+        opt!(ws!(tag!(","))) >>
         to: ws!(tag!("}")) >>
         (ExpressionStatement::StructInstantiation(StructInstantiationExpression{
             span: Span::from_to(struct_type_name.span, Span::from_nom_span(&to)),
             struct_type_name: struct_type_name,
             struct_field_initializer: struct_field_initializer,
+            uses_defaults: false,
+            base: base.map(Box::new),
+            struct_type: None,
+        }))
+    )
+);
+
+named!(parse_default_constructor<NomSpan, ExpressionStatement>,
+    do_parse!(
+        struct_type_name: parse_type_declaration >>
+        tag!("::") >>
+        ws!(tag!("default")) >>
+        ws!(tag!("(")) >>
+        to: ws!(tag!(")")) >>
+        (ExpressionStatement::StructInstantiation(StructInstantiationExpression{
+            span: Span::from_to(struct_type_name.span, Span::from_nom_span(&to)),
+            struct_type_name: struct_type_name,
+            struct_field_initializer: Vec::new(),
+            uses_defaults: true,
+            base: None,
             struct_type: None,
         }))
     )
@@ -301,6 +446,19 @@ fn parse_float_literal(before: NomSpan, after: NomSpan) -> ExpressionStatement {
     })
 }
 
+// `true` and `false` are parsed as identifiers so names starting with them like `trueColor` remain variables
+fn parse_bool_literal(value: NomSpan) -> Option<ExpressionStatement> {
+    match value.fragment {
+        "true" | "false" => Some(ExpressionStatement::Literal(LiteralExpression {
+            span: Span::from_nom_span(&value),
+            value: value.fragment.to_string(),
+            literal_expression_type: LiteralType::Bool,
+            literal_type: None,
+        })),
+        _ => None,
+    }
+}
+
 named!(parse_float_literal_expression<NomSpan, ExpressionStatement>,
     do_parse!(
         before: ws!(parse_number) >>
@@ -317,26 +475,121 @@ named!(parse_int_literal_expression<NomSpan, ExpressionStatement>,
     )
 );
 
+named!(parse_bool_literal_expression<NomSpan, ExpressionStatement>,
+    map_opt!(ws!(parse_identifier), parse_bool_literal)
+);
+
+named!(parse_string_literal_expression<NomSpan, ExpressionStatement>,
+    do_parse!(
+        ws!(tag!("\"")) >>
+        value: take_until!("\"") >>
+        tag!("\"") >>
+        (ExpressionStatement::Literal(LiteralExpression {
+            span: Span::from_nom_span(&value),
+            value: value.fragment.to_string(),
+            literal_expression_type: LiteralType::String,
+            literal_type: None,
+        }))
+    )
+);
+
 // TODO more literals
 named!(parse_literal_expression<NomSpan, ExpressionStatement>,
     alt!(
         parse_float_literal_expression |
-        parse_int_literal_expression
+        parse_int_literal_expression |
+        parse_bool_literal_expression |
+        parse_string_literal_expression
     )
 );
 
+named!(parse_infix_operator<NomSpan, Operator>,
+    ws!(
+        alt!(
+            value!(Operator::Equal, tag!("==")) |
+            value!(Operator::NotEqual, tag!("!=")) |
+            value!(Operator::LessEqual, tag!("<=")) |
+            value!(Operator::GreaterEqual, tag!(">=")) |
+            map!(one_of!("+-*/<>"), char_to_operator)
+        )
+    )
+);
+
+fn build_infix_expression(left: ExpressionStatement, operator: Operator, right: ExpressionStatement) -> ExpressionStatement {
+    ExpressionStatement::Infix(InfixExpression{
+        span: Span::from_to(left.get_span(), right.get_span()),
+        operator: operator,
+        left_hand: Box::new(left),
+        right_hand: Box::new(right),
+        infix_type: None,
+    })
+}
+
+// folds a flat list of operands and operators into a tree according to operator precedence
+fn fold_infix_expression(first: ExpressionStatement, rest: Vec<(Operator, ExpressionStatement)>) -> ExpressionStatement {
+    let mut operands = vec![first];
+    let mut operators: Vec<Operator> = Vec::new();
+
+    for (operator, operand) in rest {
+        while operators.last().map_or(false, |&top| PRECEDENCES.with(|p| p.borrow().binds_before(top, operator))) {
+            let right = operands.pop().unwrap();
+            let left = operands.pop().unwrap();
+            operands.push(build_infix_expression(left, operators.pop().unwrap(), right));
+        }
+
+        operators.push(operator);
+        operands.push(operand);
+    }
+
+    while let Some(operator) = operators.pop() {
+        let right = operands.pop().unwrap();
+        let left = operands.pop().unwrap();
+        operands.push(build_infix_expression(left, operator, right));
+    }
+
+    operands.pop().unwrap()
+}
+
 named!(parse_infix_expression<NomSpan, ExpressionStatement>,
     do_parse!(
-        left: parse_expression_no_left_recursion >>
-        operator: ws!(one_of!("+-*/")) >>
-        right: parse_expression >>
-        (ExpressionStatement::Infix(InfixExpression{
-            span: Span::from_to(left.get_span(), right.get_span()),
-            operator: char_to_operator(operator),
-            left_hand: Box::new(left),
-            right_hand: Box::new(right),
-            infix_type: None,
-        }))
+        first: parse_operand_expression >>
+        rest: many1!(pair!(parse_infix_operator, parse_operand_expression)) >>
+        (fold_infix_expression(first, rest))
+    )
+);
+
+fn build_prefix_expression(from: NomSpan, operator: PrefixOperator, expression: ExpressionStatement) -> ExpressionStatement {
+    ExpressionStatement::Prefix(PrefixExpression{
+        span: Span::from_to(Span::from_nom_span(&from), expression.get_span()),
+        operator: operator,
+        expression: Box::new(expression),
+        prefix_type: None,
+    })
+}
+
+named!(parse_prefix_operator<NomSpan, (NomSpan, PrefixOperator)>,
+    ws!(
+        alt!(
+            map!(tag!("-"), |from| (from, PrefixOperator::Negate)) |
+            map!(tag!("!"), |from| (from, PrefixOperator::Not))
+        )
+    )
+);
+
+// prefix operators bind tighter than any infix operator, `-a * b` is `(-a) * b`
+named!(parse_prefix_expression<NomSpan, ExpressionStatement>,
+    do_parse!(
+        operator: parse_prefix_operator >>
+        expression: parse_primary_expression >>
+        (build_prefix_expression(operator.0, operator.1, expression))
+    )
+);
+
+named!(parse_condition_prefix_expression<NomSpan, ExpressionStatement>,
+    do_parse!(
+        operator: parse_prefix_operator >>
+        expression: parse_condition_primary_expression >>
+        (build_prefix_expression(operator.0, operator.1, expression))
     )
 );
 
@@ -358,21 +611,77 @@ named!(parse_call_expression<NomSpan, ExpressionStatement>,
     )
 );
 
+// `sizeof(T)` and `alignof(T)` take a type, which is passed as type argument like `sizeof<T>()`
+named!(parse_layout_query_expression<NomSpan, ExpressionStatement>,
+    do_parse!(
+        function_name: map!(ws!(alt!(tag!("sizeof") | tag!("alignof"))), Identifier::from_nom_span) >>
+        ws!(tag!("(")) >>
+        type_name: parse_type_declaration >>
+        to: ws!(tag!(")")) >>
+        (ExpressionStatement::Call(CallExpression {
+            span: Span::from_to(function_name.span, Span::from_nom_span(&to)),
+            function_name: function_name,
+            type_arguments: vec![type_name],
+            arguments: Vec::new(),
+            function_type: None,
+        }))
+    )
+);
+
 named!(parse_call<NomSpan, CallExpression>,
     do_parse!(
         function_name: parse_symbol_declaration >>
+        type_arguments: opt!(delimited!(
+            ws!(tag!("<")),
+            ws!(separated_list!(tag!(","), parse_type_declaration)),
+            ws!(tag!(">"))
+        )) >>
         ws!(tag!("(")) >>
         arguments: ws!(separated_list!(tag!(","), parse_expression)) >>
         to: ws!(tag!(")")) >>
         (CallExpression {
             span: Span::from_to(function_name.span, Span::from_nom_span(&to)),
             function_name: function_name,
+            type_arguments: type_arguments.unwrap_or_default(),
             arguments: arguments,
             function_type: None,
         })
     )
 );
 
+named!(parse_enum_variant<NomSpan, EnumVariantExpression>,
+    do_parse!(
+        enum_name: parse_type_declaration >>
+        tag!("::") >>
+        variant_name: parse_symbol_declaration >>
+        (EnumVariantExpression{
+            span: Span::from_to(enum_name.span, variant_name.span),
+            enum_name: enum_name,
+            variant_name: variant_name,
+            variant_type: None,
+        })
+    )
+);
+
+named!(parse_enum_variant_expression<NomSpan, ExpressionStatement>,
+    map!(parse_enum_variant, ExpressionStatement::EnumVariant)
+);
+
+named!(parse_index_accessor_expression<NomSpan, ExpressionStatement>,
+    do_parse!(
+        variable_name: parse_symbol_declaration >>
+        ws!(tag!("[")) >>
+        access_expression: parse_expression >>
+        to: ws!(tag!("]")) >>
+        (ExpressionStatement::IndexAccessor(IndexAccesorExpression{
+            span: Span::from_to(variable_name.span, Span::from_nom_span(&to)),
+            variable_name: variable_name,
+            access_expression: Box::new(access_expression),
+            element_type: None,
+        }))
+    )
+);
+
 // TODO nested accessor expressions like `a.b.c`
 named!(parse_field_accessor_expression<NomSpan, ExpressionStatement>,
     do_parse!(
@@ -388,40 +697,139 @@ named!(parse_field_accessor_expression<NomSpan, ExpressionStatement>,
     )
 );
 
-named!(parse_expression_no_left_recursion<NomSpan, ExpressionStatement>,
+named!(parse_parenthesized_expression<NomSpan, ExpressionStatement>,
+    do_parse!(
+        ws!(tag!("(")) >>
+        expression: parse_expression >>
+        ws!(tag!(")")) >>
+        (expression)
+    )
+);
+
+named!(parse_primary_expression<NomSpan, ExpressionStatement>,
     alt!(
+        parse_parenthesized_expression |
+        parse_prefix_expression |
         parse_struct_instantiation |
         parse_literal_expression |
+        parse_default_constructor |
+        parse_enum_variant_expression |
         parse_field_accessor_expression |
+        parse_index_accessor_expression |
+        parse_layout_query_expression |
         parse_call_expression |
         parse_variable_expression
     )
 );
 
-// TODO precedence
-// TODO parentheses
-named!(parse_expression<NomSpan, ExpressionStatement>,
+// `as` binds tighter than infix operators and looser than prefix operators, `-a as i32 * b` is `((-a) as i32) * b`
+fn fold_cast_expression(expression: ExpressionStatement, target_type_names: Vec<Identifier>) -> ExpressionStatement {
+    target_type_names.into_iter().fold(expression, |expression, target_type_name| {
+        ExpressionStatement::Cast(CastExpression{
+            span: Span::from_to(expression.get_span(), target_type_name.span),
+            cast_type: CastType::Explicit,
+            expression: Box::new(expression),
+            cast_target_type_name: Some(target_type_name),
+            cast_target_type: None,
+        })
+    })
+}
+
+named!(parse_operand_expression<NomSpan, ExpressionStatement>,
+    do_parse!(
+        expression: parse_primary_expression >>
+        target_type_names: many0!(preceded!(ws!(tag!("as")), parse_type_declaration)) >>
+        (fold_cast_expression(expression, target_type_names))
+    )
+);
+
+named!(parse_binary_expression<NomSpan, ExpressionStatement>,
     alt!(
         parse_infix_expression |
-        parse_struct_instantiation |
+        parse_operand_expression
+    )
+);
+
+fn build_ternary_expression(condition: ExpressionStatement, branches: Option<(ExpressionStatement, ExpressionStatement)>) -> ExpressionStatement {
+    match branches {
+        Some((true_expression, false_expression)) => ExpressionStatement::Ternary(TernaryExpression {
+            span: Span::from_to(condition.get_span(), false_expression.get_span()),
+            condition: Box::new(condition),
+            true_expression: Box::new(true_expression),
+            false_expression: Box::new(false_expression),
+            ternary_type: None,
+        }),
+        None => condition,
+    }
+}
+
+// `?:` binds looser than any infix operator and is right associative, `a ? b : c ? d : e` is `a ? b : (c ? d : e)`
+named!(parse_expression<NomSpan, ExpressionStatement>,
+    do_parse!(
+        condition: parse_binary_expression >>
+        branches: opt!(do_parse!(
+            ws!(tag!("?")) >>
+            true_expression: parse_expression >>
+            ws!(tag!(":")) >>
+            false_expression: parse_expression >>
+            ((true_expression, false_expression))
+        )) >>
+        (build_ternary_expression(condition, branches))
+    )
+);
+
+// struct instantiations are ambiguous with the block following a condition
+// so, like in Rust, they have to be parenthesized there
+named!(parse_condition_primary_expression<NomSpan, ExpressionStatement>,
+    alt!(
+        parse_parenthesized_expression |
+        parse_condition_prefix_expression |
         parse_literal_expression |
+        parse_default_constructor |
+        parse_enum_variant_expression |
         parse_field_accessor_expression |
+        parse_index_accessor_expression |
+        parse_layout_query_expression |
         parse_call_expression |
         parse_variable_expression
     )
 );
 
+named!(parse_condition_operand_expression<NomSpan, ExpressionStatement>,
+    do_parse!(
+        expression: parse_condition_primary_expression >>
+        target_type_names: many0!(preceded!(ws!(tag!("as")), parse_type_declaration)) >>
+        (fold_cast_expression(expression, target_type_names))
+    )
+);
+
+named!(parse_condition_infix_expression<NomSpan, ExpressionStatement>,
+    do_parse!(
+        first: parse_condition_operand_expression >>
+        rest: many1!(pair!(parse_infix_operator, parse_condition_operand_expression)) >>
+        (fold_infix_expression(first, rest))
+    )
+);
+
+named!(parse_condition_expression<NomSpan, ExpressionStatement>,
+    alt!(
+        parse_condition_infix_expression |
+        parse_condition_operand_expression
+    )
+);
+
 named!(parse_local_declaration<NomSpan, BlockStatement>,
     do_parse!(
         from: ws!(tag!("let")) >>
         symbol_name: parse_symbol_declaration >>
-        ws!(tag!("=")) >>
-        expression: parse_expression >>
+        local_type_name: opt!(preceded!(ws!(tag!(":")), parse_type_declaration)) >>
+        expression: opt!(preceded!(ws!(tag!("=")), parse_expression)) >>
         to: ws!(tag!(";")) >>
         (BlockStatement::Local(
             LocalDeclaration{
                 span: Span::from_to(Span::from_nom_span(&from), Span::from_nom_span(&to)),
                 symbol_name: symbol_name,
+                local_type_name: local_type_name,
                 expression: expression,
                 local_type: None,
             }
@@ -429,6 +837,43 @@ named!(parse_local_declaration<NomSpan, BlockStatement>,
     )
 );
 
+named!(parse_local_constant_declaration<NomSpan, BlockStatement>,
+    do_parse!(
+        from: ws!(tag!("const")) >>
+        constant_name: parse_symbol_declaration >>
+        ws!(tag!(":")) >>
+        constant_type_name: parse_type_declaration >>
+        ws!(tag!("=")) >>
+        expression: parse_expression >>
+        to: ws!(tag!(";")) >>
+        (BlockStatement::Const(
+            LocalConstantDeclaration{
+                span: Span::from_to(Span::from_nom_span(&from), Span::from_nom_span(&to)),
+                constant_name: constant_name,
+                constant_type_name: constant_type_name,
+                expression: expression,
+                constant_type: None,
+            }
+        ))
+    )
+);
+
+named!(parse_assignment_statement<NomSpan, BlockStatement>,
+    do_parse!(
+        symbol_name: parse_symbol_declaration >>
+        ws!(tag!("=")) >>
+        expression: parse_expression >>
+        to: ws!(tag!(";")) >>
+        (BlockStatement::Assignment(
+            AssignmentStatement{
+                span: Span::from_to(symbol_name.span, Span::from_nom_span(&to)),
+                symbol_name: symbol_name,
+                expression: expression,
+            }
+        ))
+    )
+);
+
 named!(parse_return_declaration<NomSpan, BlockStatement>,
     do_parse!(
         from: ws!(tag!("return")) >>
@@ -442,6 +887,111 @@ named!(parse_return_declaration<NomSpan, BlockStatement>,
     )
 );
 
+named!(parse_if_statement<NomSpan, BlockStatement>,
+    do_parse!(
+        from: ws!(tag!("if")) >>
+        condition: parse_condition_expression >>
+        block: parse_block_declaration >>
+        else_block: opt!(preceded!(ws!(tag!("else")), parse_block_declaration)) >>
+        (BlockStatement::If(IfStatement{
+            span: Span::from_to(Span::from_nom_span(&from), else_block.as_ref().map_or(block.span, |b| b.span)),
+            condition: condition,
+            block: block,
+            else_block: else_block,
+        }))
+    )
+);
+
+named!(parse_while_statement<NomSpan, BlockStatement>,
+    do_parse!(
+        from: ws!(tag!("while")) >>
+        condition: parse_condition_expression >>
+        block: parse_block_declaration >>
+        (BlockStatement::While(WhileStatement{
+            span: Span::from_to(Span::from_nom_span(&from), block.span),
+            condition: condition,
+            block: block,
+        }))
+    )
+);
+
+named!(parse_for_statement<NomSpan, BlockStatement>,
+    do_parse!(
+        attributes: many0!(parse_attribute) >>
+        from: ws!(tag!("for")) >>
+        loop_variable: parse_symbol_declaration >>
+        ws!(tag!("in")) >>
+        range_from: parse_condition_expression >>
+        ws!(tag!("..")) >>
+        range_to: parse_condition_expression >>
+        block: parse_block_declaration >>
+        (BlockStatement::For(ForStatement{
+            span: Span::from_to(Span::from_nom_span(&from), block.span),
+            attributes: attributes,
+            loop_variable: loop_variable,
+            from: range_from,
+            to: range_to,
+            block: block,
+        }))
+    )
+);
+
+named!(parse_loop_control_statement<NomSpan, BlockStatement>,
+    do_parse!(
+        loop_control: alt!(
+            map!(ws!(tag!("break")), |from| (LoopControl::Break, from)) |
+            map!(ws!(tag!("continue")), |from| (LoopControl::Continue, from))
+        ) >>
+        to: ws!(tag!(";")) >>
+        (BlockStatement::LoopControl(LoopControlStatement{
+            span: Span::from_to(Span::from_nom_span(&loop_control.1), Span::from_nom_span(&to)),
+            loop_control: loop_control.0,
+        }))
+    )
+);
+
+named!(parse_match_pattern<NomSpan, MatchPattern>,
+    alt!(
+        map!(ws!(parse_number), |value: NomSpan| MatchPattern::Literal(LiteralExpression {
+            span: Span::from_nom_span(&value),
+            value: value.fragment.to_string(),
+            literal_expression_type: LiteralType::Int,
+            literal_type: None,
+        })) |
+        map!(parse_enum_variant, MatchPattern::EnumVariant) |
+        map!(ws!(tag!("_")), |wildcard| MatchPattern::Wildcard(Span::from_nom_span(&wildcard)))
+    )
+);
+
+named!(parse_match_arm<NomSpan, MatchArm>,
+    do_parse!(
+        pattern: parse_match_pattern >>
+        ws!(tag!("=>")) >>
+        block: parse_block_declaration >>
+        opt!(ws!(tag!(","))) >>
+        (MatchArm{
+            span: Span::from_to(pattern.get_span(), block.span),
+            pattern: pattern,
+            block: block,
+        })
+    )
+);
+
+named!(parse_match_statement<NomSpan, BlockStatement>,
+    do_parse!(
+        from: ws!(tag!("match")) >>
+        scrutinee: parse_condition_expression >>
+        ws!(tag!("{")) >>
+        arms: many0!(parse_match_arm) >>
+        to: ws!(tag!("}")) >>
+        (BlockStatement::Match(MatchStatement{
+            span: Span::from_to(Span::from_nom_span(&from), Span::from_nom_span(&to)),
+            scrutinee: scrutinee,
+            arms: arms,
+        }))
+    )
+);
+
 named!(parse_expression_declaration<NomSpan, BlockStatement>,
     do_parse!(
         expression: parse_expression >>
@@ -457,7 +1007,14 @@ named!(parse_block_statements<NomSpan, Vec<BlockStatement>>,
         ws!(
             alt!(
                 parse_local_declaration |
+                parse_local_constant_declaration |
                 parse_return_declaration |
+                parse_if_statement |
+                parse_while_statement |
+                parse_for_statement |
+                parse_match_statement |
+                parse_loop_control_statement |
+                parse_assignment_statement |
                 parse_expression_declaration
             )
         )
@@ -477,8 +1034,28 @@ named!(parse_block_declaration<NomSpan, BlockDeclaration>,
 );
 
 // TODO make return type optional
+named!(parse_attribute<NomSpan, AttributeDeclaration>,
+    do_parse!(
+        from: ws!(tag!("[")) >>
+        attribute_name: parse_symbol_declaration >>
+        arguments: opt!(delimited!(
+            ws!(tag!("(")),
+            ws!(separated_list!(tag!(","), parse_expression)),
+            ws!(tag!(")"))
+        )) >>
+        to: ws!(tag!("]")) >>
+        (AttributeDeclaration{
+            span: Span::from_to(Span::from_nom_span(&from), Span::from_nom_span(&to)),
+            attribute_name: attribute_name,
+            arguments: arguments.unwrap_or_default(),
+        })
+    )
+);
+
 named!(parse_function<NomSpan, ItemKind>,
     do_parse!(
+        attributes: many0!(parse_attribute) >>
+        is_const: opt!(ws!(tag!("const"))) >>
         from: ws!(tag!("fn")) >>
         function_name: parse_symbol_declaration >>
         ws!(tag!("(")) >>
@@ -489,12 +1066,33 @@ named!(parse_function<NomSpan, ItemKind>,
         block: parse_block_declaration >>
         (ItemKind::Function(FunctionDeclaration{
             span: Span::from_to(Span::from_nom_span(&from), block.span),
+            attributes: attributes,
             function_name: function_name,
             arguments: arguments,
             block: block,
             return_type_name: return_type_name,
             return_type: None,
             declaring_type: None,
+            inline_hint: None,
+            is_const: is_const.is_some(),
+            required_extensions: Vec::new(),
+            debug_name: None,
+        }))
+    )
+);
+
+named!(parse_function_alias<NomSpan, ItemKind>,
+    do_parse!(
+        from: ws!(tag!("fn")) >>
+        alias_name: parse_symbol_declaration >>
+        ws!(tag!("=")) >>
+        target_name: parse_symbol_declaration >>
+        to: ws!(tag!(";")) >>
+        (ItemKind::FunctionAlias(FunctionAliasDeclaration{
+            span: Span::from_to(Span::from_nom_span(&from), Span::from_nom_span(&to)),
+            alias_name: alias_name,
+            target_name: target_name,
+            alias_type: None,
         }))
     )
 );
@@ -526,6 +1124,8 @@ fn char_to_operator(operator: char) -> Operator {
         '-' => Operator::Minus,
         '*' => Operator::Multiply,
         '/' => Operator::Divide,
+        '<' => Operator::Less,
+        '>' => Operator::Greater,
         _ => panic!(""),
     }
 }
@@ -539,12 +1139,37 @@ named!(parse_operator<NomSpan, ItemKind>,
         ws!(tag!(")")) >>
         ws!(tag!("->")) >>
         return_type: parse_type_declaration >>
-        to: ws!(tag!(";")) >>
+        body: alt!(
+            map!(ws!(tag!(";")), |to| (Span::from_nom_span(&to), None)) |
+            map!(parse_block_declaration, |block: BlockDeclaration| (block.span, Some(block)))
+        ) >>
         (ItemKind::Operator(OperatorDeclaration{
-            span: Span::from_to(Span::from_nom_span(&from), Span::from_nom_span(&to)),
+            span: Span::from_to(Span::from_nom_span(&from), body.0),
             operator: operator,
             arguments: arguments,
             return_type: return_type,
+            block: body.1,
+            operator_type: None,
+        }))
+    )
+);
+
+named!(parse_operator_precedence<NomSpan, ItemKind>,
+    do_parse!(
+        from: ws!(tag!("operator")) >>
+        operator: parse_infix_operator >>
+        ws!(tag!("precedence")) >>
+        precedence: map_res!(ws!(parse_number), |n: NomSpan| n.fragment.parse::<usize>()) >>
+        associativity: ws!(alt!(
+            value!(Associativity::Left, tag!("left")) |
+            value!(Associativity::Right, tag!("right"))
+        )) >>
+        to: ws!(tag!(";")) >>
+        (ItemKind::OperatorPrecedence(OperatorPrecedenceDeclaration{
+            span: Span::from_to(Span::from_nom_span(&from), Span::from_nom_span(&to)),
+            operator: operator,
+            precedence: precedence,
+            associativity: associativity,
         }))
     )
 );
@@ -587,14 +1212,18 @@ named!(parse<NomSpan, Vec<ItemKind>>,
     many0!(
         ws!(
             alt!(
+                parse_module_import |
                 parse_import |
                 parse_export | 
                 parse_sampler |
                 parse_constant |
                 parse_struct |
+                parse_enum |
                 parse_program |
                 parse_function |
+                parse_function_alias |
                 parse_primitive |
+                parse_operator_precedence |
                 parse_operator |
                 parse_implicit_cast |
                 parse_explicit_cast
@@ -616,6 +1245,25 @@ pub fn parse_block(program: &str) -> CompileResult<Vec<BlockStatement>> {
     }
 }
 
+named!(parse_terminated_expression<NomSpan, ExpressionStatement>,
+    terminated!(parse_expression, ws!(tag!(";")))
+);
+
+pub fn parse_expression_str(expression: &str) -> CompileResult<ExpressionStatement> {
+    // the terminator lets the parser decide where the expression ends instead of asking for more input
+    let terminated = format!("{};", expression);
+    let input = NomSpan::new(&terminated);
+    match parse_terminated_expression(input) {
+        IResult::Done(remaining, result) => {
+            if remaining.fragment.len() > 0 {
+                return Err(CompileError::new(CompileErrorKind::ParseError, Span::from_nom_span(&remaining)));
+            }
+            Ok(result)
+        },
+        _ => Err(CompileError::new(CompileErrorKind::ParseError, Span::new(0, 0, 1, 1))),
+    }
+}
+
 pub fn parse_str(program: &str) -> CompileResult<Vec<ItemKind>> {
     let input = NomSpan::new(program);
     match parse(input) {
@@ -629,6 +1277,14 @@ pub fn parse_str(program: &str) -> CompileResult<Vec<ItemKind>> {
     }
 }
 
+/// parses `program` with infix expressions folded according to `precedences`
+pub fn parse_str_with_precedences(program: &str, precedences: &PrecedenceTable) -> CompileResult<Vec<ItemKind>> {
+    let previous = PRECEDENCES.with(|p| p.replace(precedences.clone()));
+    let result = parse_str(program);
+    PRECEDENCES.with(|p| p.replace(previous));
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -646,12 +1302,26 @@ mod tests {
                         constant_variant: ConstantVariant::Constant,
                         constant_type_name: Identifier::new("mat4x4", Span::new(11, 6, 1, 12)),
                         constant_type: None,
+                        expression: None,
                     }
                 )
             ]
         ));
     }
 
+    #[test]
+    fn test_parse_const_statement_with_initializer() {
+        let code = "const scale: f32 = 2.0;";
+
+        match parse_str(code).unwrap()[0] {
+            ItemKind::Constant(ref c) => match c.expression {
+                Some(ExpressionStatement::Literal(ref l)) => assert_eq!(l.value, "2.0"),
+                _ => panic!("expected literal initializer"),
+            },
+            _ => panic!("expected constant"),
+        }
+    }
+
     #[test]
     fn test_parse_program() {
         let code = "struct VertexInput {
@@ -681,42 +1351,56 @@ program VertexColored {
                 ItemKind::Struct(
                     StructDefinition {                     
                         span: Span::new(0, 59, 1, 1),
+                        attributes: vec![],
                         struct_name: Identifier::new("VertexInput", Span::new(7, 11, 1, 8)),
+                        base_struct_name: None,
                         struct_member: vec![
                             StructMemberDefinition {
                                 span: Span::new(25, 14, 2, 5),
+                                attributes: vec![],
                                 struct_member_name: Identifier::new("position", Span::new(25, 8, 2, 5)),                   
                                 struct_member_type_name: Identifier::new("vec4", Span::new(35, 4, 2, 15)),
+                                default_value: None,
                                 struct_member_type: None,
                             },
                             StructMemberDefinition {
                                 span: Span::new(45, 11, 3, 5),
+                                attributes: vec![],
                                 struct_member_name: Identifier::new("color", Span::new(45, 5, 3, 5)),
                                 struct_member_type_name: Identifier::new("vec4", Span::new(52, 4, 3, 12)),
+                                default_value: None,
                                 struct_member_type: None,
                             }
                         ],
+                        base_struct_type: None,
                         declaring_type: None,
                     }
                 ),
                 ItemKind::Struct(
                     StructDefinition {
                         span: Span::new(61, 60, 6, 1),
+                        attributes: vec![],
                         struct_name: Identifier::new("VertexOutput", Span::new(68, 12, 6, 8)),
+                        base_struct_name: None,
                         struct_member: vec![
                             StructMemberDefinition {
                                 span: Span::new(87, 14, 7, 5),
+                                attributes: vec![],
                                 struct_member_name: Identifier::new("position", Span::new(87, 8, 7, 5)),
                                 struct_member_type_name: Identifier::new("vec4", Span::new(97, 4, 7, 15)),
+                                default_value: None,
                                 struct_member_type: None,
                             },
                             StructMemberDefinition {
                                 span: Span::new(107, 11, 8, 5),
+                                attributes: vec![],
                                 struct_member_name: Identifier::new("color", Span::new(107, 5, 8, 5)),
                                 struct_member_type_name: Identifier::new("vec4", Span::new(114, 4, 8, 12)),
+                                default_value: None,
                                 struct_member_type: None,
                             }
                         ],
+                        base_struct_type: None,
                         declaring_type: None,
                     }                    
                 ),
@@ -730,10 +1414,13 @@ program VertexColored {
                                 stage_name: Identifier::new("vertex", Span::new(157, 6, 12, 11)),
                                 function: FunctionDeclaration {
                                     span: Span::new(151, 161, 12, 5),
+                                    attributes: vec![],
                                     function_name: Identifier::new("vertex", Span::new(157, 6, 12, 11)),
                                     arguments: vec![
                                         FunctionArgumentDeclaration {
                                             span: Span::new(164, 15, 12, 18), 
+                                            attributes: vec![],
+                                            is_out: false,
                                             argument_name: Identifier::new("in", Span::new(164, 2, 12, 18)),
                                             argument_type_name: Identifier::new("VertexInput", Span::new(168, 11, 12, 22)),
                                             argument_type: None,
@@ -777,6 +1464,8 @@ program VertexColored {
                                                                     struct_field_type: None
                                                                 }
                                                             ],
+                                                            uses_defaults: false,
+                                                            base: None,
                                                             struct_type: None
                                                         }
                                                     ),
@@ -788,18 +1477,27 @@ program VertexColored {
                                     return_type_name: Identifier::new("VertexOutput", Span::new(184, 12, 12, 38)),
                                     return_type: None,
                                     declaring_type: None,
+                                    inline_hint: None,
+                                    is_const: false,
+                                    required_extensions: Vec::new(),
+                                    debug_name: None,
                                 },
                                 declaring_type: None,
+                                workgroup_size: None,
+                                early_fragment_tests: false,
                             },
                             ProgramStageDefinition {
                                 span: Span::new(318, 73, 19, 5),
                                 stage_name: Identifier::new("fragment", Span::new(324, 8, 19, 11)),
                                 function: FunctionDeclaration {
                                     span: Span::new(318, 73, 19, 5),
+                                    attributes: vec![],
                                     function_name: Identifier::new("fragment", Span::new(324, 8, 19, 11)),
                                     arguments: vec![
                                         FunctionArgumentDeclaration {
                                             span: Span::new(333, 16, 19, 20),
+                                            attributes: vec![],
+                                            is_out: false,
                                             argument_name: Identifier::new("in", Span::new(333, 2, 19, 20)),
                                             argument_type_name: Identifier::new("VertexOutput", Span::new(337, 12, 19, 24)),
                                             argument_type: None,
@@ -827,8 +1525,14 @@ program VertexColored {
                                     return_type_name: Identifier::new("vec4", Span::new(354, 4, 19, 41)),
                                     return_type: None,
                                     declaring_type: None,
+                                    inline_hint: None,
+                                    is_const: false,
+                                    required_extensions: Vec::new(),
+                                    debug_name: None,
                                 },
                                 declaring_type: None,
+                                workgroup_size: None,
+                                early_fragment_tests: false,
                             }
                         ]
                     }
@@ -846,21 +1550,28 @@ program VertexColored {
                 ItemKind::Struct(
                     StructDefinition {
                         span: Span::new(0, 48, 1, 1),
+                        attributes: vec![],
                         struct_name: Identifier::new("VertexInput", Span::new(7, 11, 1, 8)),
+                        base_struct_name: None,
                         struct_member: vec![
                             StructMemberDefinition {
                                 span: Span::new(21, 14, 1, 22),
+                                attributes: vec![],
                                 struct_member_name: Identifier::new("position", Span::new(21, 8, 1, 22)),
                                 struct_member_type_name: Identifier::new("vec3", Span::new(31, 4, 1, 32)),
+                                default_value: None,
                                 struct_member_type: None,
                             },
                             StructMemberDefinition {
                                 span: Span::new(37, 8, 1, 38),
+                                attributes: vec![],
                                 struct_member_name: Identifier::new("uv", Span::new(37, 2, 1, 38)),
                                 struct_member_type_name: Identifier::new("vec2", Span::new(41, 4, 1, 42)),
+                                default_value: None,
                                 struct_member_type: None,
                             },
                         ],
+                        base_struct_type: None,
                         declaring_type: None,
                     }
                 )
@@ -877,6 +1588,7 @@ program VertexColored {
                 ItemKind::Function(
                     FunctionDeclaration {
                         span: Span::new(0, 32, 1, 1),
+                        attributes: vec![],
                         function_name: Identifier::new("main", Span::new(3, 4, 1, 4)),
                         arguments: vec![],
                         block: BlockDeclaration {
@@ -901,6 +1613,10 @@ program VertexColored {
                         return_type_name: Identifier::new("f32", Span::new(13, 3, 1, 14)),
                         return_type: None,
                         declaring_type: None,
+                        inline_hint: None,
+                        is_const: false,
+                        required_extensions: Vec::new(),
+                        debug_name: None,
                     }
                 )
             ]
@@ -937,18 +1653,24 @@ program VertexColored {
                         arguments: vec![
                             FunctionArgumentDeclaration {
                                 span: Span::new(12, 8, 1, 13),
+                                attributes: vec![],
+                                is_out: false,
                                 argument_name: Identifier::new("lhs", Span::new(12, 3, 1, 13)),
                                 argument_type_name: Identifier::new("f32", Span::new(17, 3, 1, 18)),
                                 argument_type: None,
                             },
                             FunctionArgumentDeclaration {
                                 span: Span::new(22, 8, 1, 23),
+                                attributes: vec![],
+                                is_out: false,
                                 argument_name: Identifier::new("rhs", Span::new(22, 3, 1, 23)),
                                 argument_type_name: Identifier::new("f32", Span::new(27, 3, 1, 28)),
                                 argument_type: None,
                             }
                         ],
                         return_type: Identifier::new("f32", Span::new(35, 3, 1, 36)),
+                        block: None,
+                        operator_type: None,
                     }
                 )
             ]
@@ -973,6 +1695,234 @@ program VertexColored {
         ));
     }
 
+    #[test]
+    fn test_parse_comparison() {
+        let code = "fn main() -> bool { return a != 1; }";
+
+        match parse_str(code).unwrap()[0] {
+            ItemKind::Function(ref function) => match function.block.statements[0] {
+                BlockStatement::Return(ReturnDeclaration { expression: ExpressionStatement::Infix(ref infix), .. }) => assert_eq!(infix.operator, Operator::NotEqual),
+                _ => panic!("expected infix expression"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    fn parse_return_expression(code: &str) -> ExpressionStatement {
+        let mut ast = parse_str(code).unwrap();
+        match ast.remove(0) {
+            ItemKind::Function(mut function) => match function.block.statements.remove(0) {
+                BlockStatement::Return(r) => r.expression,
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    fn infix_operator(expression: &ExpressionStatement) -> &Operator {
+        match *expression {
+            ExpressionStatement::Infix(ref infix) => &infix.operator,
+            _ => panic!("expected infix expression"),
+        }
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let expression = parse_return_expression("fn main() -> i32 { return 2 * 3 + 1; }");
+
+        assert_eq!(infix_operator(&expression), &Operator::Plus);
+        match expression {
+            ExpressionStatement::Infix(ref infix) => assert_eq!(infix_operator(&infix.left_hand), &Operator::Multiply),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_parse_parentheses() {
+        let grouped = parse_return_expression("fn main() -> i32 { return (1 + 2) * 3; }");
+        let ungrouped = parse_return_expression("fn main() -> i32 { return 1 + 2 * 3; }");
+
+        assert_eq!(infix_operator(&grouped), &Operator::Multiply);
+        assert_eq!(infix_operator(&ungrouped), &Operator::Plus);
+    }
+
+    #[test]
+    fn test_parse_left_associativity() {
+        let expression = parse_return_expression("fn main() -> i32 { return 1 - 2 - 3; }");
+
+        match expression {
+            ExpressionStatement::Infix(ref infix) => assert_eq!(infix_operator(&infix.left_hand), &Operator::Minus),
+            _ => panic!("expected infix expression"),
+        }
+    }
+
+    fn declared_precedences(code: &str) -> TypeCheckResult<PrecedenceTable> {
+        let ast = parse_str(code).unwrap();
+        let declarations: Vec<&OperatorPrecedenceDeclaration> = ast.iter().filter_map(|item| match *item {
+            ItemKind::OperatorPrecedence(ref p) => Some(p),
+            _ => None,
+        }).collect();
+
+        PrecedenceTable::from_declarations(&declarations)
+    }
+
+    #[test]
+    fn test_parse_module_import() {
+        let code = "import \"shapes/circle\";";
+
+        assert_eq!(parse_str(code), Ok(
+            vec![
+                ItemKind::Import(
+                    ImportDefinition {
+                        span: Span::new(0, 23, 1, 1),
+                        items: vec![ImportItem::All],
+                        module_id: "shapes/circle".to_owned(),
+                    }
+                )
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_parse_operator_precedence() {
+        let code = "operator + precedence 3 right;";
+
+        assert_eq!(parse_str(code), Ok(
+            vec![
+                ItemKind::OperatorPrecedence(
+                    OperatorPrecedenceDeclaration {
+                        span: Span::new(0, 30, 1, 1),
+                        operator: Operator::Plus,
+                        precedence: 3,
+                        associativity: Associativity::Right,
+                    }
+                )
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_parse_with_declared_precedence() {
+        let precedences = declared_precedences("operator + precedence 3 left;").unwrap();
+        let mut ast = parse_str_with_precedences("fn main() -> i32 { return 2 * 3 + 1; }", &precedences).unwrap();
+
+        let expression = match ast.remove(0) {
+            ItemKind::Function(mut function) => match function.block.statements.remove(0) {
+                BlockStatement::Return(r) => r.expression,
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        };
+
+        assert_eq!(infix_operator(&expression), &Operator::Multiply);
+        match expression {
+            ExpressionStatement::Infix(ref infix) => assert_eq!(infix_operator(&infix.right_hand), &Operator::Plus),
+            _ => unreachable!(),
+        }
+
+        // the default table is restored afterwards
+        assert_eq!(infix_operator(&parse_return_expression("fn main() -> i32 { return 2 * 3 + 1; }")), &Operator::Plus);
+    }
+
+    #[test]
+    fn test_parse_with_right_associativity() {
+        let precedences = declared_precedences("operator - precedence 1 right;").unwrap();
+        let ast = parse_str_with_precedences("fn main() -> i32 { return 1 - 2 - 3; }", &precedences).unwrap();
+
+        match ast[0] {
+            ItemKind::Function(ref function) => match function.block.statements[0] {
+                BlockStatement::Return(ReturnDeclaration { expression: ExpressionStatement::Infix(ref infix), .. }) => {
+                    assert_eq!(infix_operator(&infix.right_hand), &Operator::Minus);
+                },
+                _ => panic!("expected infix return"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn test_conflicting_precedences_produce_an_error() {
+        let error = declared_precedences("operator + precedence 1 left; operator + precedence 2 left;").unwrap_err();
+
+        assert_eq!(*error.get_kind(), ErrorKind::ConflictingOperatorPrecedence("+".to_owned()));
+    }
+
+    #[test]
+    fn test_parse_enum() {
+        let code = "enum BlendMode { Add, Multiply }";
+
+        assert_eq!(parse_str(code), Ok(
+            vec![
+                ItemKind::Enum(
+                    EnumDefinition {
+                        span: Span::new(0, 32, 1, 1),
+                        enum_name: Identifier::new("BlendMode", Span::new(5, 9, 1, 6)),
+                        variants: vec![
+                            Identifier::new("Add", Span::new(17, 3, 1, 18)),
+                            Identifier::new("Multiply", Span::new(22, 8, 1, 23)),
+                        ],
+                        declaring_type: None,
+                    }
+                )
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_parse_match() {
+        let ast = parse_str("fn main(mode: BlendMode) -> i32 { match mode { BlendMode::Add => { return 1; }, _ => { return 0; } } }").unwrap();
+
+        match ast[0] {
+            ItemKind::Function(ref function) => match function.block.statements[0] {
+                BlockStatement::Match(ref m) => {
+                    assert_eq!(m.arms.len(), 2);
+                    match m.arms[1].pattern {
+                        MatchPattern::Wildcard(_) => (),
+                        ref pattern => panic!("expected wildcard, got {:?}", pattern),
+                    }
+                },
+                _ => panic!("expected match statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn test_parse_if() {
+        let ast = parse_str("fn main(a: bool) -> i32 { if a { return 1; } if (a) { return 2; } else { return 3; } return 0; }").unwrap();
+
+        match ast[0] {
+            ItemKind::Function(ref function) => {
+                match function.block.statements[0] {
+                    BlockStatement::If(ref i) => assert!(i.else_block.is_none()),
+                    _ => panic!("expected if statement"),
+                }
+                match function.block.statements[1] {
+                    BlockStatement::If(ref i) => {
+                        assert_eq!(i.block.statements.len(), 1);
+                        assert_eq!(i.else_block.as_ref().map(|b| b.statements.len()), Some(1));
+                    },
+                    _ => panic!("expected if statement"),
+                }
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn test_parse_const_function() {
+        let ast = parse_str("const fn count() -> i32 { return 4; } const scale: f32;").unwrap();
+
+        match ast[0] {
+            ItemKind::Function(ref function) => assert!(function.is_const),
+            _ => panic!("expected function"),
+        }
+        match ast[1] {
+            ItemKind::Constant(ref constant) => assert_eq!(constant.constant_name.name, "scale"),
+            _ => panic!("expected constant"),
+        }
+    }
+
     #[test]
     fn test_parse_incomplete_function_gives_correct_error() {
         let code = "
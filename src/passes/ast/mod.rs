@@ -19,8 +19,14 @@ pub trait AstWalker {
             match *item {
                 ItemKind::Export(ref mut item) => self.visit_export(item),
                 ItemKind::Struct(ref mut item) => self.visit_struct(item),
+                ItemKind::Enum(ref mut item) => self.visit_enum(item),
                 ItemKind::Function(ref mut item) => self.visit_function(item),
+                ItemKind::FunctionAlias(ref mut item) => self.visit_function_alias(item),
                 ItemKind::Primitive(ref mut item) => self.visit_primitive(item),
+                ItemKind::Cast(ref mut item) => self.visit_cast(item),
+                ItemKind::Operator(ref mut item) => self.visit_operator(item),
+                ItemKind::Constant(ref mut item) => self.visit_constant(item),
+                ItemKind::Program(ref mut item) => self.visit_program(item),
                 _ => (),
             };
         }
@@ -29,6 +35,9 @@ pub trait AstWalker {
     fn visit_primitive(&mut self, primitive_declaration: &mut PrimitiveDeclaration) {
     }
 
+    fn visit_cast(&mut self, cast_declaration: &mut CastDeclaration) {
+    }
+
     fn visit_block(&mut self, block: &mut BlockDeclaration) {
         self.walk_block(block);
     }
@@ -37,7 +46,14 @@ pub trait AstWalker {
         for statement in block.statements.iter_mut() {
             match *statement {
                 BlockStatement::Local(ref mut s) => self.visit_local_statement(s),
+                BlockStatement::Const(ref mut s) => self.visit_const_statement(s),
+                BlockStatement::Assignment(ref mut s) => self.visit_assignment_statement(s),
                 BlockStatement::Return(ref mut s) => self.visit_return_statement(s),
+                BlockStatement::If(ref mut s) => self.visit_if_statement(s),
+                BlockStatement::While(ref mut s) => self.visit_while_statement(s),
+                BlockStatement::For(ref mut s) => self.visit_for_statement(s),
+                BlockStatement::LoopControl(ref mut s) => self.visit_loop_control_statement(s),
+                BlockStatement::Match(ref mut s) => self.visit_match_statement(s),
                 BlockStatement::Expression(ref mut s) => self.visit_expression_statement(s),
             };
         }
@@ -48,7 +64,25 @@ pub trait AstWalker {
     }
 
     fn walk_local_statement(&mut self, local_statement: &mut LocalDeclaration) {
-        self.visit_expression(&mut local_statement.expression);
+        if let Some(ref mut expression) = local_statement.expression {
+            self.visit_expression(expression);
+        }
+    }
+
+    fn visit_const_statement(&mut self, const_statement: &mut LocalConstantDeclaration) {
+        self.walk_const_statement(const_statement);
+    }
+
+    fn walk_const_statement(&mut self, const_statement: &mut LocalConstantDeclaration) {
+        self.visit_expression(&mut const_statement.expression);
+    }
+
+    fn visit_assignment_statement(&mut self, assignment_statement: &mut AssignmentStatement) {
+        self.walk_assignment_statement(assignment_statement);
+    }
+
+    fn walk_assignment_statement(&mut self, assignment_statement: &mut AssignmentStatement) {
+        self.visit_expression(&mut assignment_statement.expression);
     }
 
     fn visit_return_statement(&mut self, return_statement: &mut ReturnDeclaration) {
@@ -59,6 +93,51 @@ pub trait AstWalker {
         self.visit_expression(&mut return_statement.expression);
     }
 
+    fn visit_if_statement(&mut self, if_statement: &mut IfStatement) {
+        self.walk_if_statement(if_statement);
+    }
+
+    fn walk_if_statement(&mut self, if_statement: &mut IfStatement) {
+        self.visit_expression(&mut if_statement.condition);
+        self.visit_block(&mut if_statement.block);
+        if let Some(ref mut else_block) = if_statement.else_block {
+            self.visit_block(else_block);
+        }
+    }
+
+    fn visit_while_statement(&mut self, while_statement: &mut WhileStatement) {
+        self.walk_while_statement(while_statement);
+    }
+
+    fn walk_while_statement(&mut self, while_statement: &mut WhileStatement) {
+        self.visit_expression(&mut while_statement.condition);
+        self.visit_block(&mut while_statement.block);
+    }
+
+    fn visit_for_statement(&mut self, for_statement: &mut ForStatement) {
+        self.walk_for_statement(for_statement);
+    }
+
+    fn walk_for_statement(&mut self, for_statement: &mut ForStatement) {
+        self.visit_expression(&mut for_statement.from);
+        self.visit_expression(&mut for_statement.to);
+        self.visit_block(&mut for_statement.block);
+    }
+
+    fn visit_loop_control_statement(&mut self, loop_control_statement: &mut LoopControlStatement) {
+    }
+
+    fn visit_match_statement(&mut self, match_statement: &mut MatchStatement) {
+        self.walk_match_statement(match_statement);
+    }
+
+    fn walk_match_statement(&mut self, match_statement: &mut MatchStatement) {
+        self.visit_expression(&mut match_statement.scrutinee);
+        for arm in match_statement.arms.iter_mut() {
+            self.visit_block(&mut arm.block);
+        }
+    }
+
     fn visit_expression_statement(&mut self, expression_statement: &mut ExpressionStatement) {
         self.walk_expression_statement(expression_statement);
     }
@@ -68,14 +147,22 @@ pub trait AstWalker {
     }
 
     fn visit_expression(&mut self, expression_statement: &mut ExpressionStatement) {
+        self.walk_expression(expression_statement);
+    }
+
+    fn walk_expression(&mut self, expression_statement: &mut ExpressionStatement) {
         match *expression_statement {
             ExpressionStatement::Infix(ref mut e) => self.visit_infix_expression(e),
+            ExpressionStatement::Prefix(ref mut e) => self.visit_prefix_expression(e),
             ExpressionStatement::Literal(ref mut e) => self.visit_literal_expression(e),
             ExpressionStatement::Call(ref mut e) => self.visit_call_expression(e),
             ExpressionStatement::StructInstantiation(ref mut e) => self.visit_struct_instantiation_expression(e),
             ExpressionStatement::FieldAccessor(ref mut e) => self.visit_field_accessor_expression(e),
             ExpressionStatement::IndexAccessor(ref mut e) => self.visit_index_accessor_expression(e),
             ExpressionStatement::Variable(ref mut e) => self.visit_variable_expression(e),
+            ExpressionStatement::Cast(ref mut e) => self.visit_cast_expression(e),
+            ExpressionStatement::EnumVariant(ref mut e) => self.visit_enum_variant_expression(e),
+            ExpressionStatement::Ternary(ref mut e) => self.visit_ternary_expression(e),
         }
     }
 
@@ -96,6 +183,14 @@ pub trait AstWalker {
         self.visit_expression(&mut infix_expression.right_hand);
     }
 
+    fn visit_prefix_expression(&mut self, prefix_expression: &mut PrefixExpression) {
+        self.walk_prefix_expression(prefix_expression);
+    }
+
+    fn walk_prefix_expression(&mut self, prefix_expression: &mut PrefixExpression) {
+        self.visit_expression(&mut prefix_expression.expression);
+    }
+
     fn visit_literal_expression(&mut self, literal_expression: &mut LiteralExpression) {
     }
 
@@ -117,6 +212,9 @@ pub trait AstWalker {
         for e in struct_instantiation_expression.struct_field_initializer.iter_mut() {
             self.visit_struct_field_initializer(e);
         }
+        if let Some(ref mut base) = struct_instantiation_expression.base {
+            self.visit_expression(base);
+        }
     }
 
     fn visit_struct_field_initializer(&mut self, struct_field_initializer: &mut StructFieldInitializerExpression) {
@@ -141,6 +239,27 @@ pub trait AstWalker {
     fn visit_variable_expression(&mut self, variable_expression: &mut VariableExpression) {
     }
 
+    fn visit_enum_variant_expression(&mut self, enum_variant_expression: &mut EnumVariantExpression) {
+    }
+
+    fn visit_cast_expression(&mut self, cast_expression: &mut CastExpression) {
+        self.walk_cast_expression(cast_expression);
+    }
+
+    fn walk_cast_expression(&mut self, cast_expression: &mut CastExpression) {
+        self.visit_expression(&mut cast_expression.expression);
+    }
+
+    fn visit_ternary_expression(&mut self, ternary_expression: &mut TernaryExpression) {
+        self.walk_ternary_expression(ternary_expression);
+    }
+
+    fn walk_ternary_expression(&mut self, ternary_expression: &mut TernaryExpression) {
+        self.visit_expression(&mut ternary_expression.condition);
+        self.visit_expression(&mut ternary_expression.true_expression);
+        self.visit_expression(&mut ternary_expression.false_expression);
+    }
+
     fn visit_function_argument(&mut self, function_argument: &mut FunctionArgumentDeclaration) {
     }
 
@@ -156,6 +275,23 @@ pub trait AstWalker {
         self.walk_function(function_definition);
     }
 
+    fn visit_function_alias(&mut self, function_alias_declaration: &mut FunctionAliasDeclaration) {
+    }
+
+    fn walk_operator(&mut self, operator_declaration: &mut OperatorDeclaration) {
+        for argument in operator_declaration.arguments.iter_mut() {
+            self.visit_function_argument(argument);
+        }
+
+        if let Some(ref mut block) = operator_declaration.block {
+            self.visit_block(block);
+        }
+    }
+
+    fn visit_operator(&mut self, operator_declaration: &mut OperatorDeclaration) {
+        self.walk_operator(operator_declaration);
+    }
+
     fn walk_struct(&mut self, struct_definition: &mut StructDefinition) {
         for member in struct_definition.struct_member.iter_mut() {
             self.visit_struct_member(member);
@@ -166,9 +302,40 @@ pub trait AstWalker {
         self.walk_struct(struct_definition);
     }
 
+    fn visit_enum(&mut self, enum_definition: &mut EnumDefinition) {
+    }
+
     fn visit_struct_member(&mut self, struct_member_definition: &mut StructMemberDefinition) {
     }
 
     fn visit_export(&mut self, export_definition: &mut ExportDefinition) {
     }
+
+    fn visit_constant(&mut self, constant_definition: &mut ConstantDefinition) {
+        self.walk_constant(constant_definition);
+    }
+
+    fn walk_constant(&mut self, constant_definition: &mut ConstantDefinition) {
+        if let Some(ref mut expression) = constant_definition.expression {
+            self.visit_expression(expression);
+        }
+    }
+
+    fn visit_program(&mut self, program_definition: &mut ProgramDefinition) {
+        self.walk_program(program_definition);
+    }
+
+    fn walk_program(&mut self, program_definition: &mut ProgramDefinition) {
+        for stage in program_definition.program_stages.iter_mut() {
+            self.visit_program_stage(stage);
+        }
+    }
+
+    fn visit_program_stage(&mut self, program_stage_definition: &mut ProgramStageDefinition) {
+        self.walk_program_stage(program_stage_definition);
+    }
+
+    fn walk_program_stage(&mut self, program_stage_definition: &mut ProgramStageDefinition) {
+        self.visit_function(&mut program_stage_definition.function);
+    }
 }
@@ -0,0 +1,167 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+
+pub struct CheckCastsPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+    is_core_module: bool,
+}
+
+impl CheckCastsPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference, is_core_module: bool) -> CheckCastsPass {
+        CheckCastsPass {
+            symbol_table: symbol_table,
+            result: result,
+            is_core_module: is_core_module,
+        }
+    }
+}
+
+ast_pass_impl!(CheckCastsPass, {
+    fn visit_cast(&mut self, cast_declaration: &mut CastDeclaration) {
+        // casts may be used everywhere, but only declared in core modules
+        if !self.is_core_module {
+            pass_try!(self, Err(TypeError::new(cast_declaration.span, ErrorKind::SyntaxOnlyValidInCoreModule)));
+        }
+
+        let source_name = &cast_declaration.source_type.name;
+        let target_name = &cast_declaration.target_type.name;
+
+        let source_type = match symbol_table!(self).find_type_ref(source_name) {
+            Some(t) => t,
+            None => pass_try!(self, Err(TypeError::new(cast_declaration.source_type.span, ErrorKind::TypeNotFound(source_name.to_owned())))),
+        };
+        let target_type = match symbol_table!(self).find_type_ref(target_name) {
+            Some(t) => t,
+            None => pass_try!(self, Err(TypeError::new(cast_declaration.target_type.span, ErrorKind::TypeNotFound(target_name.to_owned())))),
+        };
+
+        let mut symbol_table = symbol_table_mut!(self);
+        let source_definition = pass_try!(self, symbol_table.find_type_mut_or_err(source_type));
+
+        if source_definition.does_cast_exist(target_type) {
+            pass_try!(self, Err(TypeError::new(cast_declaration.span, ErrorKind::CastAlreadyDeclared(source_name.to_owned(), target_name.to_owned()))));
+        }
+
+        match cast_declaration.cast_type {
+            CastType::Implicit => source_definition.add_implicit_cast(target_type),
+            CastType::Explicit => source_definition.add_explicit_cast(target_type),
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+    use ::passes::ast::type_checking::check_primitives_pass;
+    use ::passes::ast::type_checking::check_function_signatures_pass;
+    use ::passes::ast::type_checking::check_function_bodies_pass;
+
+    fn check_core_module(code: &str) -> (SymbolTableReference, PassResultReference) {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(check_primitives_pass::CheckPrimitivesPass::new(symbol_table.clone(), result.clone())),
+            Box::new(CheckCastsPass::new(symbol_table.clone(), result.clone(), true)),
+        ]);
+
+        passes.execute(&mut ast);
+
+        (symbol_table, result)
+    }
+
+    #[test]
+    fn core_module_declares_casts() {
+        let (symbol_table, result) = check_core_module("primitive type i32; primitive type f32; implicit cast i32 -> f32; explicit cast f32 -> i32;");
+
+        let i32_type = symbol_table.borrow().find_type_ref("i32").unwrap();
+        let f32_type = symbol_table.borrow().find_type_ref("f32").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        assert!(symbol_table.borrow().find_type(i32_type).unwrap().does_implicit_cast_exist(f32_type));
+        assert!(symbol_table.borrow().find_type(f32_type).unwrap().does_explicit_cast_exist(i32_type));
+    }
+
+    #[test]
+    fn duplicate_cast_produces_an_error() {
+        let (_, result) = check_core_module("primitive type i32; primitive type f32; implicit cast i32 -> f32; explicit cast i32 -> f32;");
+
+        assert!(result.borrow().has_errors());
+    }
+
+    #[test]
+    fn non_core_module_cannot_declare_casts() {
+        let (symbol_table, result) = check_core_module("primitive type i32; primitive type f32;");
+        let mut ast = compile_ast("implicit cast i32 -> f32;");
+
+        let mut pass = CheckCastsPass::new(symbol_table.clone(), result.clone(), false);
+        pass.execute(&mut ast);
+
+        let i32_type = symbol_table.borrow().find_type_ref("i32").unwrap();
+        let f32_type = symbol_table.borrow().find_type_ref("f32").unwrap();
+
+        assert!(result.borrow().has_errors());
+        assert!(!symbol_table.borrow().find_type(i32_type).unwrap().does_cast_exist(f32_type));
+    }
+
+    #[test]
+    fn non_core_module_can_use_core_casts() {
+        let (symbol_table, result) = check_core_module("primitive type i32; primitive type f32; implicit cast i32 -> f32;");
+        let mut ast = compile_ast("fn test(a: i32, b: f32) -> f32 { return a + b; }");
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(CheckCastsPass::new(symbol_table.clone(), result.clone(), false)),
+            Box::new(check_function_signatures_pass::CheckFunctionSignaturePass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_function_bodies_pass::CheckFunctionBodiesPass::new(symbol_table.clone(), result.clone())),
+        ]);
+
+        passes.execute(&mut ast);
+
+        let f32_type = symbol_table.borrow().find_type_ref("f32").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        match ast[0] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => assert_eq!(r.expression.get_type(), Some(f32_type)),
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    // implicit casts are single declared edges, they are never chained through other casts
+    #[test]
+    fn explicit_cast_is_not_chained_into_implicit_cast() {
+        let (symbol_table, result) = check_core_module("primitive type i64; primitive type i32; primitive type f32; explicit cast i64 -> i32; implicit cast i32 -> f32;");
+        let mut ast = compile_ast("fn test(a: i64) -> f32 { let b: f32 = a; return b; }");
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(check_function_signatures_pass::CheckFunctionSignaturePass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_function_bodies_pass::CheckFunctionBodiesPass::new(symbol_table.clone(), result.clone())),
+        ]);
+
+        passes.execute(&mut ast);
+
+        let i64_type = symbol_table.borrow().find_type_ref("i64").unwrap();
+        let f32_type = symbol_table.borrow().find_type_ref("f32").unwrap();
+
+        assert!(!symbol_table.borrow().does_implicit_cast_exist(i64_type, f32_type));
+        assert!(result.borrow().get_errors().iter().any(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => match *e.get_kind() {
+                ErrorKind::IncompatibleTypes(_, _, _, _) => true,
+                _ => false,
+            },
+            None => false,
+        }));
+    }
+}
@@ -0,0 +1,170 @@
+use ::std::collections::HashSet;
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+
+/// Validates that `const fn` bodies only use constructs the constant evaluator can fold:
+/// locals, assignments, returns, literals, arithmetic, casts and calls to other `const fn`s
+pub struct CheckConstFunctionsPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+    const_functions: HashSet<String>,
+    function_name: String,
+    /// arguments and locals of the checked `const fn`
+    values: HashSet<String>,
+}
+
+impl CheckConstFunctionsPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckConstFunctionsPass {
+        CheckConstFunctionsPass {
+            symbol_table: symbol_table,
+            result: result,
+            const_functions: HashSet::new(),
+            function_name: String::new(),
+            values: HashSet::new(),
+        }
+    }
+
+    fn report(&mut self, span: Span) {
+        let error = TypeError::new(span, ErrorKind::NonConstInConstFn(self.function_name.to_owned()));
+        result_mut!(self).add_error(Box::new(error));
+    }
+}
+
+ast_pass_impl!(CheckConstFunctionsPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        for item in items.iter() {
+            if let ItemKind::Function(ref function) = *item {
+                if function.is_const {
+                    self.const_functions.insert(function.function_name.name.to_owned());
+                }
+            }
+        }
+
+        for item in items.iter_mut() {
+            if let ItemKind::Function(ref mut function) = *item {
+                self.visit_function(function);
+            }
+        }
+    }
+
+    fn visit_function(&mut self, function_declaration: &mut FunctionDeclaration) {
+        if !function_declaration.is_const {
+            return;
+        }
+
+        self.function_name = function_declaration.function_name.name.to_owned();
+        self.values = function_declaration.arguments.iter().map(|a| a.argument_name.name.to_owned()).collect();
+        self.walk_block(&mut function_declaration.block);
+    }
+
+    fn visit_local_statement(&mut self, local_statement: &mut LocalDeclaration) {
+        self.walk_local_statement(local_statement);
+        self.values.insert(local_statement.symbol_name.name.to_owned());
+    }
+
+    fn visit_const_statement(&mut self, const_statement: &mut LocalConstantDeclaration) {
+        self.walk_const_statement(const_statement);
+        self.values.insert(const_statement.constant_name.name.to_owned());
+    }
+
+    fn visit_if_statement(&mut self, if_statement: &mut IfStatement) {
+        self.report(if_statement.span);
+    }
+
+    fn visit_while_statement(&mut self, while_statement: &mut WhileStatement) {
+        self.report(while_statement.span);
+    }
+
+    fn visit_for_statement(&mut self, for_statement: &mut ForStatement) {
+        self.report(for_statement.span);
+    }
+
+    fn visit_loop_control_statement(&mut self, loop_control_statement: &mut LoopControlStatement) {
+        self.report(loop_control_statement.span);
+    }
+
+    fn visit_match_statement(&mut self, match_statement: &mut MatchStatement) {
+        self.report(match_statement.span);
+    }
+
+    fn visit_call_expression(&mut self, call_expression: &mut CallExpression) {
+        if !self.const_functions.contains(&call_expression.function_name.name) {
+            self.report(call_expression.span);
+        }
+
+        self.walk_call_expression(call_expression);
+    }
+
+    // globals like samplers have no value at compile time
+    fn visit_variable_expression(&mut self, variable_expression: &mut VariableExpression) {
+        if !self.values.contains(&variable_expression.variable_name.name) {
+            self.report(variable_expression.span);
+        }
+    }
+
+    fn visit_struct_instantiation_expression(&mut self, struct_instantiation_expression: &mut StructInstantiationExpression) {
+        self.report(struct_instantiation_expression.span);
+    }
+
+    fn visit_field_accessor_expression(&mut self, field_accessor_expression: &mut FieldAccessorExpression) {
+        self.report(field_accessor_expression.span);
+    }
+
+    fn visit_index_accessor_expression(&mut self, index_accessor_expression: &mut IndexAccesorExpression) {
+        self.report(index_accessor_expression.span);
+    }
+
+    fn visit_enum_variant_expression(&mut self, enum_variant_expression: &mut EnumVariantExpression) {
+        self.report(enum_variant_expression.span);
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn check(code: &str) -> PassResultReference {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckConstFunctionsPass::new(symbol_table.clone(), result.clone());
+
+        pass.execute(&mut ast);
+
+        result
+    }
+
+    #[test]
+    fn const_fn_with_foldable_body() {
+        let result = check("const fn double(a: i32) -> i32 { let b = a * 2; return b; } const fn quad(a: i32) -> i32 { return double(double(a)); }");
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn sampler_read_in_const_fn_produces_an_error() {
+        let result = check("sampler albedo: Texture2D; const fn f(uv: vec2) -> vec4 { return sample(albedo, uv); }");
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![
+            &ErrorKind::NonConstInConstFn("f".to_owned()),
+            &ErrorKind::NonConstInConstFn("f".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn non_const_functions_are_not_checked() {
+        let result = check("sampler albedo: Texture2D; fn f(uv: vec2) -> vec4 { return sample(albedo, uv); }");
+
+        assert!(!result.borrow().has_errors());
+    }
+}
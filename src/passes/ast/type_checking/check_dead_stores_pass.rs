@@ -0,0 +1,157 @@
+use ::std::collections::{ HashMap, HashSet };
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+
+/// Warns about assignments whose value is overwritten before it is read, e.g. the first store in `x = 1.0; x = 2.0;`
+/// liveness is tracked per block, nested blocks count as reading every variable they reference as they may not execute
+pub struct CheckDeadStoresPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+}
+
+impl CheckDeadStoresPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckDeadStoresPass {
+        CheckDeadStoresPass {
+            symbol_table: symbol_table,
+            result: result,
+        }
+    }
+
+    fn check_block(&mut self, block: &BlockDeclaration) {
+        // stores not read yet, by variable name
+        let mut pending: HashMap<&str, Span> = HashMap::new();
+
+        for statement in &block.statements {
+            match *statement {
+                BlockStatement::Local(ref l) => {
+                    if let Some(ref expression) = l.expression {
+                        read_expression(expression, &mut pending);
+                    }
+                    // shadowing starts a new variable
+                    pending.remove(l.symbol_name.name.as_str());
+                },
+                BlockStatement::Const(ref c) => read_expression(&c.expression, &mut pending),
+                BlockStatement::Assignment(ref a) => {
+                    read_expression(&a.expression, &mut pending);
+                    if let Some(span) = pending.insert(&a.symbol_name.name, a.span) {
+                        pass_warning!(self, TypeError::new(span, ErrorKind::DeadStore(a.symbol_name.name.to_owned())));
+                    }
+                },
+                BlockStatement::Return(ref r) => read_expression(&r.expression, &mut pending),
+                BlockStatement::If(ref i) => {
+                    read_expression(&i.condition, &mut pending);
+                    read_block(&i.block, &mut pending);
+                    self.check_block(&i.block);
+                    if let Some(ref else_block) = i.else_block {
+                        read_block(else_block, &mut pending);
+                        self.check_block(else_block);
+                    }
+                },
+                BlockStatement::While(ref w) => {
+                    read_expression(&w.condition, &mut pending);
+                    read_block(&w.block, &mut pending);
+                    self.check_block(&w.block);
+                },
+                BlockStatement::For(ref f) => {
+                    read_expression(&f.from, &mut pending);
+                    read_expression(&f.to, &mut pending);
+                    read_block(&f.block, &mut pending);
+                    self.check_block(&f.block);
+                },
+                BlockStatement::LoopControl(_) => (),
+                BlockStatement::Match(ref m) => {
+                    read_expression(&m.scrutinee, &mut pending);
+                    for arm in &m.arms {
+                        read_block(&arm.block, &mut pending);
+                        self.check_block(&arm.block);
+                    }
+                },
+                BlockStatement::Expression(ref e) => read_expression(e, &mut pending),
+            }
+        }
+    }
+}
+
+fn read_expression(expression: &ExpressionStatement, pending: &mut HashMap<&str, Span>) {
+    for variable in free_variables(expression) {
+        pending.remove(variable.as_str());
+    }
+}
+
+/// marks every variable referenced anywhere inside the block as read
+fn read_block(block: &BlockDeclaration, pending: &mut HashMap<&str, Span>) {
+    let mut reader = BlockReader {
+        variables: HashSet::new(),
+    };
+    reader.visit_block(&mut block.clone());
+
+    for variable in reader.variables {
+        pending.remove(variable.as_str());
+    }
+}
+
+struct BlockReader {
+    variables: HashSet<String>,
+}
+
+impl AstWalker for BlockReader {
+    fn visit_expression(&mut self, expression_statement: &mut ExpressionStatement) {
+        self.variables.extend(free_variables(expression_statement));
+    }
+}
+
+ast_pass_impl!(CheckDeadStoresPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        for item in items.iter() {
+            match *item {
+                ItemKind::Function(ref function) => self.check_block(&function.block),
+                ItemKind::Program(ref program) => {
+                    for stage in &program.program_stages {
+                        self.check_block(&stage.function.block);
+                    }
+                },
+                _ => (),
+            };
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn check(code: &str) -> PassResultReference {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckDeadStoresPass::new(symbol_table.clone(), result.clone());
+
+        pass.execute(&mut ast);
+
+        result
+    }
+
+    #[test]
+    fn overwritten_store_produces_a_warning() {
+        let result = check("fn main() -> f32 { let x: f32; x = 1.0; x = 2.0; return x; }");
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_warnings().iter().filter_map(|w| w.downcast_ref::<TypeError>()).map(|w| w.get_kind()).collect();
+        assert_eq!(kinds, vec![&ErrorKind::DeadStore("x".to_owned())]);
+    }
+
+    #[test]
+    fn store_read_before_being_overwritten() {
+        let result = check("fn main() -> f32 { let x: f32; x = 1.0; let y = x * 2.0; x = y; while x < 4.0 { x = x + 1.0; } x = 0.0; return x; }");
+
+        assert!(!result.borrow().has_warnings());
+    }
+}
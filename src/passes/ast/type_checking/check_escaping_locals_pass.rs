@@ -0,0 +1,130 @@
+use ::std::collections::HashSet;
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+
+/// Conservative escape analysis, flags returning a resource handle that was created inside the function
+/// resource types are the types samplers are declared with, handles passed in as arguments or copied from
+/// a variable may be returned
+pub struct CheckEscapingLocalsPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+    resource_types: HashSet<String>,
+    local_handles: HashSet<String>,
+}
+
+impl CheckEscapingLocalsPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckEscapingLocalsPass {
+        CheckEscapingLocalsPass {
+            symbol_table: symbol_table,
+            result: result,
+            resource_types: HashSet::new(),
+            local_handles: HashSet::new(),
+        }
+    }
+
+    fn is_resource_type(&self, type_name: &Identifier) -> bool {
+        self.resource_types.contains(&type_name.name)
+    }
+
+    fn creates_handle(&self, local_statement: &LocalDeclaration) -> bool {
+        match local_statement.expression {
+            Some(ExpressionStatement::Variable(_)) => false,
+            Some(ExpressionStatement::StructInstantiation(ref instantiation)) => self.is_resource_type(&instantiation.struct_type_name),
+            _ => local_statement.local_type_name.as_ref().is_some_and(|t| self.is_resource_type(t)),
+        }
+    }
+}
+
+ast_pass_impl!(CheckEscapingLocalsPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        for item in items.iter() {
+            if let ItemKind::Constant(ref constant) = *item {
+                if constant.constant_variant == ConstantVariant::Sampler {
+                    self.resource_types.insert(constant.constant_type_name.name.to_owned());
+                }
+            }
+        }
+
+        for item in items.iter_mut() {
+            match *item {
+                ItemKind::Function(ref mut function) => self.visit_function(function),
+                ItemKind::Program(ref mut program) => {
+                    for stage in program.program_stages.iter_mut() {
+                        self.visit_function(&mut stage.function);
+                    }
+                },
+                _ => (),
+            };
+        }
+    }
+
+    fn visit_function(&mut self, function_definition: &mut FunctionDeclaration) {
+        self.local_handles.clear();
+        self.walk_function(function_definition);
+    }
+
+    fn visit_local_statement(&mut self, local_statement: &mut LocalDeclaration) {
+        let name = local_statement.symbol_name.name.to_owned();
+        if self.creates_handle(local_statement) {
+            self.local_handles.insert(name);
+        } else {
+            self.local_handles.remove(&name);
+        }
+    }
+
+    fn visit_return_statement(&mut self, return_statement: &mut ReturnDeclaration) {
+        if let ExpressionStatement::Variable(ref variable) = return_statement.expression {
+            let name = &variable.variable_name.name;
+            if self.local_handles.contains(name) {
+                result_mut!(self).add_error(Box::new(TypeError::new(return_statement.span, ErrorKind::LocalEscapes(name.to_owned()))));
+            }
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn check(code: &str) -> PassResultReference {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckEscapingLocalsPass::new(symbol_table.clone(), result.clone());
+
+        pass.execute(&mut ast);
+
+        result
+    }
+
+    #[test]
+    fn returning_local_handle_produces_an_error() {
+        let result = check("
+            sampler albedo: Texture2D;
+            fn create() -> Texture2D { let texture = Texture2D { id: 0, }; return texture; }
+        ");
+
+        assert!(result.borrow().get_errors().iter().any(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => *e.get_kind() == ErrorKind::LocalEscapes("texture".to_owned()),
+            None => false,
+        }));
+    }
+
+    #[test]
+    fn returning_parameter_handle() {
+        let result = check("
+            sampler albedo: Texture2D;
+            fn pass_through(texture: Texture2D) -> Texture2D { let copy = texture; return texture; }
+        ");
+
+        assert!(!result.borrow().has_errors());
+    }
+}
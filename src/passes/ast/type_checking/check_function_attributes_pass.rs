@@ -0,0 +1,198 @@
+use ::std::collections::HashMap;
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+use ::type_system::const_eval::{ self, ConstFunctions, ConstValue };
+
+pub struct CheckFunctionAttributesPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+    /// `const fn`s of the module, `[debug_name(...)]` may call them
+    const_functions: ConstFunctions,
+}
+
+impl CheckFunctionAttributesPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckFunctionAttributesPass {
+        CheckFunctionAttributesPass {
+            symbol_table: symbol_table,
+            result: result,
+            const_functions: ConstFunctions::new(),
+        }
+    }
+}
+
+ast_pass_impl!(CheckFunctionAttributesPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        self.const_functions = ConstFunctions::from_ast(items);
+
+        for item in items.iter_mut() {
+            if let ItemKind::Function(ref mut item) = *item {
+                self.visit_function(item);
+            }
+        }
+    }
+
+    fn visit_function(&mut self, function_declaration: &mut FunctionDeclaration) {
+        let mut inline_hint = None;
+        let mut required_extensions = Vec::new();
+        let mut debug_name = None;
+
+        for attribute in function_declaration.attributes.iter() {
+            let hint = match attribute.attribute_name.name.as_str() {
+                "inline" => InlineHint::Inline,
+                "noinline" => InlineHint::NoInline,
+                "extension" => {
+                    match extension_names(attribute) {
+                        Some(names) => required_extensions.extend(names),
+                        None => pass_try!(self, Err(TypeError::new(attribute.span, ErrorKind::InvalidAttributeArguments("extension".to_owned())))),
+                    }
+                    continue;
+                },
+                "debug_name" => {
+                    match attribute.arguments.first() {
+                        Some(argument) if attribute.arguments.len() == 1 => match pass_try!(self, const_eval::evaluate_with(argument, &self.const_functions, &HashMap::new())) {
+                            ConstValue::String(name) => debug_name = Some(name),
+                            _ => pass_try!(self, Err(TypeError::new(attribute.span, ErrorKind::InvalidAttributeArguments("debug_name".to_owned())))),
+                        },
+                        _ => pass_try!(self, Err(TypeError::new(attribute.span, ErrorKind::InvalidAttributeArguments("debug_name".to_owned())))),
+                    }
+                    continue;
+                },
+                name => pass_try!(self, Err(TypeError::new(attribute.span, ErrorKind::UnknownAttribute(name.to_owned())))),
+            };
+
+            if inline_hint.map_or(false, |h| h != hint) {
+                pass_try!(self, Err(TypeError::new(attribute.span, ErrorKind::ConflictingInlineHints(function_declaration.function_name.name.to_owned()))));
+            }
+            inline_hint = Some(hint);
+        }
+
+        function_declaration.inline_hint = inline_hint;
+        function_declaration.required_extensions = required_extensions;
+        function_declaration.debug_name = debug_name;
+    }
+});
+
+/// `[extension("GL_EXT_a", "GL_EXT_b")]` takes one or more string literals
+fn extension_names(attribute: &AttributeDeclaration) -> Option<Vec<String>> {
+    if attribute.arguments.is_empty() {
+        return None;
+    }
+
+    attribute.arguments.iter().map(|argument| match *argument {
+        ExpressionStatement::Literal(ref literal) if literal.literal_expression_type == LiteralType::String => Some(literal.value.to_owned()),
+        _ => None,
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn check(code: &str) -> (Ast, PassResultReference) {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckFunctionAttributesPass::new(symbol_table.clone(), result.clone());
+
+        pass.execute(&mut ast);
+
+        (ast, result)
+    }
+
+    fn get_inline_hint(ast: &Ast) -> Option<InlineHint> {
+        match ast[0] {
+            ItemKind::Function(ref f) => f.inline_hint,
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn inline_hint() {
+        let (ast, result) = check("[inline] fn test() -> void {}");
+
+        assert!(!result.borrow().has_errors());
+        assert_eq!(get_inline_hint(&ast), Some(InlineHint::Inline));
+    }
+
+    #[test]
+    fn noinline_hint() {
+        let (ast, result) = check("[noinline] fn test() -> void {}");
+
+        assert!(!result.borrow().has_errors());
+        assert_eq!(get_inline_hint(&ast), Some(InlineHint::NoInline));
+    }
+
+    #[test]
+    fn no_hint() {
+        let (ast, result) = check("fn test() -> void {}");
+
+        assert!(!result.borrow().has_errors());
+        assert_eq!(get_inline_hint(&ast), None);
+    }
+
+    #[test]
+    fn conflicting_hints_produce_an_error() {
+        let (_, result) = check("[inline] [noinline] fn test() -> void {}");
+
+        assert!(result.borrow().get_errors().iter().any(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => *e.get_kind() == ErrorKind::ConflictingInlineHints("test".to_owned()),
+            None => false,
+        }));
+    }
+
+    #[test]
+    fn unknown_attribute_produces_an_error() {
+        let (_, result) = check("[fast] fn test() -> void {}");
+
+        assert!(result.borrow().has_errors());
+    }
+
+    #[test]
+    fn extension_attribute() {
+        let (ast, result) = check("[extension(\"GL_EXT_a\", \"GL_EXT_b\")] fn test() -> void {}");
+
+        assert!(!result.borrow().has_errors());
+        match ast[0] {
+            ItemKind::Function(ref f) => assert_eq!(f.required_extensions, vec!["GL_EXT_a", "GL_EXT_b"]),
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn extension_attribute_without_names_produces_an_error() {
+        let (_, result) = check("[extension] fn test() -> void {}");
+
+        assert!(result.borrow().get_errors().iter().any(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => *e.get_kind() == ErrorKind::InvalidAttributeArguments("extension".to_owned()),
+            None => false,
+        }));
+    }
+
+    #[test]
+    fn debug_name_is_folded() {
+        let (ast, result) = check("const fn index() -> i32 { return 2 + 1; } [debug_name(\"mesh_\" + index() + \"_\" + 0.5)] fn test() -> void {}");
+
+        assert!(!result.borrow().has_errors());
+        match ast[1] {
+            ItemKind::Function(ref f) => assert_eq!(f.debug_name, Some("mesh_3_0.5".to_owned())),
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn debug_name_with_runtime_value_produces_an_error() {
+        let (_, result) = check("[debug_name(\"mesh_\" + id)] fn test(id: i32) -> void {}");
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![&ErrorKind::NotAConstantExpression]);
+    }
+}
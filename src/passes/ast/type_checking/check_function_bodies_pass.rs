@@ -0,0 +1,2290 @@
+use ::std::collections::{ HashMap, HashSet };
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::passes::ast::type_checking::LintOptions;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::type_environment::TypeReference;
+use ::type_system::error::{ TypeError, ErrorKind, TypeCheckResult };
+use ::type_system::builtins;
+use ::type_system::layout::{ layout_of, LayoutRules };
+use ::type_system::const_eval::{ self, ConstValue, ConstFunctions };
+
+const FLOAT_TYPE_NAMES: &[&str] = &["f16", "f32", "f64"];
+
+/// integer primitives and the range of literals they can hold
+const INTEGER_RANGES: &[(&str, i128, i128)] = &[
+    ("i32", i32::MIN as i128, i32::MAX as i128),
+    ("i64", i64::MIN as i128, i64::MAX as i128),
+    ("u32", 0, u32::MAX as i128),
+    ("u64", 0, u64::MAX as i128),
+];
+
+pub struct CheckFunctionBodiesPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+    lints: LintOptions,
+    layout_rules: LayoutRules,
+    return_type: Option<TypeReference>,
+    uninitialized: HashSet<String>,
+    /// `out` parameters of the current function, they start out uninitialized like locals without initializer
+    out_parameters: HashSet<String>,
+    /// number of loops enclosing the current statement
+    loop_depth: usize,
+    /// structs of the module and whether all their fields declare a default value
+    struct_defaults: HashMap<String, bool>,
+    /// locals holding struct values that don't set every field, with the fields they definitely set
+    partial_structs: HashMap<String, Vec<String>>,
+    /// `const fn`s of the module, calls to them can be folded in loop bounds
+    const_functions: ConstFunctions,
+    /// block scoped constants visible at the current statement, they can't be assigned to
+    constants: HashSet<String>,
+    /// folded values of the visible constants, loop bounds and later constants may use them
+    constant_values: HashMap<String, ConstValue>,
+    /// module constants, every body starts out seeing them
+    module_constants: HashSet<String>,
+    /// folded values of the module constants with initializer
+    module_constant_values: HashMap<String, ConstValue>,
+}
+
+impl CheckFunctionBodiesPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckFunctionBodiesPass {
+        CheckFunctionBodiesPass::with_lints(symbol_table, result, LintOptions::new())
+    }
+
+    pub fn with_lints(symbol_table: SymbolTableReference, result: PassResultReference, lints: LintOptions) -> CheckFunctionBodiesPass {
+        CheckFunctionBodiesPass::with_options(symbol_table, result, lints, LayoutRules::Std140)
+    }
+
+    pub fn with_options(symbol_table: SymbolTableReference, result: PassResultReference, lints: LintOptions, layout_rules: LayoutRules) -> CheckFunctionBodiesPass {
+        CheckFunctionBodiesPass {
+            symbol_table: symbol_table,
+            result: result,
+            lints: lints,
+            layout_rules: layout_rules,
+            return_type: None,
+            uninitialized: HashSet::new(),
+            out_parameters: HashSet::new(),
+            loop_depth: 0,
+            struct_defaults: HashMap::new(),
+            partial_structs: HashMap::new(),
+            const_functions: ConstFunctions::new(),
+            constants: HashSet::new(),
+            constant_values: HashMap::new(),
+            module_constants: HashSet::new(),
+            module_constant_values: HashMap::new(),
+        }
+    }
+
+    fn is_float(&self, type_reference: TypeReference) -> bool {
+        match symbol_table!(self).find_type(type_reference) {
+            Some(t) => FLOAT_TYPE_NAMES.contains(&t.get_name()),
+            None => false,
+        }
+    }
+
+    fn is_struct(&self, type_reference: TypeReference) -> bool {
+        match symbol_table!(self).find_type(type_reference) {
+            Some(t) => t.is_struct(),
+            None => false,
+        }
+    }
+
+    /// reading a local before it is assigned is an error, `out` parameters start out unassigned as well
+    fn check_initialized(&self, variable_name: &str, span: Span) -> TypeCheckResult<()> {
+        if !self.uninitialized.contains(variable_name) {
+            return Ok(());
+        }
+
+        let kind = if self.out_parameters.contains(variable_name) {
+            ErrorKind::OutParamReadBeforeAssignment(variable_name.to_owned())
+        } else {
+            ErrorKind::UsedBeforeInitialization(variable_name.to_owned())
+        };
+        Err(TypeError::new(span, kind))
+    }
+
+    fn type_name(&self, type_reference: TypeReference) -> String {
+        match symbol_table!(self).find_type(type_reference) {
+            Some(t) => t.get_name().to_owned(),
+            None => String::new(),
+        }
+    }
+
+    /// common operand type of mixed arithmetic
+    /// operands are promoted along implicit casts, preferring floating point types if both directions exist
+    fn promoted_type(&self, left_type: TypeReference, right_type: TypeReference) -> Option<TypeReference> {
+        if left_type == right_type {
+            return Some(left_type);
+        }
+
+        let left_to_right = symbol_table!(self).implicit_cast_path(left_type, right_type).is_some();
+        let right_to_left = symbol_table!(self).implicit_cast_path(right_type, left_type).is_some();
+
+        match (left_to_right, right_to_left) {
+            (true, true) if self.is_float(left_type) && !self.is_float(right_type) => Some(left_type),
+            (true, _) => Some(right_type),
+            (false, true) => Some(left_type),
+            (false, false) => None,
+        }
+    }
+
+    /// fields a struct instantiation definitely sets, following `..base` through locals holding partial structs
+    /// `None` if every field is set
+    fn initialized_fields(&self, instantiation: &StructInstantiationExpression) -> Option<Vec<String>> {
+        if instantiation.uses_defaults {
+            return None;
+        }
+
+        let mut fields: Vec<String> = instantiation.struct_field_initializer.iter().map(|f| f.struct_field_name.name.to_owned()).collect();
+        if let Some(ref base) = instantiation.base {
+            match **base {
+                ExpressionStatement::StructInstantiation(ref base) => fields.extend(self.initialized_fields(base)?),
+                ExpressionStatement::Variable(ref variable) => fields.extend(self.partial_structs.get(&variable.variable_name.name)?.iter().cloned()),
+                // any other value is complete
+                _ => return None,
+            }
+        }
+
+        let symbol_table = symbol_table!(self);
+        let members = symbol_table.find_type(instantiation.struct_type?).and_then(|t| t.get_member())?;
+        if members.iter().all(|m| fields.contains(&m.member_name)) {
+            None
+        } else {
+            Some(fields)
+        }
+    }
+
+    /// remembers which fields of `local_name` are set after it is assigned `expression`
+    fn track_partial_struct(&mut self, local_name: &str, expression: Option<&ExpressionStatement>) {
+        let fields = expression.and_then(|expression| match *expression {
+            ExpressionStatement::StructInstantiation(ref instantiation) => self.initialized_fields(instantiation),
+            ExpressionStatement::Variable(ref variable) => self.partial_structs.get(&variable.variable_name.name).cloned(),
+            _ => None,
+        });
+
+        match fields {
+            Some(fields) => self.partial_structs.insert(local_name.to_owned(), fields),
+            None => self.partial_structs.remove(local_name),
+        };
+    }
+
+    /// makes `expression` evaluate to `target_type`, inserting an implicit cast if required
+    /// returns false if no implicit cast exists
+    fn coerce_expression(&mut self, expression: &mut ExpressionStatement, target_type: TypeReference) -> bool {
+        if self.infer_integer_expression(expression, target_type) {
+            return true;
+        }
+
+        let expression_type = match expression.get_type() {
+            Some(t) => t,
+            None => return true,
+        };
+
+        if expression_type == target_type {
+            return true;
+        }
+
+        // implicit casts chain, e.g. `i32 -> f32 -> f64`
+        let path = match symbol_table!(self).implicit_cast_path(expression_type, target_type) {
+            Some(path) => path,
+            None => return false,
+        };
+
+        let is_integer_division = match *expression {
+            ExpressionStatement::Infix(ref e) => e.operator == Operator::Divide && !self.is_float(expression_type),
+            _ => false,
+        };
+        if is_integer_division && self.is_float(target_type) {
+            result_mut!(self).add_lint(self.lints.integer_division_in_float_context, Box::new(TypeError::new(expression.get_span(), ErrorKind::IntegerDivisionInFloatContext)));
+        }
+
+        for &step in path[1..].iter() {
+            expression.insert_implicit_cast(step);
+        }
+        true
+    }
+
+    /// types an integer literal, or a negated one, as the integer type it is used as
+    /// returns false if `expression` is no such literal or `target_type` is no integer type
+    fn infer_integer_expression(&mut self, expression: &mut ExpressionStatement, target_type: TypeReference) -> bool {
+        match *expression {
+            ExpressionStatement::Literal(ref mut literal) => self.infer_integer_literal(literal, false, target_type),
+            ExpressionStatement::Prefix(ref mut prefix) if prefix.operator == PrefixOperator::Negate => {
+                let is_inferred = match *prefix.expression {
+                    ExpressionStatement::Literal(ref mut literal) => self.infer_integer_literal(literal, true, target_type),
+                    _ => false,
+                };
+                if is_inferred {
+                    prefix.prefix_type = Some(target_type);
+                }
+                is_inferred
+            },
+            _ => false,
+        }
+    }
+
+    /// types an integer literal as the integer type it is used as, reporting literals that don't fit its range
+    /// returns false if `target_type` is no integer type
+    fn infer_integer_literal(&mut self, literal: &mut LiteralExpression, negated: bool, target_type: TypeReference) -> bool {
+        if literal.literal_expression_type != LiteralType::Int {
+            return false;
+        }
+
+        let type_name = self.type_name(target_type);
+        let (min, max) = match INTEGER_RANGES.iter().find(|&&(name, _, _)| name == type_name) {
+            Some(&(_, min, max)) => (min, max),
+            None => return false,
+        };
+
+        let value = literal.value.parse::<i128>().map(|value| if negated { -value } else { value });
+        match value {
+            Ok(value) if value >= min && value <= max => literal.literal_type = Some(target_type),
+            _ => {
+                let value = if negated { format!("-{}", literal.value) } else { literal.value.to_owned() };
+                result_mut!(self).add_error(Box::new(TypeError::new(literal.span, ErrorKind::LiteralOutOfRange(value, type_name))));
+            },
+        }
+
+        true
+    }
+
+    /// replaces a checked `sizeof(T)` or `alignof(T)` by its value as `u32` literal
+    fn fold_layout_query(&mut self, expression: &mut ExpressionStatement) {
+        let (value, literal_type) = match *expression {
+            ExpressionStatement::Call(ref call) if call.function_name.name == "sizeof" || call.function_name.name == "alignof" => {
+                let literal_type = match call.function_type {
+                    Some(t) => t,
+                    None => return,
+                };
+
+                let type_name = &call.type_arguments[0];
+                let layout = symbol_table!(self).find_type_ref(&type_name.name).and_then(|t| layout_of(&symbol_table!(self), t, self.layout_rules));
+                match layout {
+                    Some(layout) if call.function_name.name == "sizeof" => (layout.size, literal_type),
+                    Some(layout) => (layout.alignment, literal_type),
+                    None => pass_try!(self, Err(TypeError::new(type_name.span, ErrorKind::UnsizedTypeInSizeof(type_name.name.to_owned())))),
+                }
+            },
+            _ => return,
+        };
+
+        *expression = ExpressionStatement::Literal(LiteralExpression {
+            span: expression.get_span(),
+            value: value.to_string(),
+            literal_expression_type: LiteralType::Int,
+            literal_type: Some(literal_type),
+        });
+    }
+
+    /// checks the body of a function or operator
+    fn check_body(&mut self, arguments: &[FunctionArgumentDeclaration], return_type: Option<TypeReference>, block: &mut BlockDeclaration) {
+        self.return_type = return_type;
+        self.constants = self.module_constants.clone();
+        self.constant_values = self.module_constant_values.clone();
+        symbol_table_mut!(self).enter_scope();
+
+        let mut argument_names = HashSet::new();
+        for argument in arguments.iter() {
+            let argument_type = match argument.argument_type {
+                Some(t) => t,
+                None => continue,
+            };
+
+            // duplicate names are reported by the signatures pass
+            if !argument_names.insert(argument.argument_name.name.as_str()) {
+                continue;
+            }
+
+            if let Err(error) = symbol_table_mut!(self).add_symbol_with_type(&argument.argument_name.name, argument_type) {
+                result_mut!(self).add_error(Box::new(error));
+            }
+
+            if argument.is_out {
+                self.uninitialized.insert(argument.argument_name.name.to_owned());
+                self.out_parameters.insert(argument.argument_name.name.to_owned());
+            }
+        }
+
+        self.visit_block(block);
+
+        // falling off the end of the body returns as well
+        match block.statements.last() {
+            Some(&BlockStatement::Return(_)) => (),
+            _ => self.check_out_parameters_assigned(block.span),
+        }
+
+        self.check_unused_locals(block);
+        self.infer_local_types(block);
+
+        symbol_table_mut!(self).leave_scope();
+        self.return_type = None;
+        self.uninitialized.clear();
+        self.out_parameters.clear();
+        self.partial_structs.clear();
+        self.constants.clear();
+        self.constant_values.clear();
+        self.loop_depth = 0;
+    }
+
+    /// checks the initializer of a module constant, it has to have the declared type and fold at compile time
+    /// the constant is declared as a symbol, so later constants and function bodies can use it
+    fn check_constant(&mut self, constant: &mut ConstantDefinition) {
+        // unknown types are left to the passes checking declarations
+        let constant_type = match symbol_table!(self).find_type_ref(&constant.constant_type_name.name) {
+            Some(t) => t,
+            None => return,
+        };
+        constant.constant_type = Some(constant_type);
+
+        let constant_name = &constant.constant_name.name;
+        if let Err(error) = symbol_table_mut!(self).add_symbol_with_type(constant_name, constant_type) {
+            result_mut!(self).add_error(Box::new(error));
+        }
+        self.module_constants.insert(constant_name.to_owned());
+
+        let expression = match constant.expression {
+            Some(ref mut expression) => expression,
+            None => return,
+        };
+
+        self.visit_expression(expression);
+        let expression_type = match expression.get_type() {
+            Some(t) => t,
+            None => return,
+        };
+
+        if !self.coerce_expression(expression, constant_type) {
+            let expected = self.type_name(constant_type);
+            let found = self.type_name(expression_type);
+            pass_try!(self, Err(TypeError::new(expression.get_span(), ErrorKind::ConstantTypeMismatch(constant_name.to_owned(), expected, found))));
+        }
+
+        match const_eval::evaluate_with(expression, &self.const_functions, &self.module_constant_values) {
+            Ok(value) => {
+                self.module_constant_values.insert(constant_name.to_owned(), value);
+            },
+            Err(_) => result_mut!(self).add_error(Box::new(TypeError::new(expression.get_span(), ErrorKind::NonConstantInitializer(constant_name.to_owned())))),
+        }
+    }
+
+    fn check_condition(&mut self, condition: &ExpressionStatement) {
+        if let Some(condition_type) = condition.get_type() {
+            if symbol_table!(self).find_type_ref("bool") != Some(condition_type) {
+                let type_name = self.type_name(condition_type);
+                result_mut!(self).add_error(Box::new(TypeError::new(condition.get_span(), ErrorKind::NonBooleanCondition(type_name))));
+            }
+        }
+    }
+
+    /// checks one branch of an `if` in its own scope, leaves the locals it initializes in `uninitialized`
+    fn visit_branch(&mut self, block: &mut BlockDeclaration) {
+        let constants = (self.constants.clone(), self.constant_values.clone());
+        symbol_table_mut!(self).enter_scope();
+
+        self.visit_block(block);
+        self.check_unused_locals(block);
+        self.infer_local_types(block);
+
+        symbol_table_mut!(self).leave_scope();
+        self.constants = constants.0;
+        self.constant_values = constants.1;
+    }
+
+    /// functions with a return type other than `void` have to end in a return on every path
+    fn check_returns(&mut self, name: &str, return_type_name: &Identifier, block: &BlockDeclaration) {
+        if return_type_name.name == "void" || all_paths_return(block) {
+            return;
+        }
+
+        let kind = if block_contains_return(block) {
+            ErrorKind::MissingReturnOnPath(name.to_owned())
+        } else {
+            ErrorKind::MissingReturn(name.to_owned())
+        };
+        result_mut!(self).add_error(Box::new(TypeError::new(block.span, kind)));
+    }
+
+    /// every `out` parameter has to be assigned when the function returns
+    fn check_out_parameters_assigned(&mut self, span: Span) {
+        let mut unassigned: Vec<String> = self.out_parameters.intersection(&self.uninitialized).cloned().collect();
+        unassigned.sort();
+
+        for name in unassigned {
+            result_mut!(self).add_error(Box::new(TypeError::new(span, ErrorKind::OutParamNotAssigned(name))));
+        }
+    }
+
+    /// warns about locals of `block` that are never read, names starting with `_` are exempt
+    /// has to run before the scope of `block` is left and before `infer_local_types` looks the locals up
+    fn check_unused_locals(&mut self, block: &BlockDeclaration) {
+        for statement in block.statements.iter() {
+            if let BlockStatement::Local(ref local_statement) = *statement {
+                let symbol_name = &local_statement.symbol_name.name;
+                if !symbol_name.starts_with('_') && !symbol_table!(self).is_symbol_read(symbol_name) {
+                    pass_warning!(self, TypeError::new(local_statement.symbol_name.span, ErrorKind::UnusedLocal(symbol_name.to_owned())));
+                }
+            }
+        }
+    }
+
+    /// locals declared without initializer get their type from the first assignment
+    /// has to run before the scope of `block` is left
+    fn infer_local_types(&mut self, block: &mut BlockDeclaration) {
+        for statement in block.statements.iter_mut() {
+            if let BlockStatement::Local(ref mut local_statement) = *statement {
+                if local_statement.local_type.is_some() || local_statement.expression.is_some() {
+                    continue;
+                }
+
+                let symbol_name = &local_statement.symbol_name.name;
+                local_statement.local_type = symbol_table_mut!(self).find_symbol(symbol_name).and_then(|s| s.get_type());
+                if local_statement.local_type.is_none() {
+                    result_mut!(self).add_error(Box::new(TypeError::new(local_statement.span, ErrorKind::CannotInfer(symbol_name.to_owned()))));
+                }
+            }
+        }
+    }
+}
+
+/// whether all fields of the struct and of its bases declare a default value
+/// bases of other modules and cyclic bases count as having none
+fn struct_has_defaults(struct_definition: &StructDefinition, items: &Ast) -> bool {
+    let mut visited = Vec::new();
+    let mut current = Some(struct_definition);
+    while let Some(s) = current {
+        if visited.contains(&&s.struct_name.name) || !s.struct_member.iter().all(|m| m.default_value.is_some()) {
+            return false;
+        }
+        visited.push(&s.struct_name.name);
+
+        current = match s.base_struct_name {
+            Some(ref base_name) => match items.iter().find(|item| match **item {
+                ItemKind::Struct(ref b) => b.struct_name.name == base_name.name,
+                _ => false,
+            }) {
+                Some(&ItemKind::Struct(ref b)) => Some(b),
+                _ => return false,
+            },
+            None => None,
+        };
+    }
+    true
+}
+
+ast_pass_impl!(CheckFunctionBodiesPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        self.const_functions = ConstFunctions::from_ast(items);
+
+        for item in items.iter() {
+            if let ItemKind::Struct(ref s) = *item {
+                self.struct_defaults.insert(s.struct_name.name.to_owned(), struct_has_defaults(s, items));
+            }
+        }
+
+        // constants first, so every body sees them
+        for item in items.iter_mut() {
+            if let ItemKind::Constant(ref mut item) = *item {
+                self.check_constant(item);
+            }
+        }
+
+        for item in items.iter_mut() {
+            match *item {
+                ItemKind::Function(ref mut item) => self.visit_function(item),
+                ItemKind::Operator(ref mut item) => self.visit_operator(item),
+                ItemKind::Program(ref mut item) => {
+                    for stage in item.program_stages.iter_mut() {
+                        self.visit_function(&mut stage.function);
+                    }
+                },
+                _ => (),
+            };
+        }
+    }
+
+    fn visit_function(&mut self, function_declaration: &mut FunctionDeclaration) {
+        self.check_body(&function_declaration.arguments, function_declaration.return_type, &mut function_declaration.block);
+        self.check_returns(&function_declaration.function_name.name, &function_declaration.return_type_name, &function_declaration.block);
+    }
+
+    fn visit_operator(&mut self, operator_declaration: &mut OperatorDeclaration) {
+        if let Some(ref mut block) = operator_declaration.block {
+            self.check_body(&operator_declaration.arguments, operator_declaration.operator_type, block);
+            self.check_returns(operator_declaration.operator.get_symbol(), &operator_declaration.return_type, block);
+        }
+    }
+
+    fn visit_block(&mut self, block: &mut BlockDeclaration) {
+        self.walk_block(block);
+
+        // unreachable statements are still checked, only the first one is reported
+        if let Some(index) = block.statements.iter().position(statement_terminates) {
+            if let Some(statement) = block.statements.get(index + 1) {
+                pass_warning!(self, TypeError::new(statement.get_span(), ErrorKind::UnreachableStatement));
+            }
+        }
+    }
+
+    fn visit_local_statement(&mut self, local_statement: &mut LocalDeclaration) {
+        self.walk_local_statement(local_statement);
+
+        let declared_type = match local_statement.local_type_name {
+            Some(ref type_name) => match symbol_table!(self).find_type_ref(&type_name.name) {
+                Some(t) => Some(t),
+                None => pass_try!(self, Err(TypeError::new(type_name.span, ErrorKind::TypeNotFound(type_name.name.to_owned())))),
+            },
+            None => None,
+        };
+
+        if let (Some(declared_type), Some(ref mut expression)) = (declared_type, local_statement.expression.as_mut()) {
+            if !self.coerce_expression(expression, declared_type) {
+                let declared_name = self.type_name(declared_type);
+                let expression_name = expression.get_type().map(|t| self.type_name(t)).unwrap_or_default();
+                result_mut!(self).add_error(Box::new(TypeError::new(local_statement.span, ErrorKind::IncompatibleTypes(local_statement.symbol_name.span, expression.get_span(), declared_name, expression_name))));
+            }
+        }
+
+        // literals without a declared type keep their default type, which still has to hold the value
+        if let (None, Some(expression)) = (declared_type, local_statement.expression.as_mut()) {
+            if let Some(literal_type) = expression.get_type() {
+                self.infer_integer_expression(expression, literal_type);
+            }
+        }
+
+        let symbol_name = &local_statement.symbol_name.name;
+        if symbol_table!(self).find_type_ref(symbol_name).is_some() {
+            pass_warning!(self, TypeError::new(local_statement.symbol_name.span, ErrorKind::LocalShadowsType(symbol_name.to_owned())));
+        }
+
+        if local_statement.expression.is_none() {
+            self.uninitialized.insert(symbol_name.to_owned());
+        }
+
+        self.track_partial_struct(symbol_name, local_statement.expression.as_ref());
+
+        // a local shadows a constant of the same name
+        self.constants.remove(symbol_name);
+        self.constant_values.remove(symbol_name);
+
+        // an untyped expression already produced an error, add the symbol anyway to avoid follow-up errors
+        local_statement.local_type = declared_type.or(local_statement.expression.as_ref().and_then(|e| e.get_type()));
+        match local_statement.local_type {
+            Some(local_type) => pass_try!(self, symbol_table_mut!(self).add_symbol_with_type(symbol_name, local_type)),
+            None => pass_try!(self, symbol_table_mut!(self).add_symbol(symbol_name)),
+        }
+    }
+
+    fn visit_const_statement(&mut self, const_statement: &mut LocalConstantDeclaration) {
+        self.walk_const_statement(const_statement);
+
+        let type_name = &const_statement.constant_type_name;
+        let constant_type = match symbol_table!(self).find_type_ref(&type_name.name) {
+            Some(t) => t,
+            None => pass_try!(self, Err(TypeError::new(type_name.span, ErrorKind::TypeNotFound(type_name.name.to_owned())))),
+        };
+
+        if !self.coerce_expression(&mut const_statement.expression, constant_type) {
+            let declared_name = self.type_name(constant_type);
+            let expression_name = const_statement.expression.get_type().map(|t| self.type_name(t)).unwrap_or_default();
+            result_mut!(self).add_error(Box::new(TypeError::new(const_statement.span, ErrorKind::IncompatibleTypes(const_statement.constant_name.span, const_statement.expression.get_span(), declared_name, expression_name))));
+        }
+
+        // a constant that doesn't fold is still immutable, it just can't be used in other constant expressions
+        let constant_name = &const_statement.constant_name.name;
+        match const_eval::evaluate_with(&const_statement.expression, &self.const_functions, &self.constant_values) {
+            Ok(value) => {
+                self.constant_values.insert(constant_name.to_owned(), value);
+            },
+            Err(error) => {
+                self.constant_values.remove(constant_name);
+                result_mut!(self).add_error(Box::new(error));
+            },
+        }
+        self.constants.insert(constant_name.to_owned());
+
+        const_statement.constant_type = Some(constant_type);
+        pass_try!(self, symbol_table_mut!(self).add_symbol_with_type(constant_name, constant_type));
+    }
+
+    fn visit_assignment_statement(&mut self, assignment_statement: &mut AssignmentStatement) {
+        self.walk_assignment_statement(assignment_statement);
+
+        let symbol_name = &assignment_statement.symbol_name.name;
+        if self.constants.contains(symbol_name) {
+            pass_try!(self, Err(TypeError::new(assignment_statement.span, ErrorKind::AssignToConstant(symbol_name.to_owned()))));
+        }
+
+        // assigning doesn't read the symbol
+        let symbol_type = match symbol_table_mut!(self).find_symbol_mut(symbol_name) {
+            Some(symbol) => symbol.get_type(),
+            None => pass_try!(self, Err(TypeError::new(assignment_statement.symbol_name.span, ErrorKind::VariableNotFound(symbol_name.to_owned())))),
+        };
+        self.uninitialized.remove(symbol_name);
+        self.track_partial_struct(symbol_name, Some(&assignment_statement.expression));
+
+        let expression_type = match assignment_statement.expression.get_type() {
+            Some(t) => t,
+            None => return,
+        };
+
+        match symbol_type {
+            Some(symbol_type) => {
+                if !self.coerce_expression(&mut assignment_statement.expression, symbol_type) {
+                    let symbol_type_name = self.type_name(symbol_type);
+                    let expression_type_name = self.type_name(expression_type);
+                    pass_try!(self, Err(TypeError::new(assignment_statement.span, ErrorKind::IncompatibleTypes(assignment_statement.symbol_name.span, assignment_statement.expression.get_span(), symbol_type_name, expression_type_name))));
+                }
+            },
+            None => pass_try!(self, symbol_table_mut!(self).resolve_symbol_type(symbol_name, expression_type)),
+        }
+    }
+
+    fn visit_return_statement(&mut self, return_statement: &mut ReturnDeclaration) {
+        self.walk_return_statement(return_statement);
+        self.check_out_parameters_assigned(return_statement.span);
+
+        if let Some(return_type) = self.return_type {
+            if !self.coerce_expression(&mut return_statement.expression, return_type) {
+                let expected = self.type_name(return_type);
+                let found = return_statement.expression.get_type().map(|t| self.type_name(t)).unwrap_or_default();
+                result_mut!(self).add_error(Box::new(TypeError::new(return_statement.span, ErrorKind::ReturnTypeMismatch(expected, found))));
+            }
+        }
+
+        return_statement.return_type = return_statement.expression.get_type();
+    }
+
+    fn visit_if_statement(&mut self, if_statement: &mut IfStatement) {
+        self.visit_expression(&mut if_statement.condition);
+        self.check_condition(&if_statement.condition);
+
+        // a local is initialized after the branch if it is initialized on both paths
+        let uninitialized = self.uninitialized.clone();
+        let mut branch_uninitialized = self.uninitialized.clone();
+        self.visit_branch(&mut if_statement.block);
+
+        if let Some(ref mut else_block) = if_statement.else_block {
+            branch_uninitialized = self.uninitialized.clone();
+            self.uninitialized = uninitialized;
+            self.visit_branch(else_block);
+        }
+
+        self.uninitialized = self.uninitialized.union(&branch_uninitialized).cloned().collect();
+    }
+
+    fn visit_while_statement(&mut self, while_statement: &mut WhileStatement) {
+        self.visit_expression(&mut while_statement.condition);
+        self.check_condition(&while_statement.condition);
+
+        // the body might not run, so its assignments don't initialize locals declared before the loop
+        let uninitialized = self.uninitialized.clone();
+        let constants = (self.constants.clone(), self.constant_values.clone());
+        self.loop_depth += 1;
+        symbol_table_mut!(self).enter_scope();
+
+        self.visit_block(&mut while_statement.block);
+        self.check_unused_locals(&while_statement.block);
+        self.infer_local_types(&mut while_statement.block);
+
+        symbol_table_mut!(self).leave_scope();
+        self.loop_depth -= 1;
+        self.uninitialized = uninitialized;
+        self.constants = constants.0;
+        self.constant_values = constants.1;
+    }
+
+    fn visit_for_statement(&mut self, for_statement: &mut ForStatement) {
+        self.visit_expression(&mut for_statement.from);
+        self.visit_expression(&mut for_statement.to);
+
+        // shaders have to stay bounded, only loops marked as dynamic may depend on runtime values
+        if !for_statement.is_dynamic() {
+            let from = const_eval::evaluate_with(&for_statement.from, &self.const_functions, &self.constant_values);
+            let to = const_eval::evaluate_with(&for_statement.to, &self.const_functions, &self.constant_values);
+
+            match (from, to) {
+                (Ok(ConstValue::Int(from)), Ok(ConstValue::Int(to))) if from > to => {
+                    result_mut!(self).add_error(Box::new(TypeError::new(for_statement.span, ErrorKind::InvalidLoopRange(from.to_string(), to.to_string()))));
+                },
+                (Err(error), _) | (_, Err(error)) => result_mut!(self).add_error(Box::new(error)),
+                _ => (),
+            }
+        }
+
+        let uninitialized = self.uninitialized.clone();
+        let constants = (self.constants.clone(), self.constant_values.clone());
+        self.loop_depth += 1;
+        symbol_table_mut!(self).enter_scope();
+
+        let loop_variable = &for_statement.loop_variable.name;
+        self.constants.remove(loop_variable);
+        self.constant_values.remove(loop_variable);
+        match for_statement.from.get_type() {
+            Some(from_type) => pass_try!(self, symbol_table_mut!(self).add_symbol_with_type(loop_variable, from_type)),
+            None => pass_try!(self, symbol_table_mut!(self).add_symbol(loop_variable)),
+        }
+
+        self.visit_block(&mut for_statement.block);
+        self.check_unused_locals(&for_statement.block);
+        self.infer_local_types(&mut for_statement.block);
+
+        symbol_table_mut!(self).leave_scope();
+        self.loop_depth -= 1;
+        self.uninitialized = uninitialized;
+        self.constants = constants.0;
+        self.constant_values = constants.1;
+    }
+
+    fn visit_match_statement(&mut self, match_statement: &mut MatchStatement) {
+        self.visit_expression(&mut match_statement.scrutinee);
+
+        let scrutinee_type = match match_statement.scrutinee.get_type() {
+            Some(t) => t,
+            None => return,
+        };
+        let type_name = self.type_name(scrutinee_type);
+        let variants = symbol_table!(self).find_type(scrutinee_type).and_then(|t| t.get_variants()).map(|v| v.to_vec());
+        let is_integer = INTEGER_RANGES.iter().any(|&(name, _, _)| name == type_name);
+        if variants.is_none() && !is_integer {
+            result_mut!(self).add_error(Box::new(TypeError::new(match_statement.scrutinee.get_span(), ErrorKind::InvalidMatchScrutinee(type_name))));
+        }
+
+        // like the branches of an `if`, a local is initialized after an exhaustive match if every arm initializes it
+        let uninitialized = self.uninitialized.clone();
+        let mut arms_uninitialized = HashSet::new();
+        let mut covered_variants = Vec::new();
+        let mut has_wildcard = false;
+        for arm in match_statement.arms.iter_mut() {
+            let pattern_span = arm.pattern.get_span();
+            let pattern_type = match arm.pattern {
+                MatchPattern::Literal(ref mut literal) => {
+                    if is_integer {
+                        self.infer_integer_literal(literal, false, scrutinee_type);
+                    }
+                    literal.literal_type
+                },
+                MatchPattern::EnumVariant(ref mut variant) => {
+                    self.visit_enum_variant_expression(variant);
+                    covered_variants.push(variant.variant_name.name.to_owned());
+                    variant.variant_type
+                },
+                MatchPattern::Wildcard(_) => {
+                    has_wildcard = true;
+                    Some(scrutinee_type)
+                },
+            };
+
+            if pattern_type != Some(scrutinee_type) {
+                let pattern_name = pattern_type.map(|t| self.type_name(t)).unwrap_or_default();
+                let scrutinee_name = self.type_name(scrutinee_type);
+                result_mut!(self).add_error(Box::new(TypeError::new(pattern_span, ErrorKind::IncompatibleTypes(pattern_span, match_statement.scrutinee.get_span(), pattern_name, scrutinee_name))));
+            }
+
+            self.uninitialized = uninitialized.clone();
+            let constants = (self.constants.clone(), self.constant_values.clone());
+            symbol_table_mut!(self).enter_scope();
+
+            self.visit_block(&mut arm.block);
+            self.check_unused_locals(&arm.block);
+            self.infer_local_types(&mut arm.block);
+
+            symbol_table_mut!(self).leave_scope();
+            arms_uninitialized.extend(self.uninitialized.drain());
+            self.constants = constants.0;
+            self.constant_values = constants.1;
+        }
+
+        // integers can't be covered by listing values, so they always need a wildcard
+        let is_exhaustive = has_wildcard || variants.map_or(false, |v| v.iter().all(|v| covered_variants.contains(v)));
+        self.uninitialized = if is_exhaustive { arms_uninitialized } else { uninitialized };
+        if !is_exhaustive {
+            result_mut!(self).add_error(Box::new(TypeError::new(match_statement.span, ErrorKind::NonExhaustiveMatch)));
+        }
+    }
+
+    fn visit_struct_instantiation_expression(&mut self, struct_instantiation_expression: &mut StructInstantiationExpression) {
+        self.walk_struct_instantiation_expression(struct_instantiation_expression);
+
+        let struct_name = &struct_instantiation_expression.struct_type_name;
+        let struct_type = match symbol_table!(self).find_type_ref(&struct_name.name) {
+            Some(t) => t,
+            None => pass_try!(self, Err(TypeError::new(struct_name.span, ErrorKind::TypeNotFound(struct_name.name.to_owned())))),
+        };
+
+        if struct_instantiation_expression.uses_defaults && self.struct_defaults.get(&struct_name.name) != Some(&true) {
+            pass_try!(self, Err(TypeError::new(struct_instantiation_expression.span, ErrorKind::NoDefaultConstructor(struct_name.name.to_owned()))));
+        }
+
+        struct_instantiation_expression.struct_type = Some(struct_type);
+
+        let struct_name = struct_name.name.to_owned();
+
+        if let Some(ref base) = struct_instantiation_expression.base {
+            if let Some(base_type) = base.get_type() {
+                if base_type != struct_type {
+                    let base_name = self.type_name(base_type);
+                    result_mut!(self).add_error(Box::new(TypeError::new(base.get_span(), ErrorKind::IncompatibleTypes(struct_instantiation_expression.struct_type_name.span, base.get_span(), struct_name.to_owned(), base_name))));
+                }
+            }
+
+            // fields neither initialized here nor anywhere along the chain of bases
+            if let Some(fields) = self.initialized_fields(struct_instantiation_expression) {
+                let members: Vec<String> = symbol_table!(self).find_type(struct_type)
+                    .and_then(|t| t.get_member())
+                    .map(|m| m.iter().map(|m| m.member_name.to_owned()).collect())
+                    .unwrap_or_default();
+                for member in members.into_iter().filter(|m| !fields.contains(m)) {
+                    pass_warning!(self, TypeError::new(struct_instantiation_expression.span, ErrorKind::PossiblyUninitializedField(member)));
+                }
+            }
+        }
+
+        let mut initialized_fields: Vec<String> = Vec::new();
+        for field in struct_instantiation_expression.struct_field_initializer.iter_mut() {
+            let field_name = field.struct_field_name.name.to_owned();
+            if initialized_fields.contains(&field_name) {
+                result_mut!(self).add_error(Box::new(TypeError::new(field.span, ErrorKind::DuplicateFieldInitializer(field_name))));
+                continue;
+            }
+            initialized_fields.push(field_name.to_owned());
+
+            let member_type = match symbol_table!(self).find_type(struct_type).and_then(|t| t.find_member_type(&field_name)) {
+                Some(t) => t,
+                None => {
+                    result_mut!(self).add_error(Box::new(TypeError::new(field.struct_field_name.span, ErrorKind::UnknownStructField(struct_name.to_owned(), field_name))));
+                    continue;
+                },
+            };
+            field.struct_field_type = Some(member_type);
+
+            if !self.coerce_expression(&mut field.initializer, member_type) {
+                let expected = self.type_name(member_type);
+                let found = field.initializer.get_type().map(|t| self.type_name(t)).unwrap_or_default();
+                result_mut!(self).add_error(Box::new(TypeError::new(field.span, ErrorKind::StructFieldTypeMismatch(field_name, expected, found))));
+            }
+        }
+
+        // fields may only be left out if they are taken from a base or their defaults
+        if struct_instantiation_expression.base.is_none() && !struct_instantiation_expression.uses_defaults {
+            let missing_fields: Vec<String> = symbol_table!(self).find_type(struct_type)
+                .and_then(|t| t.get_member())
+                .map(|m| m.iter().map(|m| m.member_name.to_owned()).filter(|m| !initialized_fields.contains(m)).collect())
+                .unwrap_or_default();
+            if !missing_fields.is_empty() {
+                result_mut!(self).add_error(Box::new(TypeError::new(struct_instantiation_expression.span, ErrorKind::MissingStructFields(struct_name, missing_fields))));
+            }
+        }
+    }
+
+    fn visit_enum_variant_expression(&mut self, enum_variant_expression: &mut EnumVariantExpression) {
+        let enum_name = &enum_variant_expression.enum_name;
+        let variant_name = &enum_variant_expression.variant_name.name;
+        let enum_type = match symbol_table!(self).find_type_ref(&enum_name.name) {
+            Some(t) => t,
+            None => pass_try!(self, Err(TypeError::new(enum_name.span, ErrorKind::TypeNotFound(enum_name.name.to_owned())))),
+        };
+
+        let has_variant = symbol_table!(self).find_type(enum_type).and_then(|t| t.get_variants()).map_or(false, |v| v.contains(variant_name));
+        if !has_variant {
+            pass_try!(self, Err(TypeError::new(enum_variant_expression.span, ErrorKind::UnknownEnumVariant(enum_name.name.to_owned(), variant_name.to_owned()))));
+        }
+
+        enum_variant_expression.variant_type = Some(enum_type);
+    }
+
+    fn visit_loop_control_statement(&mut self, loop_control_statement: &mut LoopControlStatement) {
+        if self.loop_depth == 0 {
+            let keyword = loop_control_statement.loop_control.get_keyword().to_owned();
+            pass_try!(self, Err(TypeError::new(loop_control_statement.span, ErrorKind::LoopControlOutsideLoop(keyword))));
+        }
+    }
+
+    fn visit_prefix_expression(&mut self, prefix_expression: &mut PrefixExpression) {
+        self.walk_prefix_expression(prefix_expression);
+
+        let operand_type = match prefix_expression.expression.get_type() {
+            Some(t) => t,
+            None => return,
+        };
+
+        let is_defined = match prefix_expression.operator {
+            PrefixOperator::Negate => builtins::is_numeric(&symbol_table!(self), operand_type),
+            PrefixOperator::Not => symbol_table!(self).find_type_ref("bool") == Some(operand_type),
+        };
+        if !is_defined {
+            let operator = prefix_expression.operator.get_symbol().to_owned();
+            let type_name = self.type_name(operand_type);
+            pass_try!(self, Err(TypeError::new(prefix_expression.span, ErrorKind::UnaryOperatorNotDefined(operator, type_name))));
+        }
+
+        prefix_expression.prefix_type = Some(operand_type);
+    }
+
+    fn visit_literal_expression(&mut self, literal_expression: &mut LiteralExpression) {
+        let type_name = match literal_expression.literal_expression_type {
+            LiteralType::Int => "i32",
+            LiteralType::Float => "f32",
+            LiteralType::Bool => "bool",
+            LiteralType::String => pass_try!(self, Err(TypeError::new(literal_expression.span, ErrorKind::UnexpectedStringLiteral))),
+        };
+
+        let literal_type = match symbol_table!(self).find_type_ref(type_name) {
+            Some(t) => t,
+            None => pass_try!(self, Err(TypeError::new(literal_expression.span, ErrorKind::TypeNotFound(type_name.to_owned())))),
+        };
+        literal_expression.literal_type = Some(literal_type);
+    }
+
+    fn visit_variable_expression(&mut self, variable_expression: &mut VariableExpression) {
+        let variable_name = &variable_expression.variable_name.name;
+        if symbol_table!(self).is_type_name(variable_name) {
+            pass_try!(self, Err(TypeError::new(variable_expression.span, ErrorKind::TypeUsedAsValue(variable_name.to_owned()))));
+        }
+
+        pass_try!(self, self.check_initialized(variable_name, variable_expression.span));
+
+        let variable_type = match symbol_table_mut!(self).find_symbol(&variable_expression.variable_name.name) {
+            Some(symbol) => symbol.get_type(),
+            None => pass_try!(self, Err(TypeError::new(variable_expression.span, ErrorKind::VariableNotFound(variable_expression.variable_name.name.to_owned())))),
+        };
+        variable_expression.variable_type = variable_type;
+    }
+
+    fn visit_field_accessor_expression(&mut self, field_accessor_expression: &mut FieldAccessorExpression) {
+        let variable_name = &field_accessor_expression.variable_name.name;
+        pass_try!(self, self.check_initialized(variable_name, field_accessor_expression.variable_name.span));
+
+        let variable_type = match symbol_table_mut!(self).find_symbol(variable_name) {
+            Some(symbol) => symbol.get_type(),
+            None => pass_try!(self, Err(TypeError::new(field_accessor_expression.variable_name.span, ErrorKind::VariableNotFound(variable_name.to_owned())))),
+        };
+        let variable_type = match variable_type {
+            Some(t) => t,
+            None => return,
+        };
+
+        let swizzle = builtins::resolve_swizzle(&symbol_table!(self), field_accessor_expression.field_name.span, variable_type, &field_accessor_expression.field_name.name);
+        if let Some(swizzle_type) = swizzle {
+            field_accessor_expression.field_type = Some(pass_try!(self, swizzle_type));
+            return;
+        }
+
+        let type_name = self.type_name(variable_type);
+        if !self.is_struct(variable_type) {
+            pass_try!(self, Err(TypeError::new(field_accessor_expression.span, ErrorKind::FieldAccessOnNonStruct(type_name.to_owned()))));
+        }
+
+        let field_name = &field_accessor_expression.field_name;
+        match symbol_table!(self).find_type(variable_type).and_then(|t| t.find_member_type(&field_name.name)) {
+            Some(field_type) => field_accessor_expression.field_type = Some(field_type),
+            None => pass_try!(self, Err(TypeError::new(field_name.span, ErrorKind::UnknownStructField(type_name, field_name.name.to_owned())))),
+        }
+    }
+
+    fn visit_index_accessor_expression(&mut self, index_accessor_expression: &mut IndexAccesorExpression) {
+        self.walk_index_accessor_expression(index_accessor_expression);
+
+        let variable_name = &index_accessor_expression.variable_name.name;
+        pass_try!(self, self.check_initialized(variable_name, index_accessor_expression.variable_name.span));
+
+        let variable_type = match symbol_table_mut!(self).find_symbol(variable_name) {
+            Some(symbol) => symbol.get_type(),
+            None => pass_try!(self, Err(TypeError::new(index_accessor_expression.variable_name.span, ErrorKind::VariableNotFound(variable_name.to_owned())))),
+        };
+        let variable_type = match variable_type {
+            Some(t) => t,
+            None => return,
+        };
+
+        let element_type = match builtins::index_element_type(&symbol_table!(self), variable_type) {
+            Some(t) => t,
+            None => pass_try!(self, Err(TypeError::new(index_accessor_expression.span, ErrorKind::IndexOnNonArray(self.type_name(variable_type))))),
+        };
+
+        if let Some(index_type) = index_accessor_expression.access_expression.get_type() {
+            let index_type_name = self.type_name(index_type);
+            if !INTEGER_RANGES.iter().any(|&(name, _, _)| name == index_type_name) {
+                let error = TypeError::new(index_accessor_expression.access_expression.get_span(), ErrorKind::NonIntegerIndex(index_type_name));
+                result_mut!(self).add_error(Box::new(error));
+            }
+        }
+
+        index_accessor_expression.element_type = Some(element_type);
+    }
+
+    fn visit_expression(&mut self, expression_statement: &mut ExpressionStatement) {
+        // calling a type with a single argument casts the argument to it
+        let cast_target = match *expression_statement {
+            ExpressionStatement::Call(ref call) if call.arguments.len() == 1 && call.type_arguments.is_empty() => {
+                let function_name = &call.function_name.name;
+                if symbol_table!(self).is_type_name(function_name) {
+                    symbol_table!(self).find_type_ref(function_name)
+                } else {
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        if let Some(target_type) = cast_target {
+            expression_statement.convert_call_to_explicit_cast(target_type);
+        }
+
+        self.walk_expression(expression_statement);
+        self.fold_layout_query(expression_statement);
+    }
+
+    fn visit_cast_expression(&mut self, cast_expression: &mut CastExpression) {
+        self.walk_cast_expression(cast_expression);
+
+        // implicit casts are only inserted between different types after checking
+        if cast_expression.cast_type != CastType::Explicit {
+            return;
+        }
+
+        if let (None, Some(ref type_name)) = (cast_expression.cast_target_type, cast_expression.cast_target_type_name.as_ref()) {
+            match symbol_table!(self).find_type_ref(&type_name.name) {
+                Some(t) => cast_expression.cast_target_type = Some(t),
+                None => pass_try!(self, Err(TypeError::new(type_name.span, ErrorKind::TypeNotFound(type_name.name.to_owned())))),
+            }
+        }
+
+        let (source_type, target_type) = match (cast_expression.expression.get_type(), cast_expression.cast_target_type) {
+            (Some(source_type), Some(target_type)) => (source_type, target_type),
+            _ => return,
+        };
+
+        if source_type == target_type {
+            let type_name = self.type_name(target_type);
+            result_mut!(self).add_lint(self.lints.redundant_cast, Box::new(TypeError::new(cast_expression.span, ErrorKind::RedundantCast(type_name))));
+            return;
+        }
+
+        let cast_exists = symbol_table!(self).find_type(source_type).is_some_and(|t| t.does_cast_exist(target_type));
+        if !cast_exists {
+            let source_name = self.type_name(source_type);
+            let target_name = self.type_name(target_type);
+            result_mut!(self).add_error(Box::new(TypeError::new(cast_expression.span, ErrorKind::CastNotDeclared(source_name, target_name))));
+        }
+    }
+
+    fn visit_call_expression(&mut self, call_expression: &mut CallExpression) {
+        self.walk_call_expression(call_expression);
+
+        // all signatures are registered before bodies are checked, so calls may refer to functions declared later
+        let function_name = &call_expression.function_name.name;
+        let symbol = symbol_table_mut!(self).find_symbol(function_name).map(|s| s.get_type());
+        let function_type = match symbol {
+            Some(function_type) => function_type,
+            None if builtins::is_builtin(function_name) => {
+                let mut type_arguments = Vec::new();
+                for type_argument in call_expression.type_arguments.iter() {
+                    match symbol_table!(self).find_type_ref(&type_argument.name) {
+                        Some(t) => type_arguments.push(t),
+                        None => pass_try!(self, Err(TypeError::new(type_argument.span, ErrorKind::TypeNotFound(type_argument.name.to_owned())))),
+                    }
+                }
+
+                let argument_types: Option<Vec<TypeReference>> = call_expression.arguments.iter().map(|a| a.get_type()).collect();
+                if let Some(argument_types) = argument_types {
+                    let builtin_type = pass_try!(self, builtins::resolve_builtin_call(&symbol_table!(self), call_expression.span, function_name, &type_arguments, &argument_types));
+                    call_expression.function_type = Some(builtin_type);
+                }
+                return;
+            },
+            None => pass_try!(self, Err(TypeError::new(call_expression.function_name.span, ErrorKind::VariableNotFound(function_name.to_owned())))),
+        };
+        let function_type = match function_type {
+            Some(t) => t,
+            None => return,
+        };
+
+        let (argument_types, return_type) = match symbol_table!(self).find_type(function_type).and_then(|t| t.get_call_signature()) {
+            Some(signature) => (signature.get_arguments().to_vec(), signature.get_return_type()),
+            None => pass_try!(self, Err(TypeError::new(call_expression.function_name.span, ErrorKind::NotCallable))),
+        };
+        call_expression.function_type = return_type;
+
+        if call_expression.arguments.len() != argument_types.len() {
+            let error = ErrorKind::ArgumentCountMismatch(function_name.to_owned(), argument_types.len(), call_expression.arguments.len());
+            pass_try!(self, Err(TypeError::new(call_expression.span, error)));
+        }
+
+        for (index, (argument, &argument_type)) in call_expression.arguments.iter_mut().zip(argument_types.iter()).enumerate() {
+            if !self.coerce_expression(argument, argument_type) {
+                let found_name = argument.get_type().map(|t| self.type_name(t)).unwrap_or_default();
+                let error = ErrorKind::ArgumentTypeMismatch(function_name.to_owned(), index, self.type_name(argument_type), found_name);
+                result_mut!(self).add_error(Box::new(TypeError::new(argument.get_span(), error)));
+            }
+        }
+    }
+
+    fn visit_ternary_expression(&mut self, ternary_expression: &mut TernaryExpression) {
+        self.walk_ternary_expression(ternary_expression);
+        self.check_condition(&ternary_expression.condition);
+
+        let true_type = match ternary_expression.true_expression.get_type() {
+            Some(t) => t,
+            None => return,
+        };
+        let false_type = match ternary_expression.false_expression.get_type() {
+            Some(t) => t,
+            None => return,
+        };
+
+        // branches are promoted to a common type like infix operands
+        let ternary_type = match self.promoted_type(true_type, false_type) {
+            Some(t) => t,
+            None => {
+                let true_name = self.type_name(true_type);
+                let false_name = self.type_name(false_type);
+                pass_try!(self, Err(TypeError::new(ternary_expression.span, ErrorKind::IncompatibleTypes(ternary_expression.true_expression.get_span(), ternary_expression.false_expression.get_span(), true_name, false_name))))
+            },
+        };
+
+        self.coerce_expression(&mut ternary_expression.true_expression, ternary_type);
+        self.coerce_expression(&mut ternary_expression.false_expression, ternary_type);
+        ternary_expression.ternary_type = Some(ternary_type);
+    }
+
+    fn visit_infix_expression(&mut self, infix_expression: &mut InfixExpression) {
+        self.walk_infix_expression(infix_expression);
+
+        let left_type = match infix_expression.left_hand.get_type() {
+            Some(t) => t,
+            None => return,
+        };
+        let right_type = match infix_expression.right_hand.get_type() {
+            Some(t) => t,
+            None => return,
+        };
+
+        // declared operators take precedence, structs can only be combined through them
+        if let Some(operator_type) = symbol_table!(self).find_operator(infix_expression.operator, left_type, right_type) {
+            infix_expression.infix_type = Some(operator_type);
+            return;
+        }
+
+        if self.is_struct(left_type) || self.is_struct(right_type) {
+            let operator = infix_expression.operator.get_symbol().to_owned();
+            let left_name = self.type_name(left_type);
+            let right_name = self.type_name(right_type);
+            pass_try!(self, Err(TypeError::new(infix_expression.span, ErrorKind::OperatorNotFound(operator, left_name, right_name))));
+        }
+
+        let operand_type = match self.promoted_type(left_type, right_type) {
+            Some(t) => t,
+            None => {
+                let left_name = self.type_name(left_type);
+                let right_name = self.type_name(right_type);
+                pass_try!(self, Err(TypeError::new(infix_expression.span, ErrorKind::IncompatibleTypes(infix_expression.left_hand.get_span(), infix_expression.right_hand.get_span(), left_name, right_name))))
+            },
+        };
+
+        // operands are checked bottom-up, so nested infix expressions already have matching operand types
+        self.coerce_expression(&mut infix_expression.left_hand, operand_type);
+        self.coerce_expression(&mut infix_expression.right_hand, operand_type);
+
+        if !infix_expression.operator.is_comparison() {
+            infix_expression.infix_type = Some(operand_type);
+            return;
+        }
+
+        if infix_expression.operator.is_equality() && self.is_float(operand_type) {
+            result_mut!(self).add_lint(self.lints.float_equality, Box::new(TypeError::new(infix_expression.span, ErrorKind::FloatEquality)));
+        }
+
+        let bool_type = match symbol_table!(self).find_type_ref("bool") {
+            Some(t) => t,
+            None => pass_try!(self, Err(TypeError::new(infix_expression.span, ErrorKind::TypeNotFound("bool".to_owned())))),
+        };
+        infix_expression.infix_type = Some(bool_type);
+    }
+});
+
+/// every path through the block returns: it contains a return, an if whose branches both return,
+/// or a match whose arms all return and that has a wildcard arm
+/// loops don't count, their body might not run
+fn all_paths_return(block: &BlockDeclaration) -> bool {
+    block.statements.iter().any(|statement| match *statement {
+        BlockStatement::Return(_) => true,
+        BlockStatement::If(ref i) => all_paths_return(&i.block) && i.else_block.as_ref().is_some_and(all_paths_return),
+        BlockStatement::Match(ref m) => has_wildcard_arm(m) && m.arms.iter().all(|arm| all_paths_return(&arm.block)),
+        _ => false,
+    })
+}
+
+/// some path through the block returns, including returns in loops
+fn block_contains_return(block: &BlockDeclaration) -> bool {
+    block.statements.iter().any(|statement| match *statement {
+        BlockStatement::Return(_) => true,
+        BlockStatement::If(ref i) => block_contains_return(&i.block) || i.else_block.as_ref().is_some_and(block_contains_return),
+        BlockStatement::While(ref w) => block_contains_return(&w.block),
+        BlockStatement::For(ref f) => block_contains_return(&f.block),
+        BlockStatement::Match(ref m) => m.arms.iter().any(|arm| block_contains_return(&arm.block)),
+        _ => false,
+    })
+}
+
+/// control can't fall through the statement, every path returns or leaves the enclosing loop
+fn statement_terminates(statement: &BlockStatement) -> bool {
+    match *statement {
+        BlockStatement::Return(_) | BlockStatement::LoopControl(_) => true,
+        BlockStatement::If(ref i) => block_terminates(&i.block) && i.else_block.as_ref().is_some_and(block_terminates),
+        BlockStatement::Match(ref m) => has_wildcard_arm(m) && m.arms.iter().all(|arm| block_terminates(&arm.block)),
+        _ => false,
+    }
+}
+
+fn block_terminates(block: &BlockDeclaration) -> bool {
+    block.statements.iter().any(statement_terminates)
+}
+
+fn has_wildcard_arm(match_statement: &MatchStatement) -> bool {
+    match_statement.arms.iter().any(|arm| match arm.pattern { MatchPattern::Wildcard(_) => true, _ => false })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::{ PassResult, LintLevel };
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+    use ::passes::ast::type_checking::check_primitives_pass;
+    use ::passes::ast::type_checking::check_casts_pass;
+    use ::passes::ast::type_checking::discover_enums_pass;
+    use ::passes::ast::type_checking::check_function_signatures_pass;
+    use ::passes::ast::type_checking::discover_structs_pass;
+    use ::passes::ast::type_checking::check_struct_member_pass;
+
+    #[derive(PartialEq)]
+    enum Declarations {
+        Casts,
+        Structs,
+        Enums,
+        StructMembers,
+    }
+
+    /// runs the body pass after the primitive and signature passes and the passes resolving `declarations`
+    fn check_ast_with_lints(code: &str, declarations: &[Declarations], lints: LintOptions) -> (Ast, SymbolTableReference, PassResultReference) {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::new();
+        passes.add_pass(Box::new(check_primitives_pass::CheckPrimitivesPass::new(symbol_table.clone(), result.clone())));
+        if declarations.contains(&Declarations::Casts) {
+            passes.add_pass(Box::new(check_casts_pass::CheckCastsPass::new(symbol_table.clone(), result.clone(), true)));
+        }
+        if declarations.contains(&Declarations::Structs) {
+            passes.add_pass(Box::new(discover_structs_pass::DiscoverStructsPass::new(symbol_table.clone(), result.clone())));
+        }
+        if declarations.contains(&Declarations::Enums) {
+            passes.add_pass(Box::new(discover_enums_pass::DiscoverEnumsPass::new(symbol_table.clone(), result.clone())));
+        }
+        if declarations.contains(&Declarations::StructMembers) {
+            passes.add_pass(Box::new(check_struct_member_pass::CheckStructMemberPass::new(symbol_table.clone(), result.clone())));
+        }
+        passes.add_pass(Box::new(check_function_signatures_pass::CheckFunctionSignaturePass::new(symbol_table.clone(), result.clone())));
+        passes.add_pass(Box::new(CheckFunctionBodiesPass::with_lints(symbol_table.clone(), result.clone(), lints)));
+
+        passes.execute(&mut ast);
+
+        (ast, symbol_table, result)
+    }
+
+    fn check_ast(code: &str, declarations: &[Declarations]) -> (Ast, SymbolTableReference, PassResultReference) {
+        check_ast_with_lints(code, declarations, LintOptions::new())
+    }
+
+    fn check(code: &str, declarations: &[Declarations]) -> PassResultReference {
+        check_ast(code, declarations).2
+    }
+
+    fn error_kinds(result: &PassResultReference) -> Vec<ErrorKind> {
+        result.borrow().get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind().clone()).collect()
+    }
+
+    fn warning_kinds(result: &PassResultReference) -> Vec<ErrorKind> {
+        result.borrow().get_warnings().iter().filter_map(|w| w.downcast_ref::<TypeError>()).map(|w| w.get_kind().clone()).collect()
+    }
+
+    #[test]
+    fn it_works() {
+        let result = check("primitive type i32; fn test(a: i32) -> i32 { let b = a + 1; return b; }", &[]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn return_inserts_implicit_cast() {
+        let (ast, symbol_table, result) = check_ast("primitive type i32; primitive type f32; implicit cast i32 -> f32; fn test() -> f32 { return 1; }", &[Declarations::Casts]);
+
+        let i32_type = symbol_table.borrow().find_type_ref("i32").unwrap();
+        let f32_type = symbol_table.borrow().find_type_ref("f32").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        match ast[3] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => {
+                    assert_eq!(r.return_type, Some(f32_type));
+                    match r.expression {
+                        ExpressionStatement::Cast(ref c) => {
+                            assert_eq!(c.cast_type, CastType::Implicit);
+                            assert_eq!(c.cast_target_type, Some(f32_type));
+                            assert_eq!(c.expression.get_type(), Some(i32_type));
+                        },
+                        _ => panic!("expected inserted cast"),
+                    }
+                },
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn float_equality_produces_a_warning() {
+        let result = check("primitive type bool; primitive type f32; fn test() -> bool { return 1.0 == 2.0; }", &[]);
+
+        assert!(!result.borrow().has_errors());
+        assert!(warning_kinds(&result).contains(&ErrorKind::FloatEquality));
+    }
+
+    #[test]
+    fn int_equality_produces_no_warning() {
+        let result = check("primitive type bool; primitive type i32; fn test() -> bool { return 1 == 2; }", &[]);
+
+        assert!(!result.borrow().has_errors());
+        assert!(warning_kinds(&result).is_empty());
+    }
+
+    #[test]
+    fn float_equality_can_be_an_error() {
+        let mut lints = LintOptions::new();
+        lints.float_equality = LintLevel::Error;
+        let (_, _, result) = check_ast_with_lints("primitive type bool; primitive type f32; fn test() -> bool { return 1.0 != 2.0; }", &[], lints);
+
+        assert!(result.borrow().has_errors());
+    }
+
+    #[test]
+    fn unknown_variable_produces_an_error() {
+        let result = check("primitive type i32; fn test() -> i32 { return a; }", &[]);
+
+        assert!(result.borrow().has_errors());
+    }
+
+    #[test]
+    fn incompatible_infix_operands_produce_an_error() {
+        let result = check("primitive type i32; primitive type f32; fn test() -> i32 { return 1 + 1.0; }", &[]);
+
+        assert!(error_kinds(&result).iter().any(|kind| match *kind {
+            ErrorKind::IncompatibleTypes(_, _, ref left, ref right) => left == "i32" && right == "f32",
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn infix_operands_resolve_to_implicit_cast_target() {
+        let (ast, symbol_table, result) = check_ast("primitive type i32; primitive type f32; implicit cast i32 -> f32; fn test() -> f32 { return 1 + 1.0; }", &[Declarations::Casts]);
+
+        let f32_type = symbol_table.borrow().find_type_ref("f32").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        match ast[3] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => match r.expression {
+                    ExpressionStatement::Infix(ref infix) => {
+                        assert_eq!(infix.infix_type, Some(f32_type));
+                        assert_eq!(infix.left_hand.get_type(), Some(f32_type));
+                        assert_eq!(infix.right_hand.get_type(), Some(f32_type));
+                    },
+                    _ => panic!("expected infix expression"),
+                },
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn call_function_declared_later() {
+        let (ast, symbol_table, result) = check_ast("primitive type i32; fn f(a: i32) -> i32 { return g(a) + 1; } fn g(a: i32) -> i32 { return a; }", &[]);
+
+        let i32_type = symbol_table.borrow().find_type_ref("i32").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        match ast[1] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => assert_eq!(r.return_type, Some(i32_type)),
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn calling_a_variable_produces_an_error() {
+        let result = check("primitive type i32; fn f(a: i32) -> i32 { return a(1); }", &[]);
+
+        assert!(result.borrow().has_errors());
+    }
+
+    #[test]
+    fn argument_is_cast_along_implicit_cast_chain() {
+        let (ast, symbol_table, result) = check_ast("primitive type i32; primitive type f32; primitive type f64; implicit cast i32 -> f32; implicit cast f32 -> f64; fn scale(x: f64) -> f64 { return x; } fn test(a: i32) -> f64 { return scale(a); }", &[Declarations::Casts]);
+
+        let i32_type = symbol_table.borrow().find_type_ref("i32").unwrap();
+        let f32_type = symbol_table.borrow().find_type_ref("f32").unwrap();
+        let f64_type = symbol_table.borrow().find_type_ref("f64").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        match ast[6] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ReturnDeclaration { expression: ExpressionStatement::Call(ref call), .. }) => match call.arguments[0] {
+                    ExpressionStatement::Cast(ref outer) => {
+                        assert_eq!(outer.cast_target_type, Some(f64_type));
+                        assert_eq!(outer.expression.get_type(), Some(f32_type));
+                        match *outer.expression {
+                            ExpressionStatement::Cast(ref inner) => assert_eq!(inner.expression.get_type(), Some(i32_type)),
+                            _ => panic!("expected implicit cast"),
+                        }
+                    },
+                    _ => panic!("expected implicit cast"),
+                },
+                _ => panic!("expected return of a call"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn call_inserts_implicit_cast_for_arguments() {
+        let (ast, symbol_table, result) = check_ast("primitive type i32; primitive type f32; implicit cast i32 -> f32; fn add(x: f32, y: f32) -> f32 { return x + y; } fn test(a: i32) -> f32 { return add(a, 2.0); }", &[Declarations::Casts]);
+
+        let i32_type = symbol_table.borrow().find_type_ref("i32").unwrap();
+        let f32_type = symbol_table.borrow().find_type_ref("f32").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        match ast[4] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => match r.expression {
+                    ExpressionStatement::Call(ref call) => {
+                        assert_eq!(call.function_type, Some(f32_type));
+                        match call.arguments[0] {
+                            ExpressionStatement::Cast(ref c) => assert_eq!(c.expression.get_type(), Some(i32_type)),
+                            _ => panic!("expected implicit cast"),
+                        }
+                    },
+                    _ => panic!("expected call expression"),
+                },
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn call_with_wrong_argument_count_produces_an_error() {
+        let result = check("primitive type i32; primitive type f32; implicit cast i32 -> f32; fn add(x: f32, y: f32) -> f32 { return x + y; } fn test(a: f32) -> f32 { return add(a); }", &[Declarations::Casts]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::ArgumentCountMismatch("add".to_owned(), 2, 1)]);
+    }
+
+    #[test]
+    fn call_with_wrong_argument_type_produces_an_error() {
+        let result = check("primitive type bool; primitive type i32; primitive type f32; implicit cast i32 -> f32; fn add(x: f32, y: f32) -> f32 { return x + y; } fn test(a: bool) -> f32 { return add(1.0, a); }", &[Declarations::Casts]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::ArgumentTypeMismatch("add".to_owned(), 1, "f32".to_owned(), "bool".to_owned())]);
+    }
+
+    #[test]
+    fn local_used_before_assignment_produces_an_error() {
+        let result = check("primitive type i32; fn test() -> i32 { let a; let b = a + 1; a = 2; return b; }", &[]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::UsedBeforeInitialization("a".to_owned())));
+    }
+
+    #[test]
+    fn bool_literal_has_bool_type() {
+        let (ast, symbol_table, result) = check_ast("primitive type bool; fn test() -> bool { let a = true; return !a; }", &[]);
+
+        let bool_type = symbol_table.borrow().find_type_ref("bool").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        match ast[1] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Local(ref l) => assert_eq!(l.local_type, Some(bool_type)),
+                _ => panic!("expected local statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn local_assigned_before_use() {
+        let (ast, symbol_table, result) = check_ast("primitive type i32; fn test() -> i32 { let a; a = 2; let b = a + 1; return b; }", &[]);
+
+        let i32_type = symbol_table.borrow().find_type_ref("i32").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        match ast[1] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Local(ref l) => assert_eq!(l.local_type, Some(i32_type)),
+                _ => panic!("expected local statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn integer_division_in_float_context_produces_a_warning() {
+        let result = check("primitive type i32; primitive type f32; implicit cast i32 -> f32; fn test() -> f32 { let r: f32 = 1 / 2; return r; }", &[Declarations::Casts]);
+
+        assert!(!result.borrow().has_errors());
+        assert!(warning_kinds(&result).contains(&ErrorKind::IntegerDivisionInFloatContext));
+    }
+
+    #[test]
+    fn float_division_in_float_context_produces_no_warning() {
+        let result = check("primitive type i32; primitive type f32; implicit cast i32 -> f32; fn test() -> f32 { let r: f32 = 1.0 / 2.0; return r; }", &[Declarations::Casts]);
+
+        assert!(!result.borrow().has_errors());
+        assert!(warning_kinds(&result).is_empty());
+    }
+
+    #[test]
+    fn mixed_arithmetic_promotes_to_float() {
+        let (ast, symbol_table, result) = check_ast("primitive type i32; primitive type f32; implicit cast i32 -> f32; fn test() -> f32 { return 1 + 2.0 * 3; }", &[Declarations::Casts]);
+
+        let f32_type = symbol_table.borrow().find_type_ref("f32").unwrap();
+
+        fn is_cast_literal(expression: &ExpressionStatement, value: &str) -> bool {
+            match *expression {
+                ExpressionStatement::Cast(ref c) => match *c.expression {
+                    ExpressionStatement::Literal(ref l) => l.value == value,
+                    _ => false,
+                },
+                _ => false,
+            }
+        }
+
+        assert!(!result.borrow().has_errors());
+        let sum = match ast[3] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => match r.expression {
+                    ExpressionStatement::Infix(ref infix) => infix,
+                    _ => panic!("expected infix expression"),
+                },
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        };
+        assert_eq!(sum.infix_type, Some(f32_type));
+        assert!(is_cast_literal(&sum.left_hand, "1"));
+
+        match *sum.right_hand {
+            ExpressionStatement::Infix(ref product) => {
+                assert_eq!(product.infix_type, Some(f32_type));
+                assert!(is_cast_literal(&product.right_hand, "3"));
+                match *product.left_hand {
+                    ExpressionStatement::Literal(ref l) => assert_eq!(l.literal_type, Some(f32_type)),
+                    _ => panic!("expected uncast literal"),
+                }
+            },
+            _ => panic!("expected infix expression"),
+        }
+    }
+
+    #[test]
+    fn builtin_clamp_on_vector() {
+        let (ast, symbol_table, result) = check_ast("primitive type f32; primitive type vec3; fn test(v: vec3) -> vec3 { return clamp(v, 0.0, 1.0); }", &[]);
+
+        let vec3_type = symbol_table.borrow().find_type_ref("vec3").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        match ast[2] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => assert_eq!(r.return_type, Some(vec3_type)),
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn while_loop() {
+        let result = check("primitive type bool; primitive type i32; fn test(a: i32, b: i32) -> i32 { while a != b { let c = a + 1; a = c; continue; } return a; }", &[]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn non_boolean_while_condition_produces_an_error() {
+        let result = check("primitive type bool; primitive type i32; fn test(a: i32) -> i32 { while a { break; } return a; }", &[]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::NonBooleanCondition("i32".to_owned())));
+    }
+
+    #[test]
+    fn float_while_condition_produces_an_error() {
+        let result = check("primitive type bool; primitive type f32; fn test(a: f32) -> f32 { while (a) { break; } return a; }", &[]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::NonBooleanCondition("f32".to_owned())));
+    }
+
+    #[test]
+    fn while_body_locals_stay_in_the_loop() {
+        let result = check("primitive type bool; primitive type i32; fn test(a: i32, b: i32) -> i32 { while a != b { let c = a + 1; break; } return c; }", &[]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::VariableNotFound("c".to_owned())));
+    }
+
+    #[test]
+    fn if_statement() {
+        let result = check("primitive type bool; primitive type i32; fn test(a: i32, b: i32) -> i32 { if a == b { let c = a + 1; return c; } return a; }", &[]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn non_boolean_if_condition_produces_an_error() {
+        let result = check("primitive type bool; primitive type i32; fn test(a: i32) -> i32 { if a { return 1; } return a; }", &[]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::NonBooleanCondition("i32".to_owned())));
+    }
+
+    #[test]
+    fn if_else_statement() {
+        let result = check("primitive type bool; primitive type i32; fn test(a: i32, b: i32) -> i32 { let c: i32; if a == b { let d = 1; c = d; } else { c = b; } if a != b { return c; } else { return a; } }", &[]);
+
+        // both branches initialize `c` and return, so neither an uninitialized read nor a missing return is reported
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn break_outside_loop_produces_an_error() {
+        let result = check("primitive type i32; fn test(a: i32) -> i32 { break; return a; }", &[]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::LoopControlOutsideLoop("break".to_owned())));
+    }
+
+    fn local_shadows_type(code: &str) -> bool {
+        let result = check(code, &[Declarations::Structs]);
+
+        assert!(!result.borrow().has_errors());
+        warning_kinds(&result).contains(&ErrorKind::LocalShadowsType("Point".to_owned()))
+    }
+
+    #[test]
+    fn local_named_after_struct_produces_a_warning() {
+        assert!(local_shadows_type("primitive type i32; struct Point {} fn test() -> i32 { let Point = 1; return Point; }"));
+    }
+
+    #[test]
+    fn local_with_other_name_produces_no_warning() {
+        assert!(!local_shadows_type("primitive type i32; struct Point {} fn test() -> i32 { let point = 1; return point; }"));
+    }
+
+    #[test]
+    fn bitcast_to_same_size_type() {
+        let result = check("primitive type f32; primitive type u32; fn test(a: f32) -> u32 { return bitcast<u32>(a); }", &[Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn bitcast_to_different_size_type_produces_an_error() {
+        let result = check("primitive type f32; struct Vec2 { x: f32, y: f32, } fn test(a: f32) -> Vec2 { return bitcast<Vec2>(a); }", &[Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::BitcastSizeMismatch("f32".to_owned(), "Vec2".to_owned())));
+    }
+
+    #[test]
+    fn out_parameter_read_before_assignment_produces_an_error() {
+        let result = check("primitive type i32; fn test(a: i32, out b: i32) -> i32 { let c = b + a; b = a; return c; }", &[]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::OutParamReadBeforeAssignment("b".to_owned())));
+    }
+
+    #[test]
+    fn out_parameter_assigned_then_read() {
+        let result = check("primitive type i32; fn test(a: i32, out b: i32) -> i32 { b = a; let c = b + a; return c; }", &[]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn out_parameter_only_assigned_in_loop_produces_an_error() {
+        let result = check("primitive type bool; primitive type i32; fn test(a: i32, out output: i32) -> i32 { while a != 0 { output = a; } return a; }", &[]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::OutParamNotAssigned("output".to_owned())));
+    }
+
+    #[test]
+    fn field_access_has_member_type() {
+        let result = check("primitive type i32; primitive type f32; struct Vec4 { x: f32, y: f32, z: f32, w: f32, } fn test() -> f32 { let v = Vec4 { x: 1.0, y: 2.0, z: 3.0, w: 4.0 }; return v.x; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+
+        let result = check("primitive type i32; primitive type f32; struct Vec4 { x: f32, y: f32, z: f32, w: f32, } fn test(v: Vec4) -> i32 { return v.x; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(result.borrow().has_errors());
+    }
+
+    #[test]
+    fn unknown_field_access_produces_an_error() {
+        let result = check("primitive type i32; primitive type f32; struct Vec4 { x: f32, y: f32, z: f32, w: f32, } fn test(f: Vec4) -> f32 { return f.notAField; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::UnknownStructField("Vec4".to_owned(), "notAField".to_owned())]);
+    }
+
+    #[test]
+    fn field_access_on_non_struct_produces_an_error() {
+        let result = check("primitive type f32; fn test(f: f32) -> f32 { return f.x; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::FieldAccessOnNonStruct("f32".to_owned())]);
+    }
+
+    #[test]
+    fn swizzle_on_vector() {
+        let result = check("primitive type f32; primitive type vec3; primitive type vec4; fn test(v: vec4) -> vec3 { let x: f32 = v.x; return v.xyz; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn swizzle_out_of_range_produces_an_error() {
+        let result = check("primitive type f32; primitive type vec2; fn test(v: vec2) -> f32 { return v.w; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::InvalidSwizzle("w".to_owned())]);
+    }
+
+    #[test]
+    fn index_vector_component() {
+        let result = check("primitive type i32; primitive type f32; primitive type vec4; fn test(v: vec4, i: i32) -> f32 { return v[i] + v[3]; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn index_on_non_array_produces_an_error() {
+        let result = check("primitive type i32; primitive type f32; fn test(f: f32) -> f32 { return f[0]; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::IndexOnNonArray("f32".to_owned())]);
+    }
+
+    #[test]
+    fn non_integer_index_produces_an_error() {
+        let result = check("primitive type i32; primitive type f32; primitive type vec4; fn test(v: vec4) -> f32 { return v[1.0]; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::NonIntegerIndex("f32".to_owned())]);
+    }
+
+    #[test]
+    fn type_used_as_value_produces_an_error() {
+        let result = check("primitive type f32; struct Point { x: f32, } fn test() -> Point { let p = Point; return p; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::TypeUsedAsValue("Point".to_owned())));
+    }
+
+    #[test]
+    fn struct_instantiation_is_a_value() {
+        let result = check("primitive type f32; struct Point { x: f32, } fn test() -> Point { let p = Point { x: 1.0, }; return p; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn struct_field_initializer_of_wrong_type_produces_an_error() {
+        let result = check("primitive type bool; primitive type f32; struct Point { x: f32, y: f32, } fn test(b: bool) -> Point { return Point { x: b, y: 1.0, }; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::StructFieldTypeMismatch("x".to_owned(), "f32".to_owned(), "bool".to_owned())));
+    }
+
+    #[test]
+    fn narrowing_struct_field_initializer_produces_an_error() {
+        let result = check("primitive type f16; primitive type f32; implicit cast f16 -> f32; explicit cast f32 -> f16; struct Half { x: f16, } fn test(a: f32) -> Half { return Half { x: a, }; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::StructFieldTypeMismatch("x".to_owned(), "f16".to_owned(), "f32".to_owned())]);
+    }
+
+    #[test]
+    fn widening_struct_field_initializer() {
+        let result = check("primitive type f16; primitive type f32; implicit cast f16 -> f32; explicit cast f32 -> f16; struct Full { x: f32, } fn test(a: f16) -> Full { return Full { x: a, }; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn struct_field_initializer_is_implicitly_cast() {
+        let (ast, symbol_table, result) = check_ast("primitive type i32; primitive type f32; implicit cast i32 -> f32; struct Point { x: f32, } fn test(a: i32) -> Point { return Point { x: a, }; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        let f32_type = symbol_table.borrow().find_type_ref("f32").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        match ast[4] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => match r.expression {
+                    ExpressionStatement::StructInstantiation(ref s) => {
+                        assert_eq!(s.struct_field_initializer[0].struct_field_type, Some(f32_type));
+                        assert_eq!(s.struct_field_initializer[0].initializer.get_type(), Some(f32_type));
+                    },
+                    _ => panic!("expected struct instantiation"),
+                },
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn complete_struct_instantiation() {
+        let result = check("primitive type f32; struct Vec4 { x: f32, y: f32, z: f32, w: f32, } fn test() -> Vec4 { return Vec4 { w: 4.0, z: 3.0, y: 2.0, x: 1.0, }; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn missing_struct_fields_produce_an_error() {
+        let result = check("primitive type f32; struct Vec4 { x: f32, y: f32, z: f32, w: f32, } fn test() -> Vec4 { return Vec4 { x: 1.0, }; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::MissingStructFields("Vec4".to_owned(), vec!["y".to_owned(), "z".to_owned(), "w".to_owned()])));
+    }
+
+    #[test]
+    fn extra_struct_field_produces_an_error() {
+        let result = check("primitive type f32; struct Vec4 { x: f32, y: f32, z: f32, w: f32, } fn test() -> Vec4 { return Vec4 { x: 1.0, y: 2.0, z: 3.0, w: 4.0, q: 1.0, }; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::UnknownStructField("Vec4".to_owned(), "q".to_owned())]);
+    }
+
+    #[test]
+    fn unknown_struct_field_produces_an_error() {
+        let result = check("primitive type f32; struct Point { x: f32, } fn test() -> Point { return Point { x: 1.0, z: 1.0, }; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::UnknownStructField("Point".to_owned(), "z".to_owned())));
+    }
+
+    #[test]
+    fn duplicate_struct_field_initializer_produces_an_error() {
+        let result = check("primitive type f32; struct Point { x: f32, } fn test() -> Point { return Point { x: 1.0, x: 2.0, }; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::DuplicateFieldInitializer("x".to_owned())));
+    }
+
+    fn possibly_uninitialized_fields(result: &PassResultReference) -> Vec<String> {
+        warning_kinds(result).into_iter().filter_map(|kind| match kind {
+            ErrorKind::PossiblyUninitializedField(field_name) => Some(field_name),
+            _ => None,
+        }).collect()
+    }
+
+    #[test]
+    fn struct_update_chain_leaving_a_field_unset_produces_a_warning() {
+        let result = check("primitive type f32; struct Point { x: f32, y: f32, z: f32, } fn test() -> Point { let a = Point { x: 1.0, }; let b = Point { y: 2.0, ..a }; return Point { x: 3.0, ..b }; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(possibly_uninitialized_fields(&result), vec!["z", "z"]);
+    }
+
+    #[test]
+    fn struct_update_of_complete_base_produces_no_warning() {
+        let result = check("primitive type f32; struct Point { x: f32, y: f32, } fn test(p: Point) -> Point { let a = Point { x: 1.0, ..p }; return Point { y: 2.0, ..a }; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+        assert!(possibly_uninitialized_fields(&result).is_empty());
+    }
+
+    #[test]
+    fn integer_literal_in_range() {
+        let result = check("primitive type i32; fn test() -> i32 { let x: i32 = 2147483647; return x; }", &[]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn integer_literal_overflowing_i32_produces_an_error() {
+        let result = check("primitive type i32; fn test() -> i32 { let x: i32 = 3000000000; return x; }", &[]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::LiteralOutOfRange("3000000000".to_owned(), "i32".to_owned())));
+    }
+
+    #[test]
+    fn negated_integer_literal_in_range() {
+        let result = check("primitive type i32; fn test() -> i32 { let x: i32 = -2147483648; return x; }", &[]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn negated_integer_literal_overflowing_i32_produces_an_error() {
+        let result = check("primitive type i32; fn test() -> i32 { let x: i32 = -2147483649; return x; }", &[]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::LiteralOutOfRange("-2147483649".to_owned(), "i32".to_owned())));
+    }
+
+    #[test]
+    fn integer_literal_in_u32_range() {
+        let result = check("primitive type i32; primitive type u32; fn test() -> u32 { let x: u32 = 3000000000; return x; }", &[]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn same_type_cast_produces_a_warning() {
+        let result = check("primitive type f32; fn test(x: f32) -> f32 { return f32(x); }", &[Declarations::Casts]);
+
+        assert!(!result.borrow().has_errors());
+        assert!(warning_kinds(&result).contains(&ErrorKind::RedundantCast("f32".to_owned())));
+    }
+
+    #[test]
+    fn explicit_cast_produces_no_warning() {
+        let result = check("primitive type f32; primitive type i32; explicit cast f32 -> i32; fn test(x: f32) -> i32 { return i32(x); }", &[Declarations::Casts]);
+
+        assert!(!result.borrow().has_errors());
+        assert!(warning_kinds(&result).is_empty());
+    }
+
+    #[test]
+    fn undeclared_explicit_cast_produces_an_error() {
+        let result = check("primitive type f32; primitive type i32; fn test(x: f32) -> i32 { return i32(x); }", &[Declarations::Casts]);
+
+        assert!(result.borrow().has_errors());
+    }
+
+    #[test]
+    fn as_cast_has_target_type() {
+        let result = check("primitive type f32; primitive type i32; explicit cast f32 -> i32; fn test(x: f32) -> i32 { let a = x as i32; return a * 2; }", &[Declarations::Casts]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn undeclared_as_cast_produces_an_error() {
+        let result = check("primitive type f32; primitive type u32; fn test(x: f32) -> u32 { return x as u32; }", &[Declarations::Casts]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::CastNotDeclared("f32".to_owned(), "u32".to_owned())]);
+    }
+
+    #[test]
+    fn exhaustive_enum_match() {
+        let result = check("
+            primitive type i32;
+            enum BlendMode { Add, Multiply, }
+            fn test(mode: BlendMode) -> i32 {
+                match mode {
+                    BlendMode::Add => { return 1; }
+                    BlendMode::Multiply => { return 2; }
+                }
+                return 0;
+            }
+        ", &[Declarations::Enums]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn non_exhaustive_enum_match_produces_an_error() {
+        let result = check("
+            primitive type i32;
+            enum BlendMode { Add, Multiply, }
+            fn test(mode: BlendMode) -> i32 {
+                match mode {
+                    BlendMode::Add => { return 1; }
+                }
+                return 0;
+            }
+        ", &[Declarations::Enums]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::NonExhaustiveMatch));
+    }
+
+    #[test]
+    fn integer_match_with_wildcard() {
+        let result = check("primitive type i32; fn test(a: i32) -> i32 { match a { 0 => { return 1; } _ => { return 2; } } return a; }", &[Declarations::Enums]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn local_assigned_in_every_arm_is_initialized() {
+        let result = check("primitive type i32; fn test(a: i32) -> i32 { let b: i32; match a { 0 => { b = 1; } _ => { b = 2; } } return b; }", &[Declarations::Enums]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn local_assigned_in_some_arms_produces_an_error() {
+        let result = check("primitive type i32; fn test(a: i32) -> i32 { let b: i32; match a { 0 => { b = 1; } _ => { } } return b; }", &[Declarations::Enums]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::UsedBeforeInitialization("b".to_owned())]);
+    }
+
+    #[test]
+    fn unknown_enum_variant_produces_an_error() {
+        let result = check("primitive type i32; enum BlendMode { Add, } fn test(mode: BlendMode) -> i32 { match mode { BlendMode::Subtract => { return 1; } _ => { return 2; } } return 0; }", &[Declarations::Enums]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::UnknownEnumVariant("BlendMode".to_owned(), "Subtract".to_owned())));
+    }
+
+    /// value of the literal the returned layout query folded to, `None` if it wasn't folded
+    fn folded_layout_query(code: &str) -> (PassResultReference, Option<String>) {
+        let (ast, _, result) = check_ast(code, &[Declarations::Structs, Declarations::StructMembers]);
+
+        let folded = match ast.last() {
+            Some(&ItemKind::Function(ref f)) => match f.block.statements[0] {
+                BlockStatement::Return(ReturnDeclaration { expression: ExpressionStatement::Literal(ref l), .. }) => Some(l.value.to_owned()),
+                _ => None,
+            },
+            _ => panic!("expected function"),
+        };
+
+        (result, folded)
+    }
+
+    #[test]
+    fn sizeof_folds_to_std140_size() {
+        let (result, folded) = folded_layout_query("primitive type f32; primitive type u32; struct Vec4 { x: f32, y: f32, z: f32, w: f32, } fn test() -> u32 { return sizeof(Vec4); }");
+
+        assert!(!result.borrow().has_errors());
+        assert_eq!(folded, Some("16".to_owned()));
+    }
+
+    #[test]
+    fn alignof_folds_to_std140_alignment() {
+        let (result, folded) = folded_layout_query("primitive type f32; primitive type u32; struct Vec3 { x: f32, y: f32, z: f32, } fn test() -> u32 { return alignof(Vec3); }");
+
+        assert!(!result.borrow().has_errors());
+        assert_eq!(folded, Some("16".to_owned()));
+    }
+
+    #[test]
+    fn sizeof_unsized_type_produces_an_error() {
+        let (result, folded) = folded_layout_query("primitive type u32; primitive type Texture2D; fn test() -> u32 { return sizeof(Texture2D); }");
+
+        assert_eq!(folded, None);
+        assert!(error_kinds(&result).contains(&ErrorKind::UnsizedTypeInSizeof("Texture2D".to_owned())));
+    }
+
+    #[test]
+    fn default_constructor() {
+        let result = check("primitive type f32; struct Material { roughness: f32 = 0.5, metallic: f32 = 0.0, } fn test() -> Material { return Material::default(); }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn default_constructor_without_field_default_produces_an_error() {
+        let result = check("primitive type f32; struct Material { roughness: f32 = 0.5, metallic: f32, } fn test() -> Material { return Material::default(); }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::NoDefaultConstructor("Material".to_owned())));
+    }
+
+    #[test]
+    fn for_loop_with_constant_bounds() {
+        let result = check("primitive type i32; fn test() -> i32 { let a = 0; for i in 0..4 { a = a + i; } return a; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn for_loop_with_inverted_range_produces_an_error() {
+        let result = check("primitive type i32; fn test() -> i32 { let a = 0; for i in 4..0 { a = a + i; } return a; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::InvalidLoopRange("4".to_owned(), "0".to_owned())));
+    }
+
+    #[test]
+    fn for_loop_bound_calls_const_fn() {
+        let code = "primitive type i32; const fn count(n: i32) -> i32 { return n * 2; } fn test() -> i32 { let a = 0; for i in 8..count(2) { a = a + i; } return a; }";
+        let result = check(code, &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::InvalidLoopRange("8".to_owned(), "4".to_owned())]);
+    }
+
+    #[test]
+    fn negate_float_literal() {
+        let result = check("primitive type f32; fn test() -> f32 { return -1.0; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn negate_int_variable() {
+        let result = check("primitive type i32; fn test(someInt: i32) -> i32 { return -someInt * 2; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn negate_struct_produces_an_error() {
+        let result = check("primitive type f32; struct Light { intensity: f32, } fn test(someStruct: Light) -> Light { return -someStruct; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::UnaryOperatorNotDefined("-".to_owned(), "Light".to_owned())]);
+    }
+
+    #[test]
+    fn local_constant_in_expression() {
+        let result = check("primitive type f32; fn test(a: f32) -> f32 { const THRESHOLD: f32 = 0.5; return a * THRESHOLD; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn local_constant_in_loop_bound() {
+        let result = check("primitive type i32; fn test() -> i32 { const COUNT: i32 = 2 * 2; let a = 0; for i in COUNT..1 { a = a + i; } return a; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::InvalidLoopRange("4".to_owned(), "1".to_owned())]);
+    }
+
+    #[test]
+    fn assignment_to_local_constant_produces_an_error() {
+        let result = check("primitive type f32; fn test() -> f32 { const THRESHOLD: f32 = 0.5; THRESHOLD = 1.0; return THRESHOLD; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::AssignToConstant("THRESHOLD".to_owned())]);
+    }
+
+    #[test]
+    fn for_loop_with_variable_bound_requires_dynamic() {
+        let result = check("primitive type i32; fn test(n: i32) -> i32 { let a = 0; for i in 0..n { a = a + i; } return a; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::NotAConstantExpression));
+
+        let result = check("primitive type i32; fn test(n: i32) -> i32 { let a = 0; [dynamic] for i in 0..n { a = a + i; } return a; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn return_matches_return_type() {
+        let result = check("primitive type i32; fn test() -> i32 { return 1; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn mismatched_return_produces_an_error() {
+        let result = check("primitive type i32; primitive type f32; fn test(a: f32) -> i32 { return a; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::ReturnTypeMismatch("i32".to_owned(), "f32".to_owned())));
+    }
+
+    #[test]
+    fn missing_return_produces_an_error() {
+        let result = check("primitive type i32; fn test(a: i32) -> i32 { let b = a; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::MissingReturn("test".to_owned())));
+    }
+
+    #[test]
+    fn return_in_else_branch_only_produces_an_error() {
+        let result = check("primitive type bool; primitive type f32; fn test(a: f32, b: bool) -> f32 { if b { let c = a; } else { return a; } }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(error_kinds(&result).contains(&ErrorKind::MissingReturnOnPath("test".to_owned())));
+    }
+
+    #[test]
+    fn return_in_both_branches_covers_every_path() {
+        let result = check("primitive type bool; primitive type f32; fn test(a: f32, b: bool) -> f32 { if b { return a; } else { return 0.0; } }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn return_in_one_sided_if_produces_an_error() {
+        let result = check("primitive type bool; primitive type f32; fn test(a: f32, b: bool) -> f32 { if b { return a; } }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::MissingReturnOnPath("test".to_owned())]);
+    }
+
+    #[test]
+    fn constant_initializer_is_implicitly_cast() {
+        let result = check("primitive type i32; primitive type f32; implicit cast i32 -> f32; const scale: f32 = 2; const bias: f32 = 0.5 * 2.0;", &[Declarations::Casts]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn mismatched_constant_initializer_produces_an_error() {
+        let result = check("primitive type bool; primitive type f32; const scale: f32 = true;", &[Declarations::Casts]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::ConstantTypeMismatch("scale".to_owned(), "f32".to_owned(), "bool".to_owned())]);
+    }
+
+    #[test]
+    fn non_constant_initializer_produces_an_error() {
+        let result = check("primitive type f32; fn f(x: f32) -> f32 { return x; } const scale: f32 = f(1.0);", &[Declarations::Casts]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::NonConstantInitializer("scale".to_owned())]);
+    }
+
+    #[test]
+    fn module_constants_are_visible_in_bodies_and_later_constants() {
+        let result = check("primitive type i32; primitive type f32; const count: i32 = 4; const last: i32 = count; const scale: f32; \
+            fn sum(x: f32) -> f32 { let total = 0.0; for i in 0..last { total = total + x * scale; } return total; }", &[Declarations::Casts]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn assignment_to_module_constant_produces_an_error() {
+        let result = check("primitive type i32; const count: i32 = 4; fn f() -> i32 { count = 2; return count; }", &[Declarations::Casts]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::AssignToConstant("count".to_owned())]);
+    }
+
+    #[test]
+    fn unread_local_produces_a_warning() {
+        let result = check("primitive type f32; fn test(a: f32) -> f32 { let b = a * 2.0; let c: f32; c = a; let _d = a; return a; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+        assert_eq!(warning_kinds(&result), vec![ErrorKind::UnusedLocal("b".to_owned()), ErrorKind::UnusedLocal("c".to_owned())]);
+    }
+
+    #[test]
+    fn read_local_produces_no_warning() {
+        let result = check("primitive type bool; primitive type f32; fn test(a: f32) -> f32 { let b = a * 2.0; if b > a { let c = b; return c; } return b; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+        assert!(warning_kinds(&result).is_empty());
+    }
+
+    #[test]
+    fn ternary_branches_are_promoted() {
+        let result = check("primitive type bool; primitive type i32; primitive type f32; implicit cast i32 -> f32; fn test(a: f32, b: bool) -> f32 { return b ? a : 1; }", &[Declarations::Casts]);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn ternary_with_incompatible_branches_produces_an_error() {
+        let result = check("primitive type bool; primitive type f32; fn test(a: f32, b: bool) -> f32 { return a > 1.0 ? a : b; }", &[Declarations::Casts]);
+
+        assert_eq!(error_kinds(&result), vec![ErrorKind::IncompatibleTypes(Span::new(92, 1, 1, 93), Span::new(96, 1, 1, 97), "f32".to_owned(), "bool".to_owned())]);
+    }
+
+    #[test]
+    fn statement_after_return_produces_a_warning() {
+        let result = check("primitive type bool; primitive type f32; fn test(a: f32, b: bool) -> f32 { if b { return a; } else { return 0.0; } let c = a; return c; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        let result = result.borrow();
+        assert!(!result.has_errors());
+        let warnings: Vec<&TypeError> = result.get_warnings().iter().filter_map(|w| w.downcast_ref::<TypeError>()).collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(*warnings[0].get_kind(), ErrorKind::UnreachableStatement);
+        assert_eq!(warnings[0].get_span(), Span::new(115, 10, 1, 116));
+    }
+
+    #[test]
+    fn return_as_last_statement_produces_no_warning() {
+        let result = check("primitive type bool; primitive type f32; fn test(a: f32, b: bool) -> f32 { if b { return a; } let c = a * 2.0; return c; }", &[Declarations::Casts, Declarations::Structs, Declarations::StructMembers]);
+
+        assert!(!result.borrow().has_errors());
+        assert!(warning_kinds(&result).is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+
+/// Optional pass limiting the number of expression nodes per function body, e.g. for targets with instruction limits
+pub struct CheckFunctionComplexityPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+    limit: usize,
+    expression_count: usize,
+}
+
+impl CheckFunctionComplexityPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference, limit: usize) -> CheckFunctionComplexityPass {
+        CheckFunctionComplexityPass {
+            symbol_table: symbol_table,
+            result: result,
+            limit: limit,
+            expression_count: 0,
+        }
+    }
+}
+
+ast_pass_impl!(CheckFunctionComplexityPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        for item in items.iter_mut() {
+            match *item {
+                ItemKind::Function(ref mut function) => self.visit_function(function),
+                ItemKind::Program(ref mut program) => {
+                    for stage in program.program_stages.iter_mut() {
+                        self.visit_function(&mut stage.function);
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+
+    fn visit_function(&mut self, function_declaration: &mut FunctionDeclaration) {
+        self.expression_count = 0;
+        self.walk_function(function_declaration);
+
+        if self.expression_count > self.limit {
+            let name = function_declaration.function_name.name.to_owned();
+            pass_try!(self, Err(TypeError::new(function_declaration.function_name.span, ErrorKind::FunctionTooComplex(name, self.expression_count, self.limit))));
+        }
+    }
+
+    fn visit_expression(&mut self, expression_statement: &mut ExpressionStatement) {
+        self.expression_count += 1;
+        self.walk_expression(expression_statement);
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn check(code: &str, limit: usize) -> PassResultReference {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckFunctionComplexityPass::new(symbol_table.clone(), result.clone(), limit);
+
+        pass.execute(&mut ast);
+
+        result
+    }
+
+    #[test]
+    fn function_within_limit() {
+        let result = check("fn test(a: i32) -> i32 { return a + 1; }", 3);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn function_exceeding_limit_produces_an_error() {
+        let result = check("fn test(a: i32) -> i32 { let b = a * 2; return a + b + 1; }", 3);
+
+        assert!(result.borrow().get_errors().iter().any(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => *e.get_kind() == ErrorKind::FunctionTooComplex("test".to_owned(), 8, 3),
+            None => false,
+        }));
+    }
+}
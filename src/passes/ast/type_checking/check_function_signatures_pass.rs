@@ -1,3 +1,4 @@
+use ::std::collections::HashSet;
 use ::ast::*;
 use ::passes::*;
 use ::passes::ast::*;
@@ -21,9 +22,56 @@ impl CheckFunctionSignaturePass {
             argument_type_list: None,
         }
     }
+
+    /// stages can't be called, so they get no function type, only their return and argument types are resolved
+    fn check_stage_signature(&mut self, function_declaration: &mut FunctionDeclaration) {
+        let return_type_name = &function_declaration.return_type_name;
+        let type_ref = symbol_table!(self).find_type_ref(&return_type_name.name);
+        if type_ref.is_none() {
+            result_mut!(self).add_error(Box::new(TypeError::new(return_type_name.get_span(), ErrorKind::TypeNotFound(return_type_name.name.to_owned()))));
+        }
+        function_declaration.return_type = type_ref;
+
+        for argument in function_declaration.arguments.iter_mut() {
+            self.visit_function_argument(argument);
+        }
+    }
 }
 
 ast_pass_impl!(CheckFunctionSignaturePass, {
+    fn visit(&mut self, items: &mut Ast) {
+        for item in items.iter_mut() {
+            match *item {
+                ItemKind::Function(ref mut item) => self.visit_function(item),
+                ItemKind::Program(ref mut item) => {
+                    for stage in item.program_stages.iter_mut() {
+                        self.check_stage_signature(&mut stage.function);
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        // aliases may refer to functions declared after them
+        for item in items.iter_mut() {
+            if let ItemKind::FunctionAlias(ref mut item) = *item {
+                self.visit_function_alias(item);
+            }
+        }
+    }
+
+    fn visit_function_alias(&mut self, function_alias_declaration: &mut FunctionAliasDeclaration) {
+        let target_name = &function_alias_declaration.target_name;
+        let target_type = symbol_table_mut!(self).find_symbol(&target_name.name).and_then(|s| s.get_type());
+        let target_type = match target_type {
+            Some(t) if symbol_table!(self).find_type(t).map_or(false, |t| t.is_callable()) => t,
+            _ => pass_try!(self, Err(TypeError::new(target_name.span, ErrorKind::AliasTargetNotFound(target_name.name.to_owned())))),
+        };
+
+        pass_try!(self, symbol_table_mut!(self).add_symbol_with_type(&function_alias_declaration.alias_name.name, target_type));
+        function_alias_declaration.alias_type = Some(target_type);
+    }
+
     fn visit_function(&mut self, function_declaration: &mut FunctionDeclaration) {
         // let mut symbol_table = symbol_table_mut!(self);
 
@@ -32,35 +80,62 @@ ast_pass_impl!(CheckFunctionSignaturePass, {
 
         symbol_table_mut!(self).enter_scope();
 
-        let type_ref = pass_try!(self, symbol_table!(self).find_type_ref_or_err(&function_declaration.return_type_name.name));
-        function_declaration.return_type    = Some(type_ref.clone());
+        // an unknown return type is reported, the arguments are still checked and brought into scope for the body
+        let return_type_name = &function_declaration.return_type_name;
+        let type_ref = symbol_table!(self).find_type_ref(&return_type_name.name);
+        if type_ref.is_none() {
+            result_mut!(self).add_error(Box::new(TypeError::new(return_type_name.get_span(), ErrorKind::TypeNotFound(return_type_name.name.to_owned()))));
+        }
+        function_declaration.return_type    = type_ref;
         function_declaration.declaring_type = Some(function_type);
 
+        let mut argument_names = HashSet::new();
         self.argument_type_list = Some(Vec::new());
         for argument in function_declaration.arguments.iter_mut() {
             self.visit_function_argument(argument);
+
+            // the first argument with a name stays in scope so the body still checks
+            if !argument_names.insert(argument.argument_name.name.to_owned()) {
+                let error = TypeError::new(argument.span, ErrorKind::DuplicateArgumentName(function_declaration.function_name.name.to_owned(), argument.argument_name.name.to_owned()));
+                result_mut!(self).add_error(Box::new(error));
+                continue;
+            }
+
+            if let Some(argument_type) = argument.argument_type {
+                if let Err(error) = symbol_table_mut!(self).add_symbol_with_type(&argument.argument_name.name, argument_type) {
+                    result_mut!(self).add_error(Box::new(error));
+                }
+            }
         }
-        
+
         let argument_list = self.argument_type_list.take().unwrap();
-        
-        let signature = CallSignature::new(argument_list, Some(type_ref));
-        pass_try!(self, pass_try!(self, symbol_table_mut!(self).find_type_mut_or_err(function_type)).make_callable(signature));
+
+        // a signature missing arguments would report every call as mismatched
+        if argument_list.len() == function_declaration.arguments.len() {
+            let signature = CallSignature::new(argument_list, type_ref);
+            let callable = symbol_table_mut!(self).find_type_mut_or_err(function_type).and_then(|t| t.make_callable(signature));
+            if let Err(error) = callable {
+                result_mut!(self).add_error(Box::new(error));
+            }
+        }
 
         symbol_table_mut!(self).leave_scope();
     }
 
-    fn visit_function_argument(&mut self, function_argument_declaration: &mut FunctionArgumentDeclaration) {
-        let mut list = self.argument_type_list.take().unwrap();
+    fn visit_operator(&mut self, _operator_declaration: &mut OperatorDeclaration) {
+        // operator signatures are registered by the operators pass
+    }
 
+    fn visit_function_argument(&mut self, function_argument_declaration: &mut FunctionArgumentDeclaration) {
         let type_ref = match symbol_table!(self).find_type_ref(&function_argument_declaration.argument_type_name.name) {
             Some(t) => t,
             None => pass_try!(self, Err(TypeError::new(function_argument_declaration.argument_type_name.get_span(), ErrorKind::TypeNotFound(function_argument_declaration.argument_type_name.name.to_owned())))),
         };
         function_argument_declaration.argument_type = Some(type_ref);
-        pass_try!(self, symbol_table_mut!(self).add_symbol_with_type(&function_argument_declaration.argument_name.name, type_ref));
-        list.push(type_ref);
 
-        self.argument_type_list = Some(list);
+        if let Some(ref mut list) = self.argument_type_list {
+            list.push(type_ref);
+        }
     }
 });
 
@@ -73,6 +148,7 @@ mod tests {
     use ::type_system::type_environment::TypeEnvironment;
     use ::passes::ast::type_checking::check_primitives_pass;
     use ::passes::ast::type_checking::discover_structs_pass;
+    use ::passes::ast::type_checking::check_function_bodies_pass;
 
     #[test]
     fn function_no_param_empty_body() {
@@ -131,4 +207,87 @@ mod tests {
         assert!(symbol_table.borrow().find_type_by_name("test").unwrap().get_call_signature().unwrap().match_arguments(vec![i32TypeRef]));
         assert!(symbol_table.borrow().find_type_by_name("test").unwrap().get_call_signature().unwrap().match_return_type(Some(TestTypeRef)));
     }
+
+    fn check_aliases(code: &str) -> (Ast, SymbolTableReference, PassResultReference) {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(check_primitives_pass::CheckPrimitivesPass::new(symbol_table.clone(), result.clone())),
+            Box::new(CheckFunctionSignaturePass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_function_bodies_pass::CheckFunctionBodiesPass::new(symbol_table.clone(), result.clone())),
+        ]);
+
+        passes.execute(&mut ast);
+
+        (ast, symbol_table, result)
+    }
+
+    #[test]
+    fn call_through_alias() {
+        let (ast, symbol_table, result) = check_aliases("primitive type f32; fn fastscale = scale; fn scale(a: f32) -> f32 { return a * 2.0; } fn test(a: f32) -> f32 { return fastscale(a); }");
+
+        let f32_type = symbol_table.borrow().find_type_ref("f32").unwrap();
+        let scale_type = symbol_table.borrow().find_type_ref("scale").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        match ast[1] {
+            ItemKind::FunctionAlias(ref a) => assert_eq!(a.alias_type, Some(scale_type)),
+            _ => panic!("expected function alias"),
+        }
+        match ast[3] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => assert_eq!(r.return_type, Some(f32_type)),
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn alias_to_missing_function_produces_an_error() {
+        let (_, _, result) = check_aliases("primitive type f32; fn fastscale = scale;");
+
+        assert!(result.borrow().get_errors().iter().any(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => *e.get_kind() == ErrorKind::AliasTargetNotFound("scale".to_owned()),
+            None => false,
+        }));
+    }
+
+    #[test]
+    fn duplicate_argument_name_produces_an_error() {
+        let mut ast = compile_ast("primitive type f32; fn f(x: f32, x: f32) -> f32 { return x; }");
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(check_primitives_pass::CheckPrimitivesPass::new(symbol_table.clone(), result.clone())),
+            Box::new(CheckFunctionSignaturePass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_function_bodies_pass::CheckFunctionBodiesPass::new(symbol_table.clone(), result.clone())),
+        ]);
+
+        passes.execute(&mut ast);
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![&ErrorKind::DuplicateArgumentName("f".to_owned(), "x".to_owned())]);
+    }
+
+    #[test]
+    fn same_argument_name_in_different_functions() {
+        let mut ast = compile_ast("primitive type f32; fn f(x: f32) -> f32 { return x; } fn g(x: f32, y: f32) -> f32 { return x; }");
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(check_primitives_pass::CheckPrimitivesPass::new(symbol_table.clone(), result.clone())),
+            Box::new(CheckFunctionSignaturePass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_function_bodies_pass::CheckFunctionBodiesPass::new(symbol_table.clone(), result.clone())),
+        ]);
+
+        passes.execute(&mut ast);
+
+        assert!(!result.borrow().has_errors());
+    }
 }
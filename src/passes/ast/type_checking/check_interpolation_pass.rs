@@ -0,0 +1,118 @@
+use ::std::collections::HashSet;
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+
+const INTERPOLATION_QUALIFIERS: &[&str] = &["flat", "noperspective", "smooth"];
+
+/// Checks that interpolation qualifiers like `[flat]` are only used on fragment stage inputs,
+/// either on the arguments themselves or on members of the structs they are declared with
+pub struct CheckInterpolationPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+}
+
+impl CheckInterpolationPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckInterpolationPass {
+        CheckInterpolationPass {
+            symbol_table: symbol_table,
+            result: result,
+        }
+    }
+
+    fn check_attributes(&mut self, attributes: &[AttributeDeclaration], is_fragment_input: bool) {
+        for attribute in attributes.iter() {
+            let qualifier = &attribute.attribute_name.name;
+            if !INTERPOLATION_QUALIFIERS.contains(&qualifier.as_str()) {
+                continue;
+            }
+
+            if !attribute.arguments.is_empty() {
+                result_mut!(self).add_error(Box::new(TypeError::new(attribute.span, ErrorKind::InvalidAttributeArguments(qualifier.to_owned()))));
+            }
+
+            if !is_fragment_input {
+                result_mut!(self).add_error(Box::new(TypeError::new(attribute.span, ErrorKind::InterpolationNotApplicable(qualifier.to_owned()))));
+            }
+        }
+    }
+}
+
+ast_pass_impl!(CheckInterpolationPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        let mut fragment_inputs = HashSet::new();
+
+        for item in items.iter() {
+            let program = match *item {
+                ItemKind::Program(ref p) => p,
+                _ => continue,
+            };
+
+            for stage in program.program_stages.iter() {
+                let is_fragment = stage.stage_name.name == "fragment";
+                for argument in stage.function.arguments.iter() {
+                    self.check_attributes(&argument.attributes, is_fragment);
+                    if is_fragment {
+                        fragment_inputs.insert(argument.argument_type_name.name.to_owned());
+                    }
+                }
+            }
+        }
+
+        for item in items.iter() {
+            if let ItemKind::Struct(ref s) = *item {
+                let is_fragment_input = fragment_inputs.contains(&s.struct_name.name);
+                for member in s.struct_member.iter() {
+                    self.check_attributes(&member.attributes, is_fragment_input);
+                }
+            }
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn check(code: &str) -> PassResultReference {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckInterpolationPass::new(symbol_table.clone(), result.clone());
+
+        pass.execute(&mut ast);
+
+        result
+    }
+
+    #[test]
+    fn flat_fragment_input() {
+        let result = check("
+            struct VertexOutput { [flat] id: i32, [noperspective] uv: vec2, }
+            program Test { stage fragment(input: VertexOutput, [smooth] color: vec4) -> vec4 {} }
+        ");
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn flat_on_non_input_produces_an_error() {
+        let result = check("
+            struct Settings { [flat] id: i32, }
+            program Test { stage vertex([flat] position: vec4) -> vec4 {} }
+        ");
+
+        let errors = result.borrow().get_errors().iter().filter(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => *e.get_kind() == ErrorKind::InterpolationNotApplicable("flat".to_owned()),
+            None => false,
+        }).count();
+        assert_eq!(errors, 2);
+    }
+}
@@ -0,0 +1,142 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::type_environment::TypeReference;
+use ::type_system::error::{ TypeError, ErrorKind };
+
+pub struct CheckOperatorsPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+    is_core_module: bool,
+}
+
+impl CheckOperatorsPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference, is_core_module: bool) -> CheckOperatorsPass {
+        CheckOperatorsPass {
+            symbol_table: symbol_table,
+            result: result,
+            is_core_module: is_core_module,
+        }
+    }
+
+    fn find_type_ref(&mut self, type_name: &Identifier) -> Option<TypeReference> {
+        let type_ref = symbol_table!(self).find_type_ref(&type_name.name);
+        if type_ref.is_none() {
+            result_mut!(self).add_error(Box::new(TypeError::new(type_name.span, ErrorKind::TypeNotFound(type_name.name.to_owned()))));
+        }
+
+        type_ref
+    }
+}
+
+ast_pass_impl!(CheckOperatorsPass, {
+    fn visit_operator(&mut self, operator_declaration: &mut OperatorDeclaration) {
+        // operators without body are implemented by the compiler
+        if operator_declaration.block.is_none() && !self.is_core_module {
+            pass_try!(self, Err(TypeError::new(operator_declaration.span, ErrorKind::SyntaxOnlyValidInCoreModule)));
+        }
+
+        if operator_declaration.arguments.len() != 2 {
+            pass_try!(self, Err(TypeError::new(operator_declaration.span, ErrorKind::IncompatibleArguments)));
+        }
+
+        for argument in operator_declaration.arguments.iter_mut() {
+            argument.argument_type = self.find_type_ref(&argument.argument_type_name);
+        }
+        operator_declaration.operator_type = self.find_type_ref(&operator_declaration.return_type);
+
+        let (lhs, rhs, operator_type) = match (operator_declaration.arguments[0].argument_type, operator_declaration.arguments[1].argument_type, operator_declaration.operator_type) {
+            (Some(lhs), Some(rhs), Some(operator_type)) => (lhs, rhs, operator_type),
+            _ => return,
+        };
+
+        if symbol_table!(self).find_operator(operator_declaration.operator, lhs, rhs).is_some() {
+            let lhs_name = operator_declaration.arguments[0].argument_type_name.name.to_owned();
+            let rhs_name = operator_declaration.arguments[1].argument_type_name.name.to_owned();
+            pass_try!(self, Err(TypeError::new(operator_declaration.span, ErrorKind::OperatorAlreadyDeclared(operator_declaration.operator.get_symbol().to_owned(), lhs_name, rhs_name))));
+        }
+
+        symbol_table_mut!(self).add_operator(operator_declaration.operator, lhs, rhs, operator_type);
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+    use ::passes::ast::type_checking::{ TypeChecker, TypeCheckerOptions };
+
+    fn check(code: &str) -> (Ast, SymbolTableReference, PassResultReference) {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut type_checker = TypeChecker::with_options(symbol_table.clone(), result.clone(), false, TypeCheckerOptions::new());
+
+        type_checker.execute(&mut ast);
+
+        (ast, symbol_table, result)
+    }
+
+    #[test]
+    fn declare_and_use_struct_operator() {
+        let (ast, symbol_table, result) = check("
+            primitive type f32;
+            struct Vec4 { x: f32, y: f32, z: f32, w: f32, }
+            operator + (a: Vec4, b: Vec4) -> Vec4 { let c = a; return c; }
+            fn add(a: Vec4, b: Vec4) -> Vec4 { return a + b; }
+        ");
+
+        let vec4_type = symbol_table.borrow().find_type_ref("Vec4").unwrap();
+
+        assert!(!result.borrow().has_errors());
+        assert_eq!(symbol_table.borrow().find_operator(Operator::Plus, vec4_type, vec4_type), Some(vec4_type));
+        match ast[3] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => assert_eq!(r.expression.get_type(), Some(vec4_type)),
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn redeclared_operator_produces_an_error() {
+        let (_, _, result) = check("
+            primitive type f32;
+            struct Vec4 { x: f32, }
+            operator + (a: Vec4, b: Vec4) -> Vec4 { return a; }
+            operator + (lhs: Vec4, rhs: Vec4) -> Vec4 { return rhs; }
+        ");
+
+        assert!(result.borrow().get_errors().iter().any(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => *e.get_kind() == ErrorKind::OperatorAlreadyDeclared("+".to_owned(), "Vec4".to_owned(), "Vec4".to_owned()),
+            None => false,
+        }));
+    }
+
+    #[test]
+    fn undeclared_struct_operator_produces_an_error() {
+        let (_, _, result) = check("
+            primitive type f32;
+            struct Vec4 { x: f32, }
+            fn add(a: Vec4, b: Vec4) -> Vec4 { return a + b; }
+        ");
+
+        assert!(result.borrow().get_errors().iter().any(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => *e.get_kind() == ErrorKind::OperatorNotFound("+".to_owned(), "Vec4".to_owned(), "Vec4".to_owned()),
+            None => false,
+        }));
+    }
+
+    #[test]
+    fn non_core_module_cannot_declare_operators_without_body() {
+        let (_, _, result) = check("primitive type f32; operator + (a: f32, b: f32) -> f32;");
+
+        assert!(result.borrow().has_errors());
+    }
+}
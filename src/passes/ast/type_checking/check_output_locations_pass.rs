@@ -0,0 +1,123 @@
+use ::std::collections::HashMap;
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+
+/// Checks that the `[location(n)]` members of a fragment stage output struct form the range `0..n`
+pub struct CheckOutputLocationsPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+}
+
+impl CheckOutputLocationsPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckOutputLocationsPass {
+        CheckOutputLocationsPass {
+            symbol_table: symbol_table,
+            result: result,
+        }
+    }
+
+    fn find_locations(&mut self, struct_definition: &StructDefinition) -> Vec<usize> {
+        let mut locations = Vec::new();
+
+        for attribute in struct_definition.struct_member.iter().flat_map(|m| m.attributes.iter()) {
+            if attribute.attribute_name.name != "location" {
+                continue;
+            }
+
+            let location = match attribute.arguments.as_slice() {
+                [ExpressionStatement::Literal(ref literal)] if literal.literal_expression_type == LiteralType::Int => literal.value.parse::<usize>().ok(),
+                _ => None,
+            };
+
+            match location {
+                Some(location) => locations.push(location),
+                None => result_mut!(self).add_error(Box::new(TypeError::new(attribute.span, ErrorKind::InvalidAttributeArguments(attribute.attribute_name.name.to_owned())))),
+            }
+        }
+
+        locations
+    }
+}
+
+ast_pass_impl!(CheckOutputLocationsPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        let mut structs = HashMap::new();
+        for item in items.iter() {
+            if let ItemKind::Struct(ref s) = *item {
+                structs.insert(s.struct_name.name.as_str(), s);
+            }
+        }
+
+        for item in items.iter() {
+            let program = match *item {
+                ItemKind::Program(ref p) => p,
+                _ => continue,
+            };
+
+            for stage in program.program_stages.iter().filter(|s| s.stage_name.name == "fragment") {
+                let output = match structs.get(stage.function.return_type_name.name.as_str()) {
+                    Some(s) => *s,
+                    None => continue,
+                };
+
+                let locations = self.find_locations(output);
+                let count = match locations.iter().max() {
+                    Some(max) => max + 1,
+                    None => continue,
+                };
+
+                let gaps: Vec<usize> = (0..count).filter(|l| !locations.contains(l)).collect();
+                if !gaps.is_empty() {
+                    result_mut!(self).add_error(Box::new(TypeError::new(output.span, ErrorKind::NonContiguousOutputLocations(gaps))));
+                }
+            }
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn check(code: &str) -> PassResultReference {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckOutputLocationsPass::new(symbol_table.clone(), result.clone());
+
+        pass.execute(&mut ast);
+
+        result
+    }
+
+    #[test]
+    fn contiguous_output_locations() {
+        let result = check("
+            struct FragmentOutput { [location(1)] normal: vec4, [location(0)] color: vec4, }
+            program Test { stage fragment(color: vec4) -> FragmentOutput {} }
+        ");
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn output_locations_with_gap_produce_an_error() {
+        let result = check("
+            struct FragmentOutput { [location(0)] color: vec4, [location(3)] normal: vec4, }
+            program Test { stage fragment(color: vec4) -> FragmentOutput {} }
+        ");
+
+        assert!(result.borrow().get_errors().iter().any(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => *e.get_kind() == ErrorKind::NonContiguousOutputLocations(vec![1, 2]),
+            None => false,
+        }));
+    }
+}
@@ -1,14 +1,54 @@
+use ::std::rc::Rc;
 use ::ast::*;
 use ::passes::*;
 use ::passes::ast::*;
 use ::passes::results::PassResultReference;
 use ::type_system::symbol_table::{ SymbolTableReference };
 use ::type_system::type_environment::TypeReference;
+use ::type_system::error::{ TypeError, ErrorKind };
 
-ast_pass!(CheckPrimitivesPass, {
+/// Allows restricting the set of primitive types a core module may declare,
+/// e.g. to the types supported by a specific backend.
+pub trait PrimitiveValidator {
+    /// returns the reason of the rejection if the primitive is not allowed
+    fn validate(&self, primitive_name: &str) -> Result<(), String>;
+}
+
+pub struct CheckPrimitivesPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+    validator: Option<Rc<PrimitiveValidator>>,
+}
+
+impl CheckPrimitivesPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckPrimitivesPass {
+        CheckPrimitivesPass {
+            symbol_table: symbol_table,
+            result: result,
+            validator: None,
+        }
+    }
+
+    pub fn with_validator(symbol_table: SymbolTableReference, result: PassResultReference, validator: Option<Rc<PrimitiveValidator>>) -> CheckPrimitivesPass {
+        CheckPrimitivesPass {
+            symbol_table: symbol_table,
+            result: result,
+            validator: validator,
+        }
+    }
+}
+
+ast_pass_impl!(CheckPrimitivesPass, {
     fn visit_primitive(&mut self, primitive_declaration: &mut PrimitiveDeclaration) {
         pass_warning!(self, "'primitive' is experimental syntax and might get changed or removed in the future.");
 
+        if let Some(ref validator) = self.validator {
+            let primitive_name = &primitive_declaration.type_name.name;
+            if let Err(reason) = validator.validate(primitive_name) {
+                pass_try!(self, Err(TypeError::new(primitive_declaration.type_name.span, ErrorKind::InvalidPrimitive(primitive_name.to_owned(), reason))));
+            }
+        }
+
         let type_ref = pass_try!(self, symbol_table_mut!(self).create_global_type(&primitive_declaration.type_name.name));
 
         primitive_declaration.declaring_type = Some(type_ref);
@@ -46,4 +86,41 @@ mod tests {
 
         assert!(result.borrow().has_errors());
     }
+
+    struct KnownPrimitives;
+
+    impl PrimitiveValidator for KnownPrimitives {
+        fn validate(&self, primitive_name: &str) -> Result<(), String> {
+            match primitive_name {
+                "bool" | "f32" => Ok(()),
+                _ => Err("not supported by the backend".to_owned()),
+            }
+        }
+    }
+
+    #[test]
+    fn validator_accepts_known_primitive() {
+        let mut ast = compile_ast("primitive type f32;");
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckPrimitivesPass::with_validator(symbol_table.clone(), result.clone(), Some(Rc::new(KnownPrimitives)));
+
+        pass.execute(&mut ast);
+
+        assert!(!result.borrow().has_errors());
+        assert!(symbol_table.borrow().find_type_by_name("f32").is_some());
+    }
+
+    #[test]
+    fn validator_rejects_unknown_primitive() {
+        let mut ast = compile_ast("primitive type f16;");
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckPrimitivesPass::with_validator(symbol_table.clone(), result.clone(), Some(Rc::new(KnownPrimitives)));
+
+        pass.execute(&mut ast);
+
+        assert!(result.borrow().has_errors());
+        assert!(symbol_table.borrow().find_type_by_name("f16").is_none());
+    }
 }
@@ -0,0 +1,94 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+use ::type_system::layout::{ layout_of, LayoutRules };
+
+/// Checks that structs marked `[push_constant]` fit into the push constant range of the target
+/// push constants are laid out like storage buffers
+pub struct CheckPushConstantsPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+    limit: usize,
+}
+
+impl CheckPushConstantsPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference, limit: usize) -> CheckPushConstantsPass {
+        CheckPushConstantsPass {
+            symbol_table: symbol_table,
+            result: result,
+            limit: limit,
+        }
+    }
+}
+
+ast_pass_impl!(CheckPushConstantsPass, {
+    fn visit_struct(&mut self, struct_definition: &mut StructDefinition) {
+        if !struct_definition.attributes.iter().any(|a| a.attribute_name.name == "push_constant") {
+            return;
+        }
+
+        let struct_type = match struct_definition.declaring_type {
+            Some(t) => t,
+            None => return,
+        };
+
+        // structs without a known size are reported by the passes checking their members
+        let size = match layout_of(&symbol_table!(self), struct_type, LayoutRules::Std430) {
+            Some(layout) => layout.size,
+            None => return,
+        };
+
+        if size > self.limit {
+            pass_try!(self, Err(TypeError::new(struct_definition.struct_name.span, ErrorKind::PushConstantTooLarge(size, self.limit))));
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+    use ::passes::ast::type_checking::check_primitives_pass;
+    use ::passes::ast::type_checking::discover_structs_pass;
+    use ::passes::ast::type_checking::check_struct_member_pass;
+
+    fn check(code: &str, limit: usize) -> PassResultReference {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(check_primitives_pass::CheckPrimitivesPass::new(symbol_table.clone(), result.clone())),
+            Box::new(discover_structs_pass::DiscoverStructsPass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_struct_member_pass::CheckStructMemberPass::new(symbol_table.clone(), result.clone())),
+            Box::new(CheckPushConstantsPass::new(symbol_table.clone(), result.clone(), limit)),
+        ]);
+
+        passes.execute(&mut ast);
+
+        result
+    }
+
+    #[test]
+    fn push_constant_within_limit() {
+        let result = check("primitive type vec4; [push_constant] struct Params { color: vec4, offset: vec4, }", 32);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn push_constant_exceeding_limit_produces_an_error() {
+        let code = "primitive type f32; primitive type vec4; [push_constant] struct Params { color: vec4, offset: vec4, scale: f32, } struct Unmarked { a: vec4, b: vec4, c: vec4, }";
+        let result = check(code, 32);
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![&ErrorKind::PushConstantTooLarge(48, 32)]);
+    }
+}
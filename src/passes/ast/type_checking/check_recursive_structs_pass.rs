@@ -0,0 +1,143 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::type_environment::TypeReference;
+use ::type_system::error::{ TypeError, ErrorKind };
+
+/// Rejects structs containing themselves by value, directly, through other structs or through their bases, as they have no finite layout
+/// has to run after the member and base types are resolved
+pub struct CheckRecursiveStructsPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+}
+
+impl CheckRecursiveStructsPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckRecursiveStructsPass {
+        CheckRecursiveStructsPass {
+            symbol_table: symbol_table,
+            result: result,
+        }
+    }
+}
+
+/// a struct of the module with its base and the types of its members
+struct StructNode<'a> {
+    definition: &'a StructDefinition,
+    struct_type: TypeReference,
+    member_types: Vec<TypeReference>,
+}
+
+ast_pass_impl!(CheckRecursiveStructsPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        let structs: Vec<StructNode> = items.iter().filter_map(|item| match *item {
+            ItemKind::Struct(ref s) => s.declaring_type.map(|struct_type| StructNode {
+                definition: s,
+                struct_type: struct_type,
+                member_types: s.base_struct_type.into_iter().chain(s.struct_member.iter().filter_map(|m| m.struct_member_type)).collect(),
+            }),
+            _ => None,
+        }).collect();
+
+        let mut finished = Vec::new();
+        let mut path = Vec::new();
+        for node in structs.iter() {
+            if let Err(error) = visit_struct_node(node, &structs, &mut path, &mut finished) {
+                result_mut!(self).add_error(Box::new(error));
+            }
+        }
+    }
+});
+
+/// depth first search over by-value containment, each cycle is reported once from the struct it is entered at
+fn visit_struct_node<'a>(node: &'a StructNode, structs: &'a [StructNode], path: &mut Vec<&'a StructNode<'a>>, finished: &mut Vec<TypeReference>) -> Result<(), TypeError> {
+    if finished.contains(&node.struct_type) {
+        return Ok(());
+    }
+
+    if let Some(position) = path.iter().position(|n| n.struct_type == node.struct_type) {
+        let mut cycle: Vec<String> = path[position..].iter().map(|n| n.definition.struct_name.name.to_owned()).collect();
+        cycle.push(node.definition.struct_name.name.to_owned());
+        let span = path[position].definition.struct_name.span;
+
+        // finish the cycle so it isn't reported again from its other members
+        finished.extend(path[position..].iter().map(|n| n.struct_type));
+        return Err(TypeError::new(span, ErrorKind::RecursiveStruct(cycle)));
+    }
+
+    path.push(node);
+    let mut result = Ok(());
+    for member_type in node.member_types.iter() {
+        if let Some(member) = structs.iter().find(|n| n.struct_type == *member_type) {
+            result = visit_struct_node(member, structs, path, finished);
+            if result.is_err() {
+                break;
+            }
+        }
+    }
+    path.pop();
+
+    finished.push(node.struct_type);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+    use ::passes::ast::type_checking::discover_structs_pass;
+    use ::passes::ast::type_checking::check_struct_member_pass;
+
+    fn check(code: &str) -> PassResultReference {
+        let mut ast = compile_ast(code);
+        let mut symbol_table = SymbolTable::new(TypeEnvironment::new());
+        symbol_table.create_global_type("f32").unwrap();
+        let symbol_table = SymbolTableReference::new(symbol_table);
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(discover_structs_pass::DiscoverStructsPass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_struct_member_pass::CheckStructMemberPass::new(symbol_table.clone(), result.clone())),
+            Box::new(CheckRecursiveStructsPass::new(symbol_table.clone(), result.clone())),
+        ]);
+
+        passes.execute(&mut ast);
+
+        result
+    }
+
+    fn recursive_structs(result: &PassResultReference) -> Vec<Vec<String>> {
+        result.borrow().get_errors().iter().filter_map(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => match *e.get_kind() {
+                ErrorKind::RecursiveStruct(ref cycle) => Some(cycle.clone()),
+                _ => None,
+            },
+            None => None,
+        }).collect()
+    }
+
+    #[test]
+    fn forward_reference() {
+        let result = check("struct A { b: B, } struct B { x: f32, }");
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn direct_recursion_produces_an_error() {
+        let result = check("struct A { x: f32, a: A, }");
+
+        assert_eq!(recursive_structs(&result), vec![vec!["A".to_owned(), "A".to_owned()]]);
+    }
+
+    #[test]
+    fn indirect_recursion_produces_an_error() {
+        let result = check("struct A { b: B, } struct B { c: C, } struct C { a: A, }");
+
+        assert_eq!(recursive_structs(&result), vec![vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "A".to_owned()]]);
+    }
+}
@@ -0,0 +1,258 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::type_environment::TypeReference;
+use ::type_system::error::{ TypeError, ErrorKind };
+
+/// Consistency check for the type checker itself
+/// verifies that all nodes the type checker is supposed to resolve carry a type
+/// skipped if previous passes already produced errors, as those leave nodes unresolved
+pub struct CheckResolvedTypesPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+}
+
+impl CheckResolvedTypesPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckResolvedTypesPass {
+        CheckResolvedTypesPass {
+            symbol_table: symbol_table,
+            result: result,
+        }
+    }
+
+    fn expect_resolved(&mut self, type_reference: Option<TypeReference>, span: Span, node_description: String) {
+        if type_reference.is_none() {
+            result_mut!(self).add_error(Box::new(TypeError::new(span, ErrorKind::UnresolvedReference(node_description))));
+        }
+    }
+}
+
+ast_pass_impl!(CheckResolvedTypesPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        if result!(self).has_errors() {
+            return;
+        }
+
+        for item in items.iter_mut() {
+            match *item {
+                ItemKind::Struct(ref mut item) => self.visit_struct(item),
+                ItemKind::Enum(ref mut item) => self.visit_enum(item),
+                ItemKind::Function(ref mut item) => self.visit_function(item),
+                ItemKind::FunctionAlias(ref mut item) => self.visit_function_alias(item),
+                ItemKind::Primitive(ref mut item) => self.visit_primitive(item),
+                ItemKind::Operator(ref mut item) => self.visit_operator(item),
+                ItemKind::Constant(ref mut item) => self.visit_constant(item),
+                ItemKind::Program(ref mut item) => self.visit_program(item),
+                _ => (),
+            };
+        }
+    }
+
+    fn visit_primitive(&mut self, primitive_declaration: &mut PrimitiveDeclaration) {
+        let description = format!("primitive \"{}\"", primitive_declaration.type_name.name);
+        self.expect_resolved(primitive_declaration.declaring_type, primitive_declaration.span, description);
+    }
+
+    fn visit_struct(&mut self, struct_definition: &mut StructDefinition) {
+        let description = format!("struct \"{}\"", struct_definition.struct_name.name);
+        self.expect_resolved(struct_definition.declaring_type, struct_definition.span, description);
+        if let Some(ref base_name) = struct_definition.base_struct_name {
+            self.expect_resolved(struct_definition.base_struct_type, base_name.span, format!("base struct \"{}\"", base_name.name));
+        }
+        self.walk_struct(struct_definition);
+    }
+
+    fn visit_enum(&mut self, enum_definition: &mut EnumDefinition) {
+        let description = format!("enum \"{}\"", enum_definition.enum_name.name);
+        self.expect_resolved(enum_definition.declaring_type, enum_definition.span, description);
+    }
+
+    fn visit_struct_member(&mut self, struct_member_definition: &mut StructMemberDefinition) {
+        let description = format!("struct member \"{}\"", struct_member_definition.struct_member_name.name);
+        self.expect_resolved(struct_member_definition.struct_member_type, struct_member_definition.span, description);
+    }
+
+    fn visit_function(&mut self, function_declaration: &mut FunctionDeclaration) {
+        let description = format!("function \"{}\"", function_declaration.function_name.name);
+        self.expect_resolved(function_declaration.declaring_type, function_declaration.span, description.clone());
+        self.expect_resolved(function_declaration.return_type, function_declaration.return_type_name.span, format!("return type of {}", description));
+        self.walk_function(function_declaration);
+    }
+
+    /// stages have no function type, they can't be called
+    fn visit_program_stage(&mut self, program_stage_definition: &mut ProgramStageDefinition) {
+        let function_declaration = &mut program_stage_definition.function;
+        let description = format!("return type of stage \"{}\"", function_declaration.function_name.name);
+        self.expect_resolved(function_declaration.return_type, function_declaration.return_type_name.span, description);
+        self.walk_function(function_declaration);
+    }
+
+    fn visit_constant(&mut self, constant_definition: &mut ConstantDefinition) {
+        let description = format!("constant \"{}\"", constant_definition.constant_name.name);
+        self.expect_resolved(constant_definition.constant_type, constant_definition.span, description);
+        self.walk_constant(constant_definition);
+    }
+
+    fn visit_function_alias(&mut self, function_alias_declaration: &mut FunctionAliasDeclaration) {
+        let description = format!("function alias \"{}\"", function_alias_declaration.alias_name.name);
+        self.expect_resolved(function_alias_declaration.alias_type, function_alias_declaration.span, description);
+    }
+
+    fn visit_operator(&mut self, operator_declaration: &mut OperatorDeclaration) {
+        let description = format!("operator \"{}\"", operator_declaration.operator.get_symbol());
+        self.expect_resolved(operator_declaration.operator_type, operator_declaration.return_type.span, format!("return type of {}", description));
+        self.walk_operator(operator_declaration);
+    }
+
+    fn visit_function_argument(&mut self, function_argument: &mut FunctionArgumentDeclaration) {
+        let description = format!("argument \"{}\"", function_argument.argument_name.name);
+        self.expect_resolved(function_argument.argument_type, function_argument.span, description);
+    }
+
+    fn visit_local_statement(&mut self, local_statement: &mut LocalDeclaration) {
+        let description = format!("local \"{}\"", local_statement.symbol_name.name);
+        self.expect_resolved(local_statement.local_type, local_statement.span, description);
+        self.walk_local_statement(local_statement);
+    }
+
+    fn visit_const_statement(&mut self, const_statement: &mut LocalConstantDeclaration) {
+        let description = format!("constant \"{}\"", const_statement.constant_name.name);
+        self.expect_resolved(const_statement.constant_type, const_statement.span, description);
+        self.walk_const_statement(const_statement);
+    }
+
+    fn visit_return_statement(&mut self, return_statement: &mut ReturnDeclaration) {
+        self.expect_resolved(return_statement.return_type, return_statement.span, "return statement".to_owned());
+        self.walk_return_statement(return_statement);
+    }
+
+    fn visit_prefix_expression(&mut self, prefix_expression: &mut PrefixExpression) {
+        self.expect_resolved(prefix_expression.prefix_type, prefix_expression.span, "prefix expression".to_owned());
+        self.walk_prefix_expression(prefix_expression);
+    }
+
+    fn visit_literal_expression(&mut self, literal_expression: &mut LiteralExpression) {
+        let description = format!("literal \"{}\"", literal_expression.value);
+        self.expect_resolved(literal_expression.literal_type, literal_expression.span, description);
+    }
+
+    fn visit_variable_expression(&mut self, variable_expression: &mut VariableExpression) {
+        let description = format!("variable \"{}\"", variable_expression.variable_name.name);
+        self.expect_resolved(variable_expression.variable_type, variable_expression.span, description);
+    }
+
+    fn visit_infix_expression(&mut self, infix_expression: &mut InfixExpression) {
+        self.expect_resolved(infix_expression.infix_type, infix_expression.span, "infix expression".to_owned());
+        self.walk_infix_expression(infix_expression);
+    }
+
+    fn visit_call_expression(&mut self, call_expression: &mut CallExpression) {
+        let description = format!("call to \"{}\"", call_expression.function_name.name);
+        self.expect_resolved(call_expression.function_type, call_expression.span, description);
+        self.walk_call_expression(call_expression);
+    }
+
+    fn visit_enum_variant_expression(&mut self, enum_variant_expression: &mut EnumVariantExpression) {
+        let description = format!("enum variant \"{}::{}\"", enum_variant_expression.enum_name.name, enum_variant_expression.variant_name.name);
+        self.expect_resolved(enum_variant_expression.variant_type, enum_variant_expression.span, description);
+    }
+
+    fn visit_cast_expression(&mut self, cast_expression: &mut CastExpression) {
+        self.expect_resolved(cast_expression.cast_target_type, cast_expression.span, "cast expression".to_owned());
+        self.walk_cast_expression(cast_expression);
+    }
+
+    fn visit_struct_instantiation_expression(&mut self, struct_instantiation_expression: &mut StructInstantiationExpression) {
+        let description = format!("instantiation of \"{}\"", struct_instantiation_expression.struct_type_name.name);
+        self.expect_resolved(struct_instantiation_expression.struct_type, struct_instantiation_expression.span, description);
+        self.walk_struct_instantiation_expression(struct_instantiation_expression);
+    }
+
+    fn visit_struct_field_initializer(&mut self, struct_field_initializer: &mut StructFieldInitializerExpression) {
+        let description = format!("field initializer \"{}\"", struct_field_initializer.struct_field_name.name);
+        self.expect_resolved(struct_field_initializer.struct_field_type, struct_field_initializer.span, description);
+        self.walk_struct_field_initializer(struct_field_initializer);
+    }
+
+    fn visit_field_accessor_expression(&mut self, field_accessor_expression: &mut FieldAccessorExpression) {
+        let description = format!("field accessor \"{}.{}\"", field_accessor_expression.variable_name.name, field_accessor_expression.field_name.name);
+        self.expect_resolved(field_accessor_expression.field_type, field_accessor_expression.span, description);
+    }
+
+    fn visit_index_accessor_expression(&mut self, index_accessor_expression: &mut IndexAccesorExpression) {
+        let description = format!("index accessor on \"{}\"", index_accessor_expression.variable_name.name);
+        self.expect_resolved(index_accessor_expression.element_type, index_accessor_expression.span, description);
+        self.walk_index_accessor_expression(index_accessor_expression);
+    }
+
+    fn visit_ternary_expression(&mut self, ternary_expression: &mut TernaryExpression) {
+        self.expect_resolved(ternary_expression.ternary_type, ternary_expression.span, "ternary expression".to_owned());
+        self.walk_ternary_expression(ternary_expression);
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+    use ::passes::ast::type_checking::{ TypeChecker, TypeCheckerOptions };
+
+    #[test]
+    fn checked_module_is_resolved() {
+        let mut ast = compile_ast("
+            primitive type i32;
+            primitive type f32;
+            primitive type bool;
+            primitive type vec2;
+            struct Foo { a: i32, }
+            const scale: i32 = 2;
+            fn test(a: i32) -> i32 { let b = a + 1; return b; }
+            fn other(foo: Foo, v: vec2, flag: bool) -> f32 { let bar = Foo { a: foo.a * scale }; return flag ? v[bar.a] : 1.0; }
+            program Test { stage vertex(a: i32) -> i32 { return test(a) + scale; } }
+        ");
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(TypeChecker::with_options(symbol_table.clone(), result.clone(), true, TypeCheckerOptions::new())),
+            Box::new(CheckResolvedTypesPass::new(symbol_table.clone(), result.clone())),
+        ]);
+
+        passes.execute(&mut ast);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn unchecked_module_is_unresolved() {
+        let mut ast = compile_ast("fn test() -> i32 { return 1; }");
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckResolvedTypesPass::new(symbol_table.clone(), result.clone());
+
+        pass.execute(&mut ast);
+
+        assert!(result.borrow().has_errors());
+    }
+
+    #[test]
+    fn unchecked_constants_and_stages_are_unresolved() {
+        let mut ast = compile_ast("const scale: i32; program Test { stage vertex() -> i32 { return 1; } }");
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckResolvedTypesPass::new(symbol_table.clone(), result.clone());
+
+        pass.execute(&mut ast);
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert!(kinds.contains(&&ErrorKind::UnresolvedReference("constant \"scale\"".to_owned())));
+        assert!(kinds.contains(&&ErrorKind::UnresolvedReference("return type of stage \"vertex\"".to_owned())));
+        assert!(kinds.contains(&&ErrorKind::UnresolvedReference("return statement".to_owned())));
+    }
+}
@@ -0,0 +1,123 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+
+/// HLSL semantics without their optional index, compared case-insensitively like HLSL does
+const KNOWN_SEMANTICS: &[&str] = &[
+    "SV_POSITION", "SV_TARGET", "SV_DEPTH", "SV_VERTEXID", "SV_INSTANCEID", "SV_ISFRONTFACE",
+    "SV_PRIMITIVEID", "SV_SAMPLEINDEX", "SV_COVERAGE", "SV_CLIPDISTANCE", "SV_CULLDISTANCE",
+    "SV_DISPATCHTHREADID", "SV_GROUPID", "SV_GROUPTHREADID", "SV_GROUPINDEX",
+    "POSITION", "NORMAL", "TANGENT", "BINORMAL", "TEXCOORD", "COLOR", "BLENDWEIGHT", "BLENDINDICES", "PSIZE",
+];
+
+/// Validates `[semantic("...")]` attributes on struct members and program stage arguments
+pub struct CheckSemanticsPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+}
+
+impl CheckSemanticsPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckSemanticsPass {
+        CheckSemanticsPass {
+            symbol_table: symbol_table,
+            result: result,
+        }
+    }
+
+    fn check_attributes(&mut self, attributes: &[AttributeDeclaration]) {
+        for attribute in attributes.iter().filter(|a| a.attribute_name.name == "semantic") {
+            let semantic = match attribute.arguments.as_slice() {
+                [ExpressionStatement::Literal(ref literal)] if literal.literal_expression_type == LiteralType::String => literal,
+                _ => {
+                    result_mut!(self).add_error(Box::new(TypeError::new(attribute.span, ErrorKind::InvalidAttributeArguments(attribute.attribute_name.name.to_owned()))));
+                    continue;
+                },
+            };
+
+            if !is_known_semantic(&semantic.value) {
+                result_mut!(self).add_error(Box::new(TypeError::new(semantic.span, ErrorKind::UnknownSemantic(semantic.value.to_owned()))));
+            }
+        }
+    }
+}
+
+fn is_known_semantic(semantic: &str) -> bool {
+    let name = semantic.trim_end_matches(|c: char| c.is_ascii_digit()).to_ascii_uppercase();
+    KNOWN_SEMANTICS.contains(&name.as_str())
+}
+
+ast_pass_impl!(CheckSemanticsPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        for item in items.iter_mut() {
+            match *item {
+                ItemKind::Struct(ref mut item) => self.visit_struct(item),
+                ItemKind::Program(ref program) => {
+                    for stage in program.program_stages.iter() {
+                        for argument in stage.function.arguments.iter() {
+                            self.check_attributes(&argument.attributes);
+                        }
+                    }
+                },
+                _ => (),
+            };
+        }
+    }
+
+    fn visit_struct_member(&mut self, struct_member_definition: &mut StructMemberDefinition) {
+        self.check_attributes(&struct_member_definition.attributes);
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn check(code: &str) -> PassResultReference {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckSemanticsPass::new(symbol_table.clone(), result.clone());
+
+        pass.execute(&mut ast);
+
+        result
+    }
+
+    #[test]
+    fn known_semantics() {
+        let result = check("
+            struct VertexOutput { [semantic(\"SV_Position\")] position: vec4, [semantic(\"TEXCOORD0\")] uv: vec2, }
+            program Test { stage vertex([semantic(\"POSITION\")] position: vec4) -> VertexOutput {} }
+        ");
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn unknown_semantic_produces_an_error() {
+        let result = check("
+            struct VertexOutput { [semantic(\"SV_Positon\")] position: vec4, }
+        ");
+
+        assert!(result.borrow().get_errors().iter().any(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => *e.get_kind() == ErrorKind::UnknownSemantic("SV_Positon".to_owned()),
+            None => false,
+        }));
+    }
+
+    #[test]
+    fn unknown_semantic_on_stage_argument_produces_an_error() {
+        let result = check("
+            program Test { stage fragment([semantic(\"SV_Color\")] color: vec4) -> vec4 {} }
+        ");
+
+        assert!(result.borrow().has_errors());
+    }
+}
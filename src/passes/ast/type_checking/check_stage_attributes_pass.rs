@@ -0,0 +1,154 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+
+/// Validates the attributes of program stages, e.g. `[workgroup_size(8, 8, 1)]` on compute stages
+/// or `[early_fragment_tests]` on fragment stages
+pub struct CheckStageAttributesPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+}
+
+impl CheckStageAttributesPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckStageAttributesPass {
+        CheckStageAttributesPass {
+            symbol_table: symbol_table,
+            result: result,
+        }
+    }
+
+    fn check_stage(&mut self, stage: &mut ProgramStageDefinition) {
+        for attribute in stage.function.attributes.iter() {
+            let name = &attribute.attribute_name.name;
+            if name == "early_fragment_tests" {
+                if stage.stage_name.name != "fragment" {
+                    result_mut!(self).add_error(Box::new(TypeError::new(attribute.span, ErrorKind::AttributeNotApplicable(name.to_owned(), stage.stage_name.name.to_owned()))));
+                } else if !attribute.arguments.is_empty() {
+                    result_mut!(self).add_error(Box::new(TypeError::new(attribute.span, ErrorKind::InvalidAttributeArguments(name.to_owned()))));
+                } else {
+                    stage.early_fragment_tests = true;
+                }
+                continue;
+            }
+
+            if name != "workgroup_size" || stage.stage_name.name != "compute" {
+                result_mut!(self).add_error(Box::new(TypeError::new(attribute.span, ErrorKind::UnknownAttribute(name.to_owned()))));
+                continue;
+            }
+
+            match workgroup_size(&attribute.arguments) {
+                Some(size) => stage.workgroup_size = Some(size),
+                None => result_mut!(self).add_error(Box::new(TypeError::new(attribute.span, ErrorKind::InvalidWorkgroupSize))),
+            }
+        }
+    }
+}
+
+/// three positive integer literals
+fn workgroup_size(arguments: &[ExpressionStatement]) -> Option<[u32; 3]> {
+    let mut size = [0; 3];
+    if arguments.len() != size.len() {
+        return None;
+    }
+
+    for (dimension, argument) in size.iter_mut().zip(arguments.iter()) {
+        *dimension = match *argument {
+            ExpressionStatement::Literal(ref literal) if literal.literal_expression_type == LiteralType::Int => literal.value.parse::<u32>().ok()?,
+            _ => return None,
+        };
+
+        if *dimension == 0 {
+            return None;
+        }
+    }
+
+    Some(size)
+}
+
+ast_pass_impl!(CheckStageAttributesPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        for item in items.iter_mut() {
+            if let ItemKind::Program(ref mut program) = *item {
+                for stage in program.program_stages.iter_mut() {
+                    self.check_stage(stage);
+                }
+            }
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn check(code: &str) -> (Ast, PassResultReference) {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckStageAttributesPass::new(symbol_table.clone(), result.clone());
+
+        pass.execute(&mut ast);
+
+        (ast, result)
+    }
+
+    fn get_workgroup_size(ast: &Ast) -> Option<[u32; 3]> {
+        match ast[0] {
+            ItemKind::Program(ref p) => p.program_stages[0].workgroup_size,
+            _ => panic!("expected program"),
+        }
+    }
+
+    #[test]
+    fn valid_workgroup_size() {
+        let (ast, result) = check("program Test { [workgroup_size(8, 8, 1)] stage compute() -> void {} }");
+
+        assert!(!result.borrow().has_errors());
+        assert_eq!(get_workgroup_size(&ast), Some([8, 8, 1]));
+    }
+
+    #[test]
+    fn zero_workgroup_size_produces_an_error() {
+        let (ast, result) = check("program Test { [workgroup_size(8, 0, 1)] stage compute() -> void {} }");
+
+        assert!(result.borrow().get_errors().iter().any(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => *e.get_kind() == ErrorKind::InvalidWorkgroupSize,
+            None => false,
+        }));
+        assert_eq!(get_workgroup_size(&ast), None);
+    }
+
+    #[test]
+    fn early_fragment_tests_on_fragment_stage() {
+        let (ast, result) = check("program Test { [early_fragment_tests] stage fragment() -> vec4 {} }");
+
+        assert!(!result.borrow().has_errors());
+        match ast[0] {
+            ItemKind::Program(ref p) => assert!(p.program_stages[0].early_fragment_tests),
+            _ => panic!("expected program"),
+        }
+    }
+
+    #[test]
+    fn early_fragment_tests_on_vertex_stage_produces_an_error() {
+        let (_, result) = check("program Test { [early_fragment_tests] stage vertex() -> vec4 {} }");
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![&ErrorKind::AttributeNotApplicable("early_fragment_tests".to_owned(), "vertex".to_owned())]);
+    }
+
+    #[test]
+    fn workgroup_size_on_vertex_stage_produces_an_error() {
+        let (_, result) = check("program Test { [workgroup_size(8, 8, 1)] stage vertex() -> vec4 {} }");
+
+        assert!(result.borrow().has_errors());
+    }
+}
@@ -0,0 +1,144 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+use ::type_system::builtins;
+
+/// Checks that program stages only reference builtin globals of their own stage, e.g. `frag_coord` only in fragment stages
+/// arguments and locals of the stage shadow builtin globals
+pub struct CheckStageGlobalsPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+    stage_name: String,
+    /// arguments and locals visible at the current statement
+    locals: Vec<String>,
+}
+
+impl CheckStageGlobalsPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckStageGlobalsPass {
+        CheckStageGlobalsPass {
+            symbol_table: symbol_table,
+            result: result,
+            stage_name: String::new(),
+            locals: Vec::new(),
+        }
+    }
+
+    fn check_reference(&mut self, name: &Identifier) {
+        if self.locals.contains(&name.name) {
+            return;
+        }
+
+        match builtins::stage_global_stage(&name.name) {
+            Some(stage) if stage != self.stage_name => {
+                result_mut!(self).add_error(Box::new(TypeError::new(name.span, ErrorKind::GlobalNotAvailableInStage(name.name.to_owned(), self.stage_name.to_owned()))));
+            },
+            _ => (),
+        }
+    }
+}
+
+ast_pass_impl!(CheckStageGlobalsPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        for item in items.iter_mut() {
+            if let ItemKind::Program(ref mut program) = *item {
+                for stage in program.program_stages.iter_mut() {
+                    self.stage_name = stage.stage_name.name.to_owned();
+                    self.locals = stage.function.arguments.iter().map(|a| a.argument_name.name.to_owned()).collect();
+                    self.visit_block(&mut stage.function.block);
+                }
+            }
+        }
+    }
+
+    fn visit_block(&mut self, block: &mut BlockDeclaration) {
+        let local_count = self.locals.len();
+        self.walk_block(block);
+        self.locals.truncate(local_count);
+    }
+
+    fn visit_local_statement(&mut self, local_statement: &mut LocalDeclaration) {
+        self.walk_local_statement(local_statement);
+        self.locals.push(local_statement.symbol_name.name.to_owned());
+    }
+
+    fn visit_const_statement(&mut self, const_statement: &mut LocalConstantDeclaration) {
+        self.walk_const_statement(const_statement);
+        self.locals.push(const_statement.constant_name.name.to_owned());
+    }
+
+    fn visit_assignment_statement(&mut self, assignment_statement: &mut AssignmentStatement) {
+        self.check_reference(&assignment_statement.symbol_name);
+        self.walk_assignment_statement(assignment_statement);
+    }
+
+    fn visit_for_statement(&mut self, for_statement: &mut ForStatement) {
+        self.visit_expression(&mut for_statement.from);
+        self.visit_expression(&mut for_statement.to);
+
+        self.locals.push(for_statement.loop_variable.name.to_owned());
+        self.visit_block(&mut for_statement.block);
+        self.locals.pop();
+    }
+
+    fn visit_variable_expression(&mut self, variable_expression: &mut VariableExpression) {
+        self.check_reference(&variable_expression.variable_name);
+    }
+
+    fn visit_field_accessor_expression(&mut self, field_accessor_expression: &mut FieldAccessorExpression) {
+        self.check_reference(&field_accessor_expression.variable_name);
+    }
+
+    fn visit_index_accessor_expression(&mut self, index_accessor_expression: &mut IndexAccesorExpression) {
+        self.check_reference(&index_accessor_expression.variable_name);
+        self.walk_index_accessor_expression(index_accessor_expression);
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn check(code: &str) -> PassResultReference {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = CheckStageGlobalsPass::new(symbol_table.clone(), result.clone());
+
+        pass.execute(&mut ast);
+
+        result
+    }
+
+    #[test]
+    fn fragment_global_in_fragment_stage() {
+        let result = check("program Test { stage fragment() -> vec4 { return frag_coord; } }");
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn fragment_global_in_vertex_stage_produces_an_error() {
+        let result = check("program Test { stage vertex() -> vec4 { let position = frag_coord.xy; return frag_coord; } }");
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![
+            &ErrorKind::GlobalNotAvailableInStage("frag_coord".to_owned(), "vertex".to_owned()),
+            &ErrorKind::GlobalNotAvailableInStage("frag_coord".to_owned(), "vertex".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn local_shadows_global() {
+        let result = check("program Test { stage vertex(frag_coord: vec4) -> vec4 { return frag_coord; } }");
+
+        assert!(!result.borrow().has_errors());
+    }
+}
@@ -0,0 +1,137 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::type_environment::TypeReference;
+use ::type_system::error::{ TypeError, ErrorKind };
+
+/// Optional pass limiting the number of members per struct, e.g. for targets with attribute limits
+/// structs count their flattened members, including inherited ones and the members of nested structs
+pub struct CheckStructMemberCountPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+    limit: usize,
+}
+
+impl CheckStructMemberCountPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference, limit: usize) -> CheckStructMemberCountPass {
+        CheckStructMemberCountPass {
+            symbol_table: symbol_table,
+            result: result,
+            limit: limit,
+        }
+    }
+
+    /// number of non-struct members of `type_reference` after inlining nested structs
+    /// recursive structs are reported by `CheckRecursiveStructsPass`, their cycles are not followed
+    fn flattened_member_count(&self, type_reference: TypeReference, path: &mut Vec<TypeReference>) -> usize {
+        let member_types: Vec<TypeReference> = match symbol_table!(self).find_type(type_reference).and_then(|t| t.get_member()) {
+            Some(members) => members.iter().map(|m| m.member_type).collect(),
+            None => return 1,
+        };
+
+        if path.contains(&type_reference) {
+            return 0;
+        }
+
+        path.push(type_reference);
+        let count = member_types.into_iter().map(|t| self.flattened_member_count(t, path)).sum();
+        path.pop();
+
+        count
+    }
+}
+
+ast_pass_impl!(CheckStructMemberCountPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        for item in items.iter() {
+            let struct_definition = match *item {
+                ItemKind::Struct(ref s) => s,
+                _ => continue,
+            };
+
+            // structs whose type failed to resolve have errors already
+            let count = match struct_definition.declaring_type {
+                Some(struct_type) => self.flattened_member_count(struct_type, &mut Vec::new()),
+                None => struct_definition.struct_member.len(),
+            };
+
+            if count > self.limit {
+                result_mut!(self).add_error(Box::new(TypeError::new(struct_definition.struct_name.span, ErrorKind::TooManyStructMembers(struct_definition.struct_name.name.to_owned(), count, self.limit))));
+            }
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+    use ::passes::ast::type_checking::discover_structs_pass;
+    use ::passes::ast::type_checking::check_struct_member_pass;
+    use ::passes::ast::type_checking::resolve_struct_inheritance_pass;
+
+    fn check(code: &str, limit: usize) -> PassResultReference {
+        let mut ast = compile_ast(code);
+        let mut symbol_table = SymbolTable::new(TypeEnvironment::new());
+        symbol_table.create_global_type("f32").unwrap();
+        let symbol_table = SymbolTableReference::new(symbol_table);
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(discover_structs_pass::DiscoverStructsPass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_struct_member_pass::CheckStructMemberPass::new(symbol_table.clone(), result.clone())),
+            Box::new(resolve_struct_inheritance_pass::ResolveStructInheritancePass::new(symbol_table.clone(), result.clone())),
+            Box::new(CheckStructMemberCountPass::new(symbol_table.clone(), result.clone(), limit)),
+        ]);
+
+        passes.execute(&mut ast);
+
+        result
+    }
+
+    #[test]
+    fn struct_within_limit() {
+        let result = check("struct Foo { a: f32, b: f32, }", 2);
+
+        assert!(!result.borrow().has_errors());
+    }
+
+    #[test]
+    fn struct_exceeding_limit_produces_an_error() {
+        let result = check("struct Foo { a: f32, b: f32, c: f32, }", 2);
+
+        assert!(result.borrow().has_errors());
+    }
+
+    #[test]
+    fn nested_struct_counts_flattened_members() {
+        let result = check("struct Inner { a: f32, b: f32, } struct Outer { inner: Inner, c: f32, }", 2);
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![&ErrorKind::TooManyStructMembers("Outer".to_owned(), 3, 2)]);
+    }
+
+    #[test]
+    fn inherited_members_are_counted() {
+        let result = check("struct Base { a: f32, b: f32, } struct Derived : Base { c: f32, }", 2);
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![&ErrorKind::TooManyStructMembers("Derived".to_owned(), 3, 2)]);
+    }
+
+    #[test]
+    fn nested_io_struct_counts_flattened_members() {
+        let result = check("struct Base { a: f32, } struct Inner { b: f32, c: f32, } struct Output : Base { inner: Inner, } program Test { stage vertex() -> Output { return Output { a: 1.0, inner: Inner { b: 1.0, c: 1.0, }, }; } }", 2);
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![&ErrorKind::TooManyStructMembers("Output".to_owned(), 3, 2)]);
+    }
+}
@@ -5,6 +5,7 @@ use ::passes::results::PassResultReference;
 use ::type_system::symbol_table::{ SymbolTableReference };
 use ::type_system::type_environment::TypeReference;
 use ::type_system::structure_members::{ StructureMembers, StructureMember };
+use ::type_system::error::{ TypeError, ErrorKind };
 
 pub struct CheckStructMemberPass {
     symbol_table: SymbolTableReference,
@@ -24,6 +25,16 @@ impl CheckStructMemberPass {
 
 ast_pass_impl!(CheckStructMemberPass, {
     fn visit_struct(&mut self, struct_definition: &mut StructDefinition) {
+        // reported before member types are resolved, a later lookup would silently pick the first member
+        let mut member_names: Vec<&str> = Vec::new();
+        for member in struct_definition.struct_member.iter() {
+            let member_name = &member.struct_member_name;
+            if member_names.contains(&member_name.name.as_str()) {
+                result_mut!(self).add_error(Box::new(TypeError::new(member_name.span, ErrorKind::DuplicateStructMember(struct_definition.struct_name.name.to_owned(), member_name.name.to_owned()))));
+            }
+            member_names.push(&member_name.name);
+        }
+
         self.member_list = Some(Vec::new());
 
         self.walk_struct(struct_definition);
@@ -36,11 +47,22 @@ ast_pass_impl!(CheckStructMemberPass, {
     }
 
     fn visit_struct_member(&mut self, struct_member_definition: &mut StructMemberDefinition) {
-        let mut list = self.member_list.take().unwrap();
-        let struct_member_type = pass_try!(self, symbol_table!(self).find_type_ref_or_err(&struct_member_definition.struct_member_type_name.name));
+        // members with unknown types are left out, the remaining members are still checked
+        let type_name = &struct_member_definition.struct_member_type_name;
+        let struct_member_type = match symbol_table!(self).find_type_ref(&type_name.name) {
+            Some(t) => t,
+            None => pass_try!(self, Err(TypeError::new(type_name.get_span(), ErrorKind::TypeNotFound(type_name.name.to_owned())))),
+        };
         struct_member_definition.struct_member_type = Some(struct_member_type);
-        list.push(StructureMember::new(struct_member_definition.struct_member_name.name.clone(), struct_member_type));
-        self.member_list = Some(list);
+
+        if let Some(ref default_value) = struct_member_definition.default_value {
+            if !default_value.is_const() {
+                result_mut!(self).add_error(Box::new(TypeError::new(default_value.get_span(), ErrorKind::NotAConstantExpression)));
+            }
+        }
+        if let Some(ref mut list) = self.member_list {
+            list.push(StructureMember::new(struct_member_definition.struct_member_name.name.clone(), struct_member_type));
+        }
     }
 });
 
@@ -74,4 +96,49 @@ mod tests {
             StructureMember::new("bar".to_string(), TypeReference::new(0)),
         ]));
     }
+
+    fn check(code: &str) -> PassResultReference {
+        let mut ast = compile_ast(code);
+        let mut symbol_table = SymbolTable::new(TypeEnvironment::new());
+        symbol_table.create_global_type("f32").unwrap();
+        let symbol_table = SymbolTableReference::new(symbol_table);
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(discover_structs_pass::DiscoverStructsPass::new(symbol_table.clone(), result.clone())),
+            Box::new(CheckStructMemberPass::new(symbol_table.clone(), result.clone())),
+        ]);
+
+        passes.execute(&mut ast);
+
+        result
+    }
+
+    #[test]
+    fn duplicate_member_produces_an_error() {
+        let result = check("struct S { x: f32, x: f32, }");
+
+        assert!(result.borrow().get_errors().iter().any(|e| match e.downcast_ref::<TypeError>() {
+            Some(e) => *e.get_kind() == ErrorKind::DuplicateStructMember("S".to_owned(), "x".to_owned()),
+            None => false,
+        }));
+    }
+
+    #[test]
+    fn unknown_member_type_reports_its_location() {
+        let result = check("struct S {\n    x: f32,\n    y: Unknown,\n}");
+
+        let result = result.borrow();
+        let error = result.get_errors()[0].downcast_ref::<TypeError>().unwrap();
+        assert_eq!(*error.get_kind(), ErrorKind::TypeNotFound("Unknown".to_owned()));
+        assert_eq!((error.get_span().line, error.get_span().column), (3, 8));
+        assert_eq!(error.to_string(), "3:8: Unknown type \"Unknown\".");
+    }
+
+    #[test]
+    fn structs_sharing_a_member_name() {
+        let result = check("struct S { x: f32, } struct T { x: f32, }");
+
+        assert!(!result.borrow().has_errors());
+    }
 }
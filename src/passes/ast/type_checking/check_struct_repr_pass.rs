@@ -0,0 +1,110 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+use ::type_system::layout::Repr;
+
+/// Validates `[repr(packed)]` and `[repr(align(n))]` on structs and records them on the struct type, so layouts honor them
+/// has to run before layouts are computed, e.g. by `CheckPushConstantsPass`
+pub struct CheckStructReprPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+}
+
+impl CheckStructReprPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> CheckStructReprPass {
+        CheckStructReprPass {
+            symbol_table: symbol_table,
+            result: result,
+        }
+    }
+}
+
+/// `None` for anything but a single `packed` or `align(n)` argument with `n` a power of two
+fn parse_repr(attribute: &AttributeDeclaration) -> Option<Repr> {
+    match attribute.arguments.as_slice() {
+        [ExpressionStatement::Variable(ref variable)] if variable.variable_name.name == "packed" => Some(Repr::Packed),
+        [ExpressionStatement::Call(ref call)] if call.function_name.name == "align" => match call.arguments.as_slice() {
+            [ExpressionStatement::Literal(ref literal)] if literal.literal_expression_type == LiteralType::Int => {
+                literal.value.parse::<usize>().ok().filter(|n| n.is_power_of_two()).map(Repr::Align)
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+ast_pass_impl!(CheckStructReprPass, {
+    fn visit_struct(&mut self, struct_definition: &mut StructDefinition) {
+        let attribute = match struct_definition.attributes.iter().find(|a| a.attribute_name.name == "repr") {
+            Some(attribute) => attribute,
+            None => return,
+        };
+
+        let repr = match parse_repr(attribute) {
+            Some(repr) => repr,
+            None => pass_try!(self, Err(TypeError::new(attribute.span, ErrorKind::InvalidRepr(struct_definition.struct_name.name.to_owned())))),
+        };
+
+        if let Some(struct_type) = struct_definition.declaring_type {
+            if let Some(type_definition) = symbol_table_mut!(self).find_type_mut(struct_type) {
+                type_definition.set_repr(repr);
+            }
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+    use ::type_system::layout::{ layout_of, Layout, LayoutRules };
+    use ::passes::ast::type_checking::check_primitives_pass;
+    use ::passes::ast::type_checking::discover_structs_pass;
+    use ::passes::ast::type_checking::check_struct_member_pass;
+
+    fn check(code: &str) -> (SymbolTableReference, PassResultReference) {
+        let mut ast = compile_ast(code);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(check_primitives_pass::CheckPrimitivesPass::new(symbol_table.clone(), result.clone())),
+            Box::new(discover_structs_pass::DiscoverStructsPass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_struct_member_pass::CheckStructMemberPass::new(symbol_table.clone(), result.clone())),
+            Box::new(CheckStructReprPass::new(symbol_table.clone(), result.clone())),
+        ]);
+
+        passes.execute(&mut ast);
+
+        (symbol_table, result)
+    }
+
+    fn layout(symbol_table: &SymbolTableReference, struct_name: &str) -> Option<Layout> {
+        let symbol_table = symbol_table.borrow();
+        layout_of(&symbol_table, symbol_table.find_type_ref(struct_name).unwrap(), LayoutRules::Std430)
+    }
+
+    #[test]
+    fn align_repr_raises_alignment() {
+        let (symbol_table, result) = check("primitive type f32; struct Plain { a: f32, b: f32, } [repr(align(16))] struct Aligned { a: f32, b: f32, }");
+
+        assert!(!result.borrow().has_errors());
+        assert_eq!(layout(&symbol_table, "Plain"), Some(Layout { size: 8, alignment: 4 }));
+        assert_eq!(layout(&symbol_table, "Aligned"), Some(Layout { size: 16, alignment: 16 }));
+    }
+
+    #[test]
+    fn invalid_repr_produces_an_error() {
+        let (_, result) = check("primitive type f32; [repr(align(12))] struct A { a: f32, } [repr(tight)] struct B { b: f32, }");
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![&ErrorKind::InvalidRepr("A".to_owned()), &ErrorKind::InvalidRepr("B".to_owned())]);
+    }
+}
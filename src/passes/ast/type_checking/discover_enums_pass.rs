@@ -0,0 +1,37 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+
+ast_pass!(DiscoverEnumsPass, {
+    fn visit_enum(&mut self, enum_definition: &mut EnumDefinition) {
+        let type_ref = pass_try!(self, symbol_table_mut!(self).create_type(&enum_definition.enum_name.name));
+        let variants = enum_definition.variants.iter().map(|v| v.name.to_owned()).collect();
+        pass_try!(self, symbol_table_mut!(self).find_type_mut_or_err(type_ref)).set_variants(variants);
+        enum_definition.declaring_type = Some(type_ref);
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    #[test]
+    fn it_works() {
+        let mut ast = compile_ast("enum BlendMode { Add, Multiply, }");
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        let mut pass = DiscoverEnumsPass::new(symbol_table.clone(), result.clone());
+
+        pass.execute(&mut ast);
+
+        let symbol_table = symbol_table.borrow();
+        let enum_type = symbol_table.find_type_by_name("BlendMode").unwrap();
+        assert_eq!(enum_type.get_variants(), Some(&["Add".to_owned(), "Multiply".to_owned()][..]));
+    }
+}
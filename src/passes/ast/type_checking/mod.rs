@@ -1,28 +1,160 @@
+use ::std::rc::Rc;
 use ::ast::Ast;
 use ::passes::{ Pass, PassCollection };
-use ::passes::results::PassResultReference;
+use ::passes::results::{ PassResultReference, LintLevel };
 use ::type_system::symbol_table::SymbolTableReference;
+use ::type_system::layout::LayoutRules;
 
 mod check_primitives_pass;
+mod check_casts_pass;
 mod discover_structs_pass;
+mod discover_enums_pass;
 mod check_struct_member_pass;
+mod resolve_struct_inheritance_pass;
+mod check_struct_member_count_pass;
+mod check_recursive_structs_pass;
+mod check_struct_repr_pass;
+mod check_push_constants_pass;
 mod check_exports_pass;
+mod check_function_attributes_pass;
 mod check_function_signatures_pass;
+mod check_const_functions_pass;
+mod check_operators_pass;
+mod check_function_bodies_pass;
+mod check_function_complexity_pass;
+mod check_escaping_locals_pass;
+mod check_dead_stores_pass;
+mod check_output_locations_pass;
+mod check_interpolation_pass;
+mod check_stage_attributes_pass;
+mod check_stage_globals_pass;
+mod check_semantics_pass;
+mod check_resolved_types_pass;
+mod report_constant_types_pass;
+
+pub use self::check_primitives_pass::PrimitiveValidator;
+
+/// Report levels of the lints performed while type checking
+#[derive(Debug, Copy, Clone)]
+pub struct LintOptions {
+    /// `==` and `!=` on floating point operands
+    pub float_equality: LintLevel,
+    /// integer `/` whose result is implicitly cast to a floating point type
+    pub integer_division_in_float_context: LintLevel,
+    /// explicit casts to the type the expression already has, e.g. `f32(x)` with `x: f32`
+    pub redundant_cast: LintLevel,
+}
+
+impl LintOptions {
+    pub fn new() -> LintOptions {
+        LintOptions {
+            float_equality: LintLevel::Warning,
+            integer_division_in_float_context: LintLevel::Warning,
+            redundant_cast: LintLevel::Warning,
+        }
+    }
+}
+
+impl Default for LintOptions {
+    fn default() -> LintOptions {
+        LintOptions::new()
+    }
+}
+
+/// Configures optional behaviour of the `TypeChecker`
+/// the `Compiler` checks every module with the same options
+#[derive(Clone)]
+pub struct TypeCheckerOptions {
+    pub lints: LintOptions,
+    pub primitive_validator: Option<Rc<PrimitiveValidator>>,
+    pub max_struct_members: Option<usize>,
+    /// number of expression nodes a function body may contain
+    pub max_function_expressions: Option<usize>,
+    /// verify all type slots in the AST got resolved, enabled in debug builds by default
+    pub verify_resolved_types: bool,
+    /// layout `sizeof` and `alignof` are folded with, std140 by default
+    pub layout_rules: LayoutRules,
+    /// bytes available to structs marked `[push_constant]`, 128 by default
+    pub push_constant_limit: usize,
+    /// record the type and folded value of every top-level constant as info, e.g. for build logs
+    pub report_constant_types: bool,
+    /// type check the bodies of functions, disabled for interface modules whose functions have no bodies
+    pub check_function_bodies: bool,
+}
+
+impl TypeCheckerOptions {
+    pub fn new() -> TypeCheckerOptions {
+        TypeCheckerOptions {
+            lints: LintOptions::new(),
+            primitive_validator: None,
+            max_struct_members: None,
+            max_function_expressions: None,
+            verify_resolved_types: cfg!(debug_assertions),
+            layout_rules: LayoutRules::Std140,
+            push_constant_limit: 128,
+            report_constant_types: false,
+            check_function_bodies: true,
+        }
+    }
+}
+
+impl Default for TypeCheckerOptions {
+    fn default() -> TypeCheckerOptions {
+        TypeCheckerOptions::new()
+    }
+}
 
 pub struct TypeChecker {
     passes: PassCollection<Ast>,
 }
 
 impl TypeChecker {
-    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> TypeChecker {
+    pub fn with_options(symbol_table: SymbolTableReference, result: PassResultReference, is_core_module: bool, options: TypeCheckerOptions) -> TypeChecker {
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(check_primitives_pass::CheckPrimitivesPass::with_validator(symbol_table.clone(), result.clone(), options.primitive_validator)),
+            Box::new(check_casts_pass::CheckCastsPass::new(symbol_table.clone(), result.clone(), is_core_module)),
+            Box::new(discover_structs_pass::DiscoverStructsPass::new(symbol_table.clone(), result.clone())),
+            Box::new(discover_enums_pass::DiscoverEnumsPass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_struct_member_pass::CheckStructMemberPass::new(symbol_table.clone(), result.clone())),
+            Box::new(resolve_struct_inheritance_pass::ResolveStructInheritancePass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_recursive_structs_pass::CheckRecursiveStructsPass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_struct_repr_pass::CheckStructReprPass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_push_constants_pass::CheckPushConstantsPass::new(symbol_table.clone(), result.clone(), options.push_constant_limit)),
+        ]);
+
+        if let Some(limit) = options.max_struct_members {
+            passes.add_pass(Box::new(check_struct_member_count_pass::CheckStructMemberCountPass::new(symbol_table.clone(), result.clone(), limit)));
+        }
+
+        passes.add_pass(Box::new(check_function_attributes_pass::CheckFunctionAttributesPass::new(symbol_table.clone(), result.clone())));
+        passes.add_pass(Box::new(check_function_signatures_pass::CheckFunctionSignaturePass::new(symbol_table.clone(), result.clone())));
+        passes.add_pass(Box::new(check_const_functions_pass::CheckConstFunctionsPass::new(symbol_table.clone(), result.clone())));
+        passes.add_pass(Box::new(check_operators_pass::CheckOperatorsPass::new(symbol_table.clone(), result.clone(), is_core_module)));
+        if options.check_function_bodies {
+            passes.add_pass(Box::new(check_function_bodies_pass::CheckFunctionBodiesPass::with_options(symbol_table.clone(), result.clone(), options.lints, options.layout_rules)));
+        }
+        if options.report_constant_types {
+            passes.add_pass(Box::new(report_constant_types_pass::ReportConstantTypesPass::new(symbol_table.clone(), result.clone())));
+        }
+        if let Some(limit) = options.max_function_expressions {
+            passes.add_pass(Box::new(check_function_complexity_pass::CheckFunctionComplexityPass::new(symbol_table.clone(), result.clone(), limit)));
+        }
+
+        passes.add_pass(Box::new(check_escaping_locals_pass::CheckEscapingLocalsPass::new(symbol_table.clone(), result.clone())));
+        passes.add_pass(Box::new(check_dead_stores_pass::CheckDeadStoresPass::new(symbol_table.clone(), result.clone())));
+        passes.add_pass(Box::new(check_output_locations_pass::CheckOutputLocationsPass::new(symbol_table.clone(), result.clone())));
+        passes.add_pass(Box::new(check_interpolation_pass::CheckInterpolationPass::new(symbol_table.clone(), result.clone())));
+        passes.add_pass(Box::new(check_stage_attributes_pass::CheckStageAttributesPass::new(symbol_table.clone(), result.clone())));
+        passes.add_pass(Box::new(check_stage_globals_pass::CheckStageGlobalsPass::new(symbol_table.clone(), result.clone())));
+        passes.add_pass(Box::new(check_semantics_pass::CheckSemanticsPass::new(symbol_table.clone(), result.clone())));
+        passes.add_pass(Box::new(check_exports_pass::CheckExportsPass::new(symbol_table.clone(), result.clone())));
+
+        if options.verify_resolved_types {
+            passes.add_pass(Box::new(check_resolved_types_pass::CheckResolvedTypesPass::new(symbol_table.clone(), result.clone())));
+        }
+
         TypeChecker {
-            passes: PassCollection::from_passes(vec![
-                Box::new(check_primitives_pass::CheckPrimitivesPass::new(symbol_table.clone(), result.clone())),
-                Box::new(discover_structs_pass::DiscoverStructsPass::new(symbol_table.clone(), result.clone())),
-                Box::new(check_struct_member_pass::CheckStructMemberPass::new(symbol_table.clone(), result.clone())),
-                Box::new(check_function_signatures_pass::CheckFunctionSignaturePass::new(symbol_table.clone(), result.clone())),
-                Box::new(check_exports_pass::CheckExportsPass::new(symbol_table.clone(), result.clone())),
-            ]),
+            passes: passes,
         }
     }
 }
@@ -0,0 +1,103 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::error::{ TypeError, ErrorKind };
+use ::type_system::const_eval;
+
+/// Optional pass recording the resolved type and folded value of every top-level constant as info, e.g. for build logs
+/// has to run after the function bodies are checked, which folds layout queries in initializers
+/// constants without initializer are set by the application, only their type is reported
+pub struct ReportConstantTypesPass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+}
+
+impl ReportConstantTypesPass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> ReportConstantTypesPass {
+        ReportConstantTypesPass {
+            symbol_table: symbol_table,
+            result: result,
+        }
+    }
+}
+
+ast_pass_impl!(ReportConstantTypesPass, {
+    fn visit(&mut self, items: &mut Ast) {
+        let values = const_eval::evaluate_constants(items);
+        for item in items.iter() {
+            let constant = match *item {
+                ItemKind::Constant(ref constant) => constant,
+                _ => continue,
+            };
+
+            // unknown types are left to the passes checking declarations
+            let type_name = {
+                let symbol_table = symbol_table!(self);
+                match symbol_table.find_type_ref(&constant.constant_type_name.name).and_then(|t| symbol_table.find_type(t)) {
+                    Some(t) => t.get_name().to_owned(),
+                    None => continue,
+                }
+            };
+
+            let info = TypeError::new(constant.span, ErrorKind::ConstantType(constant.constant_name.name.to_owned(), type_name, values.get(&constant.constant_name.name).cloned()));
+            result_mut!(self).add_info(Box::new(info));
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+    use ::passes::ast::type_checking::check_primitives_pass;
+    use ::type_system::const_eval::ConstValue;
+
+    #[test]
+    fn constant_types_are_reported() {
+        let mut ast = compile_ast("primitive type f32; primitive type i32; const scale: f32; const count: i32;");
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(check_primitives_pass::CheckPrimitivesPass::new(symbol_table.clone(), result.clone())),
+            Box::new(ReportConstantTypesPass::new(symbol_table.clone(), result.clone())),
+        ]);
+
+        passes.execute(&mut ast);
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_infos().iter().filter_map(|i| i.downcast_ref::<TypeError>()).map(|i| i.get_kind()).collect();
+        assert_eq!(kinds, vec![
+            &ErrorKind::ConstantType("scale".to_owned(), "f32".to_owned(), None),
+            &ErrorKind::ConstantType("count".to_owned(), "i32".to_owned(), None),
+        ]);
+        assert!(!result.has_errors());
+    }
+
+    #[test]
+    fn folded_constant_values_are_reported() {
+        let mut ast = compile_ast("primitive type f32; primitive type i32; const scale: f32 = 0.5; const count: i32 = 2 * (3 + 4); const doubled: i32 = count * 2;");
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(check_primitives_pass::CheckPrimitivesPass::new(symbol_table.clone(), result.clone())),
+            Box::new(ReportConstantTypesPass::new(symbol_table.clone(), result.clone())),
+        ]);
+
+        passes.execute(&mut ast);
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_infos().iter().filter_map(|i| i.downcast_ref::<TypeError>()).map(|i| i.get_kind()).collect();
+        assert_eq!(kinds, vec![
+            &ErrorKind::ConstantType("scale".to_owned(), "f32".to_owned(), Some(ConstValue::Float(0.5))),
+            &ErrorKind::ConstantType("count".to_owned(), "i32".to_owned(), Some(ConstValue::Int(14))),
+            &ErrorKind::ConstantType("doubled".to_owned(), "i32".to_owned(), Some(ConstValue::Int(28))),
+        ]);
+    }
+}
@@ -0,0 +1,204 @@
+use ::ast::*;
+use ::passes::*;
+use ::passes::ast::*;
+use ::passes::results::PassResultReference;
+use ::type_system::symbol_table::{ SymbolTableReference };
+use ::type_system::type_environment::TypeReference;
+use ::type_system::structure_members::{ StructureMembers, StructureMember };
+use ::type_system::error::{ TypeError, ErrorKind };
+
+/// Flattens structs declaring a base, e.g. `struct Derived : Base`, so their members are the base's followed by their own
+/// has to run after the own members of all structs are resolved, as bases may be declared after the structs using them
+pub struct ResolveStructInheritancePass {
+    symbol_table: SymbolTableReference,
+    result: PassResultReference,
+}
+
+impl ResolveStructInheritancePass {
+    pub fn new(symbol_table: SymbolTableReference, result: PassResultReference) -> ResolveStructInheritancePass {
+        ResolveStructInheritancePass {
+            symbol_table: symbol_table,
+            result: result,
+        }
+    }
+
+    fn resolve_base(&mut self, struct_definition: &mut StructDefinition) {
+        let base_type = match struct_definition.base_struct_name {
+            Some(ref base_name) => match symbol_table!(self).find_type_ref(&base_name.name) {
+                Some(base_type) => base_type,
+                None => pass_try!(self, Err(TypeError::new(base_name.span, ErrorKind::TypeNotFound(base_name.name.to_owned())))),
+            },
+            None => return,
+        };
+
+        if !symbol_table!(self).find_type(base_type).is_some_and(|t| t.is_struct()) {
+            let base_name = struct_definition.base_struct_name.as_ref().unwrap();
+            pass_try!(self, Err(TypeError::new(base_name.span, ErrorKind::InvalidBaseStruct(base_name.name.to_owned()))));
+        }
+
+        struct_definition.base_struct_type = Some(base_type);
+    }
+
+    /// flattens the bases of the struct first, returns false if the base chain is cyclic
+    /// cycles are left unflattened, `CheckRecursiveStructsPass` reports them
+    fn flatten(&mut self, struct_definition: &StructDefinition, structs: &[&StructDefinition], resolved: &mut Vec<TypeReference>, path: &mut Vec<TypeReference>) -> bool {
+        let (struct_type, base_type) = match (struct_definition.declaring_type, struct_definition.base_struct_type) {
+            (Some(struct_type), Some(base_type)) => (struct_type, base_type),
+            _ => return true,
+        };
+
+        if resolved.contains(&struct_type) {
+            return true;
+        }
+
+        if path.contains(&struct_type) {
+            return false;
+        }
+
+        path.push(struct_type);
+        let base_flattened = match structs.iter().find(|s| s.declaring_type == Some(base_type)) {
+            Some(base) => self.flatten(base, structs, resolved, path),
+            // bases of other modules were flattened when their module was checked
+            None => true,
+        };
+        path.pop();
+
+        if !base_flattened {
+            return false;
+        }
+
+        resolved.push(struct_type);
+
+        let base_name = &struct_definition.base_struct_name.as_ref().unwrap().name;
+        let mut members = self.member_list(base_type);
+        for member in self.member_list(struct_type) {
+            if members.iter().any(|m| m.member_name == member.member_name) {
+                let span = struct_definition.struct_member.iter()
+                    .find(|m| m.struct_member_name.name == member.member_name)
+                    .map_or(struct_definition.span, |m| m.struct_member_name.span);
+                result_mut!(self).add_error(Box::new(TypeError::new(span, ErrorKind::InheritedMemberCollision(struct_definition.struct_name.name.to_owned(), base_name.to_owned(), member.member_name))));
+                continue;
+            }
+            members.push(member);
+        }
+
+        if let Some(struct_type) = symbol_table_mut!(self).find_type_mut(struct_type) {
+            if let Err(error) = struct_type.set_members(StructureMembers::new(members)) {
+                result_mut!(self).add_error(Box::new(error));
+            }
+        }
+
+        true
+    }
+
+    fn member_list(&self, type_reference: TypeReference) -> Vec<StructureMember> {
+        match symbol_table!(self).find_type(type_reference).and_then(|t| t.get_member()) {
+            Some(members) => members.iter().map(|m| StructureMember::new(m.member_name.to_owned(), m.member_type)).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+ast_pass_impl!(ResolveStructInheritancePass, {
+    fn visit(&mut self, items: &mut Ast) {
+        for item in items.iter_mut() {
+            if let ItemKind::Struct(ref mut s) = *item {
+                self.resolve_base(s);
+            }
+        }
+
+        let structs: Vec<&StructDefinition> = items.iter().filter_map(|item| match *item {
+            ItemKind::Struct(ref s) => Some(s),
+            _ => None,
+        }).collect();
+
+        let mut resolved = Vec::new();
+        for s in structs.iter() {
+            self.flatten(s, &structs, &mut resolved, &mut Vec::new());
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::PassResult;
+    use ::type_system::symbol_table::SymbolTable;
+    use ::type_system::type_environment::TypeEnvironment;
+    use ::type_system::layout::{ layout_of, LayoutRules };
+    use ::passes::ast::type_checking::discover_structs_pass;
+    use ::passes::ast::type_checking::check_struct_member_pass;
+    use ::passes::ast::type_checking::check_recursive_structs_pass;
+
+    fn check(code: &str) -> (SymbolTableReference, PassResultReference) {
+        let mut ast = compile_ast(code);
+        let mut symbol_table = SymbolTable::new(TypeEnvironment::new());
+        symbol_table.create_global_type("f32").unwrap();
+        let symbol_table = SymbolTableReference::new(symbol_table);
+        let result = PassResultReference::new(PassResult::new());
+
+        let mut passes = PassCollection::from_passes(vec![
+            Box::new(discover_structs_pass::DiscoverStructsPass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_struct_member_pass::CheckStructMemberPass::new(symbol_table.clone(), result.clone())),
+            Box::new(ResolveStructInheritancePass::new(symbol_table.clone(), result.clone())),
+            Box::new(check_recursive_structs_pass::CheckRecursiveStructsPass::new(symbol_table.clone(), result.clone())),
+        ]);
+
+        passes.execute(&mut ast);
+
+        (symbol_table, result)
+    }
+
+    fn member_names(symbol_table: &SymbolTableReference, struct_name: &str) -> Vec<String> {
+        symbol_table.borrow().find_type_by_name(struct_name).unwrap().get_member().unwrap().iter().map(|m| m.member_name.to_owned()).collect()
+    }
+
+    #[test]
+    fn base_members_come_first() {
+        let (symbol_table, result) = check("struct Derived : Base { extra: f32, } struct Base { a: f32, b: f32, }");
+
+        assert!(!result.borrow().has_errors());
+        assert_eq!(member_names(&symbol_table, "Derived"), vec!["a".to_owned(), "b".to_owned(), "extra".to_owned()]);
+        assert_eq!(member_names(&symbol_table, "Base"), vec!["a".to_owned(), "b".to_owned()]);
+
+        let symbol_table = symbol_table.borrow();
+        let derived = symbol_table.find_type_ref("Derived").unwrap();
+        assert_eq!(layout_of(&symbol_table, derived, LayoutRules::Std430).unwrap().size, 12);
+    }
+
+    #[test]
+    fn chained_bases() {
+        let (symbol_table, result) = check("struct A { a: f32, } struct B : A { b: f32, } struct C : B { c: f32, }");
+
+        assert!(!result.borrow().has_errors());
+        assert_eq!(member_names(&symbol_table, "C"), vec!["a".to_owned(), "b".to_owned(), "c".to_owned()]);
+    }
+
+    #[test]
+    fn colliding_member_produces_an_error() {
+        let (_, result) = check("struct Base { a: f32, } struct Derived : Base { a: f32, }");
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![&ErrorKind::InheritedMemberCollision("Derived".to_owned(), "Base".to_owned(), "a".to_owned())]);
+    }
+
+    #[test]
+    fn non_struct_base_produces_an_error() {
+        let (_, result) = check("struct Derived : f32 { a: f32, }");
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![&ErrorKind::InvalidBaseStruct("f32".to_owned())]);
+    }
+
+    #[test]
+    fn cyclic_bases_produce_an_error() {
+        let (_, result) = check("struct A : B { a: f32, } struct B : A { b: f32, }");
+
+        let result = result.borrow();
+        let kinds: Vec<&ErrorKind> = result.get_errors().iter().filter_map(|e| e.downcast_ref::<TypeError>()).map(|e| e.get_kind()).collect();
+        assert_eq!(kinds, vec![&ErrorKind::RecursiveStruct(vec!["A".to_owned(), "B".to_owned(), "A".to_owned()])]);
+    }
+}
@@ -28,10 +28,10 @@ macro_rules! pass_try_fatal {
 
 // produces a warning
 // expects `self` to have a `result` field of type `::passes::results::PassResultReference`
-// usage: `pass_warning!(self, "some warning")`
+// usage: `pass_warning!(self, "some warning")` or `pass_warning!(self, some_error)`
 macro_rules! pass_warning {
     ($s:expr, $w:expr) => (
-        $s.result.borrow_mut().add_warning($w);
+        $s.result.borrow_mut().add_warning(::std::convert::From::from($w));
     );
 }
 
@@ -12,15 +12,27 @@ pub enum PassError {
     Fatal,
 }
 
+/// How a lint reports its findings
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LintLevel {
+    Allow,
+    Warning,
+    Error,
+}
+
 #[derive(Debug)]
 pub struct PassResult {
     errors: Vec<Box<Error>>,
+    warnings: Vec<Box<Error>>,
+    infos: Vec<Box<Error>>,
 }
 
 impl PassResult {
     pub fn new() -> PassResult {
         PassResult {
             errors: Vec::new(),
+            warnings: Vec::new(),
+            infos: Vec::new(),
         }
     }
 
@@ -28,6 +40,35 @@ impl PassResult {
         self.errors.len() > 0
     }
 
+    pub fn get_errors(&self) -> &Vec<Box<Error>> {
+        &self.errors
+    }
+
+    pub fn take_errors(&mut self) -> Vec<Box<Error>> {
+        ::std::mem::take(&mut self.errors)
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        self.warnings.len() > 0
+    }
+
+    pub fn get_warnings(&self) -> &Vec<Box<Error>> {
+        &self.warnings
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<Box<Error>> {
+        ::std::mem::take(&mut self.warnings)
+    }
+
+    /// informational entries that never fail a compilation
+    pub fn get_infos(&self) -> &Vec<Box<Error>> {
+        &self.infos
+    }
+
+    pub fn take_infos(&mut self) -> Vec<Box<Error>> {
+        ::std::mem::take(&mut self.infos)
+    }
+
     pub fn add_error(&mut self, error: Box<Error>) {
         self.errors.push(error);
     }
@@ -37,7 +78,19 @@ impl PassResult {
         self.errors.push(error);
     }
 
-    pub fn add_warning(&mut self, warning: &str) {
-        // TODO
+    pub fn add_warning(&mut self, warning: Box<Error>) {
+        self.warnings.push(warning);
+    }
+
+    pub fn add_info(&mut self, info: Box<Error>) {
+        self.infos.push(info);
+    }
+
+    pub fn add_lint(&mut self, level: LintLevel, lint: Box<Error>) {
+        match level {
+            LintLevel::Allow => (),
+            LintLevel::Warning => self.add_warning(lint),
+            LintLevel::Error => self.add_error(lint),
+        }
     }
 }
@@ -0,0 +1,218 @@
+use ::ast::*;
+use ::string_builder::StringBuilder;
+use ::parser::PrecedenceTable;
+
+/// Prints an expression as source code
+/// parentheses are inserted where the precedences the expression was parsed with require them
+pub fn print_expression(expression: &ExpressionStatement, precedences: &PrecedenceTable) -> String {
+    let mut sb = StringBuilder::new(64);
+    append_expression(&mut sb, expression, precedences);
+    sb.to_string().unwrap()
+}
+
+fn append_expression(sb: &mut StringBuilder, expression: &ExpressionStatement, precedences: &PrecedenceTable) {
+    match *expression {
+        ExpressionStatement::Infix(ref e) => {
+            append_operand(sb, &e.left_hand, e.operator, false, precedences);
+            sb.append(" ");
+            sb.append(e.operator.get_symbol());
+            sb.append(" ");
+            append_operand(sb, &e.right_hand, e.operator, true, precedences);
+        },
+        ExpressionStatement::Prefix(ref e) => {
+            sb.append(e.operator.get_symbol());
+            match *e.expression {
+                ExpressionStatement::Infix(_) | ExpressionStatement::Prefix(_) | ExpressionStatement::Ternary(_) => {
+                    sb.append("(");
+                    append_expression(sb, &e.expression, precedences);
+                    sb.append(")");
+                },
+                _ => append_expression(sb, &e.expression, precedences),
+            }
+        },
+        ExpressionStatement::Literal(ref e) => match e.literal_expression_type {
+            LiteralType::String => {
+                sb.append("\"");
+                sb.append(&e.value);
+                sb.append("\"");
+            },
+            _ => sb.append(&e.value),
+        },
+        ExpressionStatement::Call(ref e) => {
+            sb.append(&e.function_name.name);
+            if !e.type_arguments.is_empty() {
+                let type_arguments: Vec<&str> = e.type_arguments.iter().map(|t| t.name.as_str()).collect();
+                sb.append("<");
+                sb.append(&type_arguments.join(", "));
+                sb.append(">");
+            }
+            sb.append("(");
+            for (i, argument) in e.arguments.iter().enumerate() {
+                if i > 0 {
+                    sb.append(", ");
+                }
+                append_expression(sb, argument, precedences);
+            }
+            sb.append(")");
+        },
+        ExpressionStatement::StructInstantiation(ref e) if e.uses_defaults => {
+            sb.append(&e.struct_type_name.name);
+            sb.append("::default()");
+        },
+        ExpressionStatement::StructInstantiation(ref e) => {
+            sb.append(&e.struct_type_name.name);
+            sb.append(" {");
+            for (i, field) in e.struct_field_initializer.iter().enumerate() {
+                sb.append(if i > 0 { ", " } else { " " });
+                sb.append(&field.struct_field_name.name);
+                sb.append(": ");
+                append_expression(sb, &field.initializer, precedences);
+            }
+            if let Some(ref base) = e.base {
+                sb.append(if e.struct_field_initializer.is_empty() { " .." } else { ", .." });
+                append_expression(sb, base, precedences);
+            }
+            sb.append(if e.struct_field_initializer.is_empty() && e.base.is_none() { "}" } else { " }" });
+        },
+        ExpressionStatement::FieldAccessor(ref e) => {
+            sb.append(&e.variable_name.name);
+            sb.append(".");
+            sb.append(&e.field_name.name);
+        },
+        ExpressionStatement::IndexAccessor(ref e) => {
+            sb.append(&e.variable_name.name);
+            sb.append("[");
+            append_expression(sb, &e.access_expression, precedences);
+            sb.append("]");
+        },
+        ExpressionStatement::Variable(ref e) => sb.append(&e.variable_name.name),
+        ExpressionStatement::Cast(ref e) => match e.cast_target_type_name {
+            Some(ref target_type_name) => {
+                match *e.expression {
+                    ExpressionStatement::Infix(_) | ExpressionStatement::Ternary(_) => {
+                        sb.append("(");
+                        append_expression(sb, &e.expression, precedences);
+                        sb.append(")");
+                    },
+                    _ => append_expression(sb, &e.expression, precedences),
+                }
+                sb.append(" as ");
+                sb.append(&target_type_name.name);
+            },
+            // implicit casts are inserted by the type checker and have no syntax
+            None => append_expression(sb, &e.expression, precedences),
+        },
+        ExpressionStatement::EnumVariant(ref e) => {
+            sb.append(&e.enum_name.name);
+            sb.append("::");
+            sb.append(&e.variant_name.name);
+        },
+        // right associative, only a ternary condition needs grouping
+        ExpressionStatement::Ternary(ref e) => {
+            match *e.condition {
+                ExpressionStatement::Ternary(_) => {
+                    sb.append("(");
+                    append_expression(sb, &e.condition, precedences);
+                    sb.append(")");
+                },
+                _ => append_expression(sb, &e.condition, precedences),
+            }
+            sb.append(" ? ");
+            append_expression(sb, &e.true_expression, precedences);
+            sb.append(" : ");
+            append_expression(sb, &e.false_expression, precedences);
+        },
+    }
+}
+
+fn append_operand(sb: &mut StringBuilder, operand: &ExpressionStatement, parent: Operator, is_right_hand: bool, precedences: &PrecedenceTable) {
+    let needs_parentheses = match *operand {
+        // grouping is needed where the parser would otherwise apply the operators in the other order
+        ExpressionStatement::Infix(ref e) if is_right_hand => precedences.binds_before(parent, e.operator),
+        ExpressionStatement::Infix(ref e) => !precedences.binds_before(e.operator, parent),
+        ExpressionStatement::Ternary(_) => true,
+        _ => false,
+    };
+
+    if needs_parentheses {
+        sb.append("(");
+        append_expression(sb, operand, precedences);
+        sb.append(")");
+    } else {
+        append_expression(sb, operand, precedences);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::parser::{ parse_str, parse_str_with_precedences };
+
+    fn round_trip(expression: &str) -> String {
+        round_trip_with(expression, "")
+    }
+
+    /// `declarations` are operator precedence declarations the expression is parsed and printed with
+    fn round_trip_with(expression: &str, declarations: &str) -> String {
+        let declarations = parse_str(declarations).unwrap();
+        let declarations: Vec<&OperatorPrecedenceDeclaration> = declarations.iter().filter_map(|item| match *item {
+            ItemKind::OperatorPrecedence(ref p) => Some(p),
+            _ => None,
+        }).collect();
+        let precedences = PrecedenceTable::from_declarations(&declarations).unwrap();
+
+        let code = format!("fn main() -> i32 {{ return {}; }}", expression);
+        let ast = parse_str_with_precedences(&code, &precedences).unwrap();
+        match ast[0] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => print_expression(&r.expression, &precedences),
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn parentheses_are_preserved() {
+        assert_eq!(round_trip("(1 + 2) * 3"), "(1 + 2) * 3");
+        assert_eq!(round_trip("1 - (2 - 3)"), "1 - (2 - 3)");
+    }
+
+    #[test]
+    fn redundant_parentheses_are_dropped() {
+        assert_eq!(round_trip("1 + (2 * 3)"), "1 + 2 * 3");
+        assert_eq!(round_trip("(1 - 2) - 3"), "1 - 2 - 3");
+    }
+
+    #[test]
+    fn declared_precedences_are_respected() {
+        let right_minus = "operator - precedence 1 right;";
+        assert_eq!(round_trip_with("(1 - 2) - 3", right_minus), "(1 - 2) - 3");
+        assert_eq!(round_trip_with("1 - (2 - 3)", right_minus), "1 - 2 - 3");
+        assert_eq!(round_trip_with("1 + (2 - 3)", right_minus), "1 + 2 - 3");
+        assert_eq!(round_trip_with("(1 - 2) + 3", right_minus), "1 - 2 + 3");
+        assert_eq!(round_trip_with("(1 + 2) - 3", right_minus), "(1 + 2) - 3");
+
+        let loose_multiply = "operator * precedence 1 left;";
+        assert_eq!(round_trip_with("(1 + 2) * 3", loose_multiply), "1 + 2 * 3");
+        assert_eq!(round_trip_with("1 + (2 * 3)", loose_multiply), "1 + (2 * 3)");
+    }
+
+    #[test]
+    fn print_other_expressions() {
+        assert_eq!(round_trip("Foo { a: b.c, d: f(1, 2.5) }"), "Foo { a: b.c, d: f(1, 2.5) }");
+        assert_eq!(round_trip("Foo { a: 1, ..b }"), "Foo { a: 1, ..b }");
+        assert_eq!(round_trip("bitcast<u32>(a) >= 1"), "bitcast<u32>(a) >= 1");
+        assert_eq!(round_trip("v[i + 1] * 2.0"), "v[i + 1] * 2.0");
+        assert_eq!(round_trip("-a * -(b + 1)"), "-a * -(b + 1)");
+        assert_eq!(round_trip("trueColor != false"), "trueColor != false");
+        assert_eq!(round_trip("(a + 1) as i32 * -b as i32"), "(a + 1) as i32 * -b as i32");
+    }
+
+    #[test]
+    fn print_ternary_expressions() {
+        assert_eq!(round_trip("a > b ? a - b : c ? 1 : 2"), "a > b ? a - b : c ? 1 : 2");
+        assert_eq!(round_trip("(a ? b : c) ? 1 : 2"), "(a ? b : c) ? 1 : 2");
+        assert_eq!(round_trip("1 + (a ? 2 : 3)"), "1 + (a ? 2 : 3)");
+    }
+}
@@ -0,0 +1,126 @@
+use ::std::collections::HashMap;
+use ::ast::*;
+use ::module::Module;
+use ::passes::ast::AstWalker;
+
+/// Declarations of `module` named like one of the `reserved` words of a target language, and the name to emit them as
+/// the new name appends `_` until it is neither reserved nor declared, so it is the same for every compilation of the module
+/// backends rename every declaration and use of a renamed name, applications use the map to find e.g. renamed constants
+pub fn rename_reserved(module: &Module, reserved: &[&str]) -> HashMap<String, String> {
+    let declared = declared_names(module);
+    let mut renames = HashMap::new();
+    for name in declared.iter().filter(|name| reserved.contains(&name.as_str())) {
+        let mut renamed = format!("{}_", name);
+        while reserved.contains(&renamed.as_str()) || declared.contains(&renamed) {
+            renamed.push('_');
+        }
+        renames.insert(name.to_string(), renamed);
+    }
+    renames
+}
+
+/// names of the structs, struct members, constants, functions, arguments and locals backends emit
+fn declared_names(module: &Module) -> Vec<String> {
+    let mut collector = DeclaredNames {
+        names: Vec::new(),
+    };
+    collector.visit(&mut module.get_ast().to_vec());
+    collector.names
+}
+
+struct DeclaredNames {
+    names: Vec<String>,
+}
+
+impl AstWalker for DeclaredNames {
+    // operators, casts and program stages are not emitted by name
+    fn visit(&mut self, items: &mut Ast) {
+        for item in items.iter_mut() {
+            match *item {
+                ItemKind::Struct(ref mut s) => self.visit_struct(s),
+                ItemKind::Constant(ref mut c) => self.visit_constant(c),
+                ItemKind::Function(ref mut f) => self.visit_function(f),
+                _ => (),
+            }
+        }
+    }
+
+    fn visit_struct(&mut self, struct_definition: &mut StructDefinition) {
+        self.names.push(struct_definition.struct_name.name.to_owned());
+        self.walk_struct(struct_definition);
+    }
+
+    fn visit_struct_member(&mut self, struct_member_definition: &mut StructMemberDefinition) {
+        self.names.push(struct_member_definition.struct_member_name.name.to_owned());
+    }
+
+    fn visit_constant(&mut self, constant_definition: &mut ConstantDefinition) {
+        self.names.push(constant_definition.constant_name.name.to_owned());
+    }
+
+    fn visit_function(&mut self, function_definition: &mut FunctionDeclaration) {
+        self.names.push(function_definition.function_name.name.to_owned());
+        self.walk_function(function_definition);
+    }
+
+    fn visit_function_argument(&mut self, function_argument: &mut FunctionArgumentDeclaration) {
+        self.names.push(function_argument.argument_name.name.to_owned());
+    }
+
+    fn visit_local_statement(&mut self, local_statement: &mut LocalDeclaration) {
+        self.names.push(local_statement.symbol_name.name.to_owned());
+    }
+
+    fn visit_const_statement(&mut self, const_statement: &mut LocalConstantDeclaration) {
+        self.names.push(const_statement.constant_name.name.to_owned());
+    }
+
+    fn visit_for_statement(&mut self, for_statement: &mut ForStatement) {
+        self.names.push(for_statement.loop_variable.name.to_owned());
+        self.walk_for_statement(for_statement);
+    }
+
+    // expressions declare no names
+    fn visit_expression(&mut self, _expression_statement: &mut ExpressionStatement) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+
+    fn rename(code: &str, reserved: &[&str]) -> HashMap<String, String> {
+        let module = Module::new("test".to_owned(), code.to_owned(), compile_ast(code), false);
+        rename_reserved(&module, reserved)
+    }
+
+    #[test]
+    fn reserved_declarations_are_renamed() {
+        let renames = rename("struct Light { active: f32, } fn output(input: f32) -> f32 { let filter = input; return filter; }", &["active", "output", "input", "filter", "float"]);
+
+        let mut renames: Vec<(String, String)> = renames.into_iter().collect();
+        renames.sort();
+        assert_eq!(renames, vec![
+            ("active".to_owned(), "active_".to_owned()),
+            ("filter".to_owned(), "filter_".to_owned()),
+            ("input".to_owned(), "input_".to_owned()),
+            ("output".to_owned(), "output_".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn new_names_avoid_declared_names() {
+        let renames = rename("fn scale(input: f32, input_: f32) -> f32 { return input_; }", &["input"]);
+
+        assert_eq!(renames.get("input").map(String::as_str), Some("input__"));
+        assert_eq!(renames.len(), 1);
+    }
+
+    #[test]
+    fn locals_of_nested_blocks_are_renamed() {
+        let renames = rename("fn scale(a: f32, b: bool) -> f32 { if b { let input = a; return input; } for sample in 0..4 { let output = a; } return a; }", &["input", "sample", "output"]);
+
+        assert_eq!(renames.len(), 3);
+    }
+}
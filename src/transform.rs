@@ -0,0 +1,170 @@
+use ::ast::*;
+use ::module::Module;
+use ::passes::ast::AstWalker;
+use ::type_system::symbol_table::SymbolTable;
+
+/// collapses chains of implicit casts, e.g. `i8 -> i16 -> i32`, into a single cast to the final type
+/// a chain is only collapsed if an implicit cast from the innermost type to the final type exists
+/// modules that are not type checked have no cast types yet and are left unchanged
+pub fn collapse_casts(module: &mut Module) {
+    let symbol_table = match module.get_symbol_table() {
+        Some(symbol_table) => symbol_table.clone(),
+        None => return,
+    };
+    let symbol_table = symbol_table.borrow();
+    let mut collapser = CastCollapser {
+        symbol_table: &symbol_table,
+    };
+    collapser.visit(module.get_ast_mut());
+}
+
+/// copy of `module` containing only the `stage` entry points of its programs and the declarations they use
+/// e.g. for engines compiling stages separately, programs without such a stage are dropped
+/// primitives, casts, operators and imports are kept, exports are dropped as nothing imports a single stage
+pub fn extract_stage(module: &Module, stage: ShaderStage) -> Module {
+    let used = module.stage_dependencies(stage.get_name());
+    let is_used = |name: &Identifier| used.contains(&name.name);
+
+    let ast = module.get_ast().iter().filter_map(|item| match *item {
+        ItemKind::Program(ref p) => {
+            let program_stages: Vec<ProgramStageDefinition> = p.program_stages.iter().filter(|s| s.stage_name.name == stage.get_name()).cloned().collect();
+            if program_stages.is_empty() {
+                return None;
+            }
+
+            Some(ItemKind::Program(ProgramDefinition {
+                span: p.span,
+                program_name: p.program_name.clone(),
+                program_stages: program_stages,
+            }))
+        },
+        ItemKind::Function(ref f) if !is_used(&f.function_name) => None,
+        ItemKind::FunctionAlias(ref a) if !is_used(&a.alias_name) => None,
+        ItemKind::Struct(ref s) if !is_used(&s.struct_name) => None,
+        ItemKind::Enum(ref e) if !is_used(&e.enum_name) => None,
+        ItemKind::Constant(ref c) if !is_used(&c.constant_name) => None,
+        ItemKind::Export(_) => None,
+        ref item => Some(item.clone()),
+    }).collect();
+
+    module.with_ast(ast)
+}
+
+struct CastCollapser<'a> {
+    symbol_table: &'a SymbolTable,
+}
+
+impl<'a> AstWalker for CastCollapser<'a> {
+    fn visit(&mut self, items: &mut Ast) {
+        for item in items.iter_mut() {
+            match *item {
+                ItemKind::Function(ref mut function) => self.visit_function(function),
+                ItemKind::Operator(ref mut operator) => self.visit_operator(operator),
+                ItemKind::Program(ref mut program) => {
+                    for stage in program.program_stages.iter_mut() {
+                        self.visit_function(&mut stage.function);
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+
+    fn visit_cast_expression(&mut self, cast_expression: &mut CastExpression) {
+        // inner chains are collapsed first so every chain ends up with a single node
+        self.walk_cast_expression(cast_expression);
+
+        if cast_expression.cast_type != CastType::Implicit {
+            return;
+        }
+
+        let collapsed = match *cast_expression.expression {
+            ExpressionStatement::Cast(ref inner) if inner.cast_type == CastType::Implicit => {
+                match (inner.expression.get_type(), cast_expression.cast_target_type) {
+                    (Some(source), Some(target)) if self.symbol_table.does_implicit_cast_exist(source, target) => inner.expression.clone(),
+                    _ => return,
+                }
+            },
+            _ => return,
+        };
+
+        cast_expression.expression = collapsed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+    use ::passes::results::{ PassResult, PassResultReference };
+    use ::type_system::symbol_table::SymbolTableReference;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn return_expression(module: &mut Module) -> &mut ExpressionStatement {
+        match module.get_ast_mut()[6] {
+            ItemKind::Function(ref mut f) => match f.block.statements[0] {
+                BlockStatement::Return(ref mut r) => &mut r.expression,
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn collapse_two_step_cast_chain() {
+        let code = "primitive type i8; primitive type i16; primitive type i32; implicit cast i8 -> i16; implicit cast i16 -> i32; implicit cast i8 -> i32; fn widen(a: i8) -> i32 { return a; }";
+        let mut module = Module::new("test".to_owned(), code.to_owned(), compile_ast(code), true);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        module.type_check(symbol_table.clone(), result.clone());
+        assert!(!result.borrow().has_errors());
+
+        let i16_type = symbol_table.borrow().find_type_ref("i16").unwrap();
+        let i32_type = symbol_table.borrow().find_type_ref("i32").unwrap();
+
+        // replace the direct cast the type checker inserted by the chain `i8 -> i16 -> i32`
+        {
+            let expression = return_expression(&mut module);
+            let variable = match *expression {
+                ExpressionStatement::Cast(ref cast) => cast.expression.clone(),
+                _ => panic!("expected implicit cast"),
+            };
+            *expression = *variable;
+            expression.insert_implicit_cast(i16_type);
+            expression.insert_implicit_cast(i32_type);
+        }
+
+        collapse_casts(&mut module);
+
+        match *return_expression(&mut module) {
+            ExpressionStatement::Cast(ref cast) => {
+                assert_eq!(cast.cast_target_type, Some(i32_type));
+                match *cast.expression {
+                    ExpressionStatement::Variable(ref v) => assert_eq!(v.variable_name.name, "a"),
+                    _ => panic!("expected a single cast node"),
+                }
+            },
+            _ => panic!("expected implicit cast"),
+        }
+    }
+
+    #[test]
+    fn extract_fragment_stage() {
+        let code = "primitive type f32; struct Light { intensity: f32, } fn displace(x: f32) -> f32 { return x; } fn shade(light: Light) -> f32 { return light.intensity; } \
+            program Test { stage vertex(x: f32) -> f32 { return displace(x); } stage fragment(light: Light) -> f32 { return shade(light); } }";
+        let mut module = Module::new("test".to_owned(), code.to_owned(), compile_ast(code), true);
+        let symbol_table = SymbolTableReference::new(SymbolTable::new(TypeEnvironment::new()));
+        let result = PassResultReference::new(PassResult::new());
+        module.type_check(symbol_table.clone(), result.clone());
+        assert!(!result.borrow().has_errors());
+
+        let fragment = extract_stage(&module, ShaderStage::Fragment);
+
+        let function_names: Vec<&str> = fragment.find_functions().iter().map(|f| f.function_name.name.as_str()).collect();
+        assert_eq!(function_names, vec!["shade"]);
+        assert_eq!(fragment.find_structs().len(), 1);
+        let stage_names: Vec<&str> = fragment.find_programs()[0].program_stages.iter().map(|s| s.stage_name.name.as_str()).collect();
+        assert_eq!(stage_names, vec!["fragment"]);
+        assert!(fragment.is_checked());
+    }
+}
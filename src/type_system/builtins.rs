@@ -0,0 +1,391 @@
+use ::ast::Span;
+use ::type_system::error::{ TypeError, ErrorKind, TypeCheckResult };
+use ::type_system::symbol_table::SymbolTable;
+use ::type_system::type_environment::TypeReference;
+use ::type_system::layout::{ layout_of, LayoutRules };
+
+const SCALAR_TYPE_NAMES: &[&str] = &["f32", "f64", "i32", "i64"];
+
+/// vector type name, the type name of its components and the number of components
+const VECTOR_TYPE_NAMES: &[(&str, &str, usize)] = &[("vec2", "f32", 2), ("vec3", "f32", 3), ("vec4", "f32", 4)];
+
+/// channel names a swizzle can select components with, a swizzle may not mix the sets
+const SWIZZLE_CHANNELS: &[&str] = &["xyzw", "rgba", "stpq"];
+
+/// vector type name and the boolean vector with the same dimension
+const BOOL_VECTOR_TYPE_NAMES: &[(&str, &str)] = &[("vec2", "bvec2"), ("vec3", "bvec3"), ("vec4", "bvec4")];
+
+/// component-wise comparisons of two vectors
+const COMPARISON_NAMES: &[&str] = &["lessThan", "lessThanEqual", "greaterThan", "greaterThanEqual", "equal", "notEqual"];
+
+/// builtins taking fixed leading arguments followed by any number of arguments of one type
+/// name, types of the leading arguments, type of the repeated arguments and return type
+const VARIADIC_BUILTINS: &[(&str, &[&str], &str, &str)] = &[
+    // writes the values with the format registered under the id to the debug output, false if the output is full
+    ("debugPrintf", &["u32"], "f32", "bool"),
+];
+
+/// variables provided by the pipeline and the only stage they are available in
+const STAGE_GLOBALS: &[(&str, &str)] = &[
+    ("vertex_index", "vertex"),
+    ("instance_index", "vertex"),
+    ("frag_coord", "fragment"),
+    ("front_facing", "fragment"),
+    ("local_invocation_index", "compute"),
+];
+
+/// the stage a builtin global is restricted to, `None` if `name` is no builtin global
+pub fn stage_global_stage(name: &str) -> Option<&'static str> {
+    STAGE_GLOBALS.iter().find(|&&(global, _)| global == name).map(|&(_, stage)| stage)
+}
+
+/// Builtin functions are generic over scalar and vector types
+/// so they are checked by the rules below instead of a single call signature
+pub fn is_builtin(function_name: &str) -> bool {
+    match function_name {
+        "min" | "max" | "clamp" | "bitcast" | "sizeof" | "alignof" => true,
+        name if COMPARISON_NAMES.contains(&name) => true,
+        name if VARIADIC_BUILTINS.iter().any(|&(variadic, _, _, _)| variadic == name) => true,
+        name if VECTOR_TYPE_NAMES.iter().any(|&(vector, _, _)| vector == name) => true,
+        _ => false,
+    }
+}
+
+/// type of a call to the builtin `function_name` with the given type arguments and argument types
+pub fn resolve_builtin_call(symbol_table: &SymbolTable, span: Span, function_name: &str, type_arguments: &[TypeReference], arguments: &[TypeReference]) -> TypeCheckResult<TypeReference> {
+    let mismatch = || TypeError::new(span, ErrorKind::BuiltinArgumentMismatch(function_name.to_owned()));
+
+    if function_name == "bitcast" {
+        return match (type_arguments, arguments) {
+            (&[target_type], &[source_type]) => resolve_bitcast(symbol_table, span, source_type, target_type),
+            _ => Err(mismatch()),
+        };
+    }
+
+    // `sizeof(T)` is parsed with `T` as type argument, the value is folded by the type checker
+    if function_name == "sizeof" || function_name == "alignof" {
+        return match (type_arguments, arguments) {
+            (&[_], &[]) => symbol_table.find_type_ref("u32").ok_or_else(|| TypeError::new(span, ErrorKind::TypeNotFound("u32".to_owned()))),
+            _ => Err(mismatch()),
+        };
+    }
+
+    if !type_arguments.is_empty() {
+        return Err(mismatch());
+    }
+
+    // vector constructors fill the components from scalars and vectors in order, e.g. `vec4(v.xyz, 1.0)`
+    // a single argument is a cast, those are converted before calls are resolved
+    if let Some(&(_, component, width)) = VECTOR_TYPE_NAMES.iter().find(|&&(vector, _, _)| vector == function_name) {
+        let mut components = 0;
+        for &argument in arguments {
+            let name = type_name(symbol_table, argument);
+            components += match VECTOR_TYPE_NAMES.iter().find(|&&(vector, vector_component, _)| vector == name && vector_component == component) {
+                Some(&(_, _, argument_width)) => argument_width,
+                None if name == component => 1,
+                None => return Err(mismatch()),
+            };
+        }
+
+        if components != width {
+            return Err(mismatch());
+        }
+        return symbol_table.find_type_ref(function_name).ok_or_else(|| TypeError::new(span, ErrorKind::TypeNotFound(function_name.to_owned())));
+    }
+
+    if let Some(&(_, prefix, repeated, return_type)) = VARIADIC_BUILTINS.iter().find(|&&(name, _, _, _)| name == function_name) {
+        if arguments.len() < prefix.len() {
+            return Err(mismatch());
+        }
+
+        let (leading, tail) = arguments.split_at(prefix.len());
+        let leading_match = leading.iter().zip(prefix.iter()).all(|(&t, &name)| type_name(symbol_table, t) == name);
+        if !leading_match || !tail.iter().all(|&t| type_name(symbol_table, t) == repeated) {
+            return Err(mismatch());
+        }
+
+        return symbol_table.find_type_ref(return_type).ok_or_else(|| TypeError::new(span, ErrorKind::TypeNotFound(return_type.to_owned())));
+    }
+
+    if COMPARISON_NAMES.contains(&function_name) {
+        return match arguments {
+            &[left, right] if left == right => resolve_comparison(symbol_table, span, left).unwrap_or_else(|| Err(mismatch())),
+            _ => Err(mismatch()),
+        };
+    }
+
+    let value_type = match arguments.first() {
+        Some(t) if is_numeric(symbol_table, *t) => *t,
+        _ => return Err(mismatch()),
+    };
+
+    match function_name {
+        // component-wise on matching operands
+        "min" | "max" => {
+            if arguments.len() != 2 || arguments[1] != value_type {
+                return Err(mismatch());
+            }
+        },
+        // bounds either match the value or are broadcast from its component type
+        "clamp" => {
+            if arguments.len() != 3 {
+                return Err(mismatch());
+            }
+
+            let component_type = component_type(symbol_table, value_type);
+            if !arguments[1..].iter().all(|&bound| bound == value_type || Some(bound) == component_type) {
+                return Err(mismatch());
+            }
+        },
+        _ => return Err(TypeError::new(span, ErrorKind::VariableNotFound(function_name.to_owned()))),
+    }
+
+    Ok(value_type)
+}
+
+/// reinterprets the bits of a value, so both types need the same size
+fn resolve_bitcast(symbol_table: &SymbolTable, span: Span, source_type: TypeReference, target_type: TypeReference) -> TypeCheckResult<TypeReference> {
+    match (size_of(symbol_table, source_type), size_of(symbol_table, target_type)) {
+        (Some(source_size), Some(target_size)) if source_size == target_size => Ok(target_type),
+        _ => {
+            let source_name = type_name(symbol_table, source_type).to_owned();
+            let target_name = type_name(symbol_table, target_type).to_owned();
+            Err(TypeError::new(span, ErrorKind::BitcastSizeMismatch(source_name, target_name)))
+        },
+    }
+}
+
+/// compares two vectors component-wise into a boolean vector of the same dimension
+/// `None` if the operands are not vectors
+fn resolve_comparison(symbol_table: &SymbolTable, span: Span, vector_type: TypeReference) -> Option<TypeCheckResult<TypeReference>> {
+    let name = type_name(symbol_table, vector_type);
+    BOOL_VECTOR_TYPE_NAMES.iter().find(|&&(vector, _)| vector == name).map(|&(_, bool_vector)| {
+        symbol_table.find_type_ref(bool_vector).ok_or_else(|| TypeError::new(span, ErrorKind::TypeNotFound(bool_vector.to_owned())))
+    })
+}
+
+/// type of the swizzle `v.xyz` on the vector `vector_type`, a single component selects the component type
+/// `None` if `vector_type` is no vector type
+pub fn resolve_swizzle(symbol_table: &SymbolTable, span: Span, vector_type: TypeReference, swizzle: &str) -> Option<TypeCheckResult<TypeReference>> {
+    let name = type_name(symbol_table, vector_type);
+    VECTOR_TYPE_NAMES.iter().find(|&&(vector, _, _)| vector == name).map(|&(_, component, width)| {
+        let invalid = |component: &str| TypeError::new(span, ErrorKind::InvalidSwizzle(component.to_owned()));
+
+        if swizzle.len() > 4 {
+            return Err(invalid(swizzle));
+        }
+
+        let channels = SWIZZLE_CHANNELS.iter().find(|channels| swizzle.chars().next().is_some_and(|c| channels.contains(c)));
+        for c in swizzle.chars() {
+            match channels.and_then(|channels| channels.find(c)) {
+                Some(index) if index < width => (),
+                _ => return Err(invalid(&c.to_string())),
+            }
+        }
+
+        let result_name = match swizzle.len() {
+            1 => component,
+            count => match VECTOR_TYPE_NAMES.iter().find(|&&(_, c, w)| c == component && w == count) {
+                Some(&(vector, _, _)) => vector,
+                None => return Err(invalid(swizzle)),
+            },
+        };
+        symbol_table.find_type_ref(result_name).ok_or_else(|| TypeError::new(span, ErrorKind::TypeNotFound(result_name.to_owned())))
+    })
+}
+
+/// element type of `v[i]` on the indexable `indexed_type`, vectors are indexed by component
+/// `None` if `indexed_type` can't be indexed
+pub fn index_element_type(symbol_table: &SymbolTable, indexed_type: TypeReference) -> Option<TypeReference> {
+    let name = type_name(symbol_table, indexed_type);
+    VECTOR_TYPE_NAMES.iter().find(|&&(vector, _, _)| vector == name).and_then(|&(_, component, _)| symbol_table.find_type_ref(component))
+}
+
+/// byte size of a primitive or of a struct with its members tightly packed
+/// `None` for types without a known size
+pub fn size_of(symbol_table: &SymbolTable, type_reference: TypeReference) -> Option<usize> {
+    layout_of(symbol_table, type_reference, LayoutRules::Packed).map(|layout| layout.size)
+}
+
+fn type_name(symbol_table: &SymbolTable, type_reference: TypeReference) -> &str {
+    match symbol_table.find_type(type_reference) {
+        Some(t) => t.get_name(),
+        None => "",
+    }
+}
+
+/// signed scalars and vectors of them, the types arithmetic and negation are defined for
+pub fn is_numeric(symbol_table: &SymbolTable, type_reference: TypeReference) -> bool {
+    let name = type_name(symbol_table, type_reference);
+    SCALAR_TYPE_NAMES.contains(&name) || VECTOR_TYPE_NAMES.iter().any(|&(vector, _, _)| vector == name)
+}
+
+fn component_type(symbol_table: &SymbolTable, type_reference: TypeReference) -> Option<TypeReference> {
+    let name = type_name(symbol_table, type_reference);
+    match VECTOR_TYPE_NAMES.iter().find(|&&(vector, _, _)| vector == name) {
+        Some(&(_, component, _)) => symbol_table.find_type_ref(component),
+        None => Some(type_reference),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::type_system::type_environment::TypeEnvironment;
+
+    fn symbol_table() -> SymbolTable {
+        let mut symbol_table = SymbolTable::new(TypeEnvironment::new());
+        for name in &["bool", "f32", "i32", "u32", "vec2", "vec3", "vec4", "bvec3"] {
+            symbol_table.create_global_type(name).unwrap();
+        }
+        symbol_table
+    }
+
+    fn find(symbol_table: &SymbolTable, name: &str) -> TypeReference {
+        symbol_table.find_type_ref(name).unwrap()
+    }
+
+    #[test]
+    fn clamp_broadcasts_scalar_bounds() {
+        let symbol_table = symbol_table();
+        let vec3 = find(&symbol_table, "vec3");
+        let f32 = find(&symbol_table, "f32");
+
+        assert_eq!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "clamp", &[], &[vec3, f32, f32]), Ok(vec3));
+        assert_eq!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "clamp", &[], &[vec3, vec3, f32]), Ok(vec3));
+    }
+
+    #[test]
+    fn clamp_rejects_other_bounds() {
+        let symbol_table = symbol_table();
+        let vec3 = find(&symbol_table, "vec3");
+        let i32 = find(&symbol_table, "i32");
+
+        assert!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "clamp", &[], &[vec3, i32, i32]).is_err());
+    }
+
+    #[test]
+    fn min_is_component_wise() {
+        let symbol_table = symbol_table();
+        let vec4 = find(&symbol_table, "vec4");
+        let f32 = find(&symbol_table, "f32");
+
+        assert_eq!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "min", &[], &[vec4, vec4]), Ok(vec4));
+        assert_eq!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "max", &[], &[f32, f32]), Ok(f32));
+    }
+
+    #[test]
+    fn min_rejects_dimension_mismatch() {
+        let symbol_table = symbol_table();
+        let vec3 = find(&symbol_table, "vec3");
+        let vec4 = find(&symbol_table, "vec4");
+
+        let error = resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "min", &[], &[vec3, vec4]).unwrap_err();
+        assert_eq!(*error.get_kind(), ErrorKind::BuiltinArgumentMismatch("min".to_owned()));
+    }
+
+    #[test]
+    fn bitcast_between_same_size_types() {
+        let symbol_table = symbol_table();
+        let f32 = find(&symbol_table, "f32");
+        let u32 = find(&symbol_table, "u32");
+
+        assert_eq!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "bitcast", &[u32], &[f32]), Ok(u32));
+    }
+
+    #[test]
+    fn bitcast_between_different_size_types() {
+        let symbol_table = symbol_table();
+        let f32 = find(&symbol_table, "f32");
+        let vec3 = find(&symbol_table, "vec3");
+
+        let error = resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "bitcast", &[vec3], &[f32]).unwrap_err();
+        assert_eq!(*error.get_kind(), ErrorKind::BitcastSizeMismatch("f32".to_owned(), "vec3".to_owned()));
+    }
+
+    #[test]
+    fn vector_constructor_fills_all_components() {
+        let symbol_table = symbol_table();
+        let f32 = find(&symbol_table, "f32");
+        let vec2 = find(&symbol_table, "vec2");
+        let vec3 = find(&symbol_table, "vec3");
+        let vec4 = find(&symbol_table, "vec4");
+
+        assert_eq!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "vec4", &[], &[vec3, f32]), Ok(vec4));
+        assert_eq!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "vec4", &[], &[vec2, f32, f32]), Ok(vec4));
+        assert!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "vec4", &[], &[vec3, vec2]).is_err());
+    }
+
+    #[test]
+    fn variadic_builtin_takes_any_number_of_trailing_arguments() {
+        let symbol_table = symbol_table();
+        let u32 = find(&symbol_table, "u32");
+        let f32 = find(&symbol_table, "f32");
+        let bool_type = find(&symbol_table, "bool");
+
+        assert_eq!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "debugPrintf", &[], &[u32, f32, f32]), Ok(bool_type));
+        assert_eq!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "debugPrintf", &[], &[u32, f32, f32, f32, f32]), Ok(bool_type));
+    }
+
+    #[test]
+    fn variadic_builtin_rejects_wrong_trailing_type() {
+        let symbol_table = symbol_table();
+        let u32 = find(&symbol_table, "u32");
+        let f32 = find(&symbol_table, "f32");
+        let vec3 = find(&symbol_table, "vec3");
+
+        let error = resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "debugPrintf", &[], &[u32, f32, vec3]).unwrap_err();
+        assert_eq!(*error.get_kind(), ErrorKind::BuiltinArgumentMismatch("debugPrintf".to_owned()));
+
+        let error = resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "debugPrintf", &[], &[f32, f32]).unwrap_err();
+        assert_eq!(*error.get_kind(), ErrorKind::BuiltinArgumentMismatch("debugPrintf".to_owned()));
+    }
+
+    #[test]
+    fn less_than_produces_bool_vector() {
+        let symbol_table = symbol_table();
+        let vec3 = find(&symbol_table, "vec3");
+        let bvec3 = find(&symbol_table, "bvec3");
+
+        assert_eq!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "lessThan", &[], &[vec3, vec3]), Ok(bvec3));
+        assert_eq!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "notEqual", &[], &[vec3, vec3]), Ok(bvec3));
+    }
+
+    #[test]
+    fn comparison_rejects_dimension_mismatch() {
+        let symbol_table = symbol_table();
+        let vec3 = find(&symbol_table, "vec3");
+        let vec4 = find(&symbol_table, "vec4");
+        let f32 = find(&symbol_table, "f32");
+
+        let error = resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "lessThan", &[], &[vec3, vec4]).unwrap_err();
+        assert_eq!(*error.get_kind(), ErrorKind::BuiltinArgumentMismatch("lessThan".to_owned()));
+        assert!(resolve_builtin_call(&symbol_table, Span::new(0, 0, 1, 1), "lessThan", &[], &[f32, f32]).is_err());
+    }
+
+    #[test]
+    fn swizzle_selects_vector_or_component() {
+        let symbol_table = symbol_table();
+        let vec3 = find(&symbol_table, "vec3");
+        let vec4 = find(&symbol_table, "vec4");
+        let f32 = find(&symbol_table, "f32");
+
+        assert_eq!(resolve_swizzle(&symbol_table, Span::new(0, 0, 1, 1), vec4, "xyz"), Some(Ok(vec3)));
+        assert_eq!(resolve_swizzle(&symbol_table, Span::new(0, 0, 1, 1), vec4, "x"), Some(Ok(f32)));
+        assert_eq!(resolve_swizzle(&symbol_table, Span::new(0, 0, 1, 1), vec3, "bgr"), Some(Ok(vec3)));
+        assert_eq!(resolve_swizzle(&symbol_table, Span::new(0, 0, 1, 1), f32, "x"), None);
+    }
+
+    #[test]
+    fn swizzle_out_of_range_component_produces_an_error() {
+        let symbol_table = symbol_table();
+        let vec2 = find(&symbol_table, "vec2");
+        let vec4 = find(&symbol_table, "vec4");
+
+        let error = resolve_swizzle(&symbol_table, Span::new(0, 0, 1, 1), vec2, "xyw").unwrap().unwrap_err();
+        assert_eq!(*error.get_kind(), ErrorKind::InvalidSwizzle("w".to_owned()));
+
+        let error = resolve_swizzle(&symbol_table, Span::new(0, 0, 1, 1), vec4, "xg").unwrap().unwrap_err();
+        assert_eq!(*error.get_kind(), ErrorKind::InvalidSwizzle("g".to_owned()));
+    }
+}
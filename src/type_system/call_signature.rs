@@ -32,6 +32,10 @@ impl CallSignature {
         self.return_type == return_type
     }
 
+    pub fn get_arguments(&self) -> &[TypeReference] {
+        &self.arguments
+    }
+
     pub fn get_return_type(&self) -> Option<TypeReference> {
         match self.return_type {
             Some(t) => Some(t),
@@ -0,0 +1,426 @@
+use ::std::collections::HashMap;
+use ::std::fmt;
+use ::ast::*;
+use ::parser::parse_expression_str;
+use ::type_system::error::{ TypeError, ErrorKind, TypeCheckResult };
+
+/// value of an expression evaluated at compile time
+#[derive(Debug, Clone)]
+pub enum ConstValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// string literals and their concatenations, only attribute arguments accept them
+    String(String),
+}
+
+/// floats compare by their bits, so every value equals itself and values can be part of diagnostics
+impl PartialEq for ConstValue {
+    fn eq(&self, other: &ConstValue) -> bool {
+        match (self, other) {
+            (ConstValue::Int(l), ConstValue::Int(r)) => l == r,
+            (ConstValue::Float(l), ConstValue::Float(r)) => l.to_bits() == r.to_bits(),
+            (ConstValue::Bool(l), ConstValue::Bool(r)) => l == r,
+            (ConstValue::String(l), ConstValue::String(r)) => l == r,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ConstValue {}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", to_text(self))
+    }
+}
+
+impl ConstValue {
+    /// name of the type a literal of this value defaults to
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            ConstValue::Int(_) => "i32",
+            ConstValue::Float(_) => "f32",
+            ConstValue::Bool(_) => "bool",
+            ConstValue::String(_) => "string",
+        }
+    }
+}
+
+/// recursive `const fn` calls are cut off at this depth instead of never terminating
+const MAX_CALL_DEPTH: usize = 64;
+
+/// `const fn` declarations calls in constant expressions are evaluated with
+pub struct ConstFunctions {
+    functions: HashMap<String, FunctionDeclaration>,
+}
+
+impl ConstFunctions {
+    pub fn new() -> ConstFunctions {
+        ConstFunctions {
+            functions: HashMap::new(),
+        }
+    }
+
+    /// collects the `const fn`s declared in `items`
+    pub fn from_ast(items: &[ItemKind]) -> ConstFunctions {
+        let mut functions = HashMap::new();
+        for item in items.iter() {
+            if let ItemKind::Function(ref function) = *item {
+                if function.is_const {
+                    functions.insert(function.function_name.name.to_owned(), function.clone());
+                }
+            }
+        }
+
+        ConstFunctions {
+            functions: functions,
+        }
+    }
+}
+
+/// folds a constant expression to its value
+/// mixed integer and floating point operands are promoted to floating point, like the type checker does
+pub fn evaluate(expression: &ExpressionStatement) -> TypeCheckResult<ConstValue> {
+    evaluate_with(expression, &ConstFunctions::new(), &HashMap::new())
+}
+
+/// folds a constant expression which may call the given `const fn`s and refer to the given local constants
+pub fn evaluate_with(expression: &ExpressionStatement, functions: &ConstFunctions, constants: &HashMap<String, ConstValue>) -> TypeCheckResult<ConstValue> {
+    evaluate_in(expression, functions, constants, 0)
+}
+
+fn evaluate_in(expression: &ExpressionStatement, functions: &ConstFunctions, locals: &HashMap<String, ConstValue>, depth: usize) -> TypeCheckResult<ConstValue> {
+    match *expression {
+        ExpressionStatement::Literal(ref literal) => evaluate_literal(literal),
+        ExpressionStatement::Infix(ref infix) => {
+            let left = evaluate_in(&infix.left_hand, functions, locals, depth)?;
+            let right = evaluate_in(&infix.right_hand, functions, locals, depth)?;
+            evaluate_infix(infix, left, right)
+        },
+        ExpressionStatement::Prefix(ref prefix) => {
+            let value = evaluate_in(&prefix.expression, functions, locals, depth)?;
+            evaluate_prefix(prefix, value)
+        },
+        // casts between numeric types are resolved by promotion once the value is combined
+        ExpressionStatement::Cast(ref cast) => evaluate_in(&cast.expression, functions, locals, depth),
+        // only local constants, and arguments and locals of a `const fn` body have values
+        ExpressionStatement::Variable(ref variable) => match locals.get(&variable.variable_name.name) {
+            Some(value) => Ok(value.clone()),
+            None => Err(TypeError::new(variable.span, ErrorKind::NotAConstantExpression)),
+        },
+        ExpressionStatement::Call(ref call) => evaluate_call(call, functions, locals, depth),
+        // only the chosen branch is evaluated
+        ExpressionStatement::Ternary(ref ternary) => match evaluate_in(&ternary.condition, functions, locals, depth)? {
+            ConstValue::Bool(true) => evaluate_in(&ternary.true_expression, functions, locals, depth),
+            ConstValue::Bool(false) => evaluate_in(&ternary.false_expression, functions, locals, depth),
+            _ => Err(TypeError::new(ternary.condition.get_span(), ErrorKind::NotAConstantExpression)),
+        },
+        _ => Err(TypeError::new(expression.get_span(), ErrorKind::NotAConstantExpression)),
+    }
+}
+
+/// interprets the body of a `const fn` with its arguments bound to the folded argument values
+fn evaluate_call(call: &CallExpression, functions: &ConstFunctions, locals: &HashMap<String, ConstValue>, depth: usize) -> TypeCheckResult<ConstValue> {
+    let not_const = || TypeError::new(call.span, ErrorKind::NotAConstantExpression);
+
+    let function = match functions.functions.get(&call.function_name.name) {
+        Some(function) if depth < MAX_CALL_DEPTH && function.arguments.len() == call.arguments.len() => function,
+        _ => return Err(not_const()),
+    };
+
+    let mut frame = HashMap::new();
+    for (argument, expression) in function.arguments.iter().zip(call.arguments.iter()) {
+        let value = evaluate_in(expression, functions, locals, depth)?;
+        frame.insert(argument.argument_name.name.to_owned(), value);
+    }
+
+    for statement in function.block.statements.iter() {
+        match *statement {
+            BlockStatement::Local(ref local) => {
+                if let Some(ref expression) = local.expression {
+                    let value = evaluate_in(expression, functions, &frame, depth + 1)?;
+                    frame.insert(local.symbol_name.name.to_owned(), value);
+                }
+            },
+            BlockStatement::Const(ref constant) => {
+                let value = evaluate_in(&constant.expression, functions, &frame, depth + 1)?;
+                frame.insert(constant.constant_name.name.to_owned(), value);
+            },
+            BlockStatement::Assignment(ref assignment) => {
+                let value = evaluate_in(&assignment.expression, functions, &frame, depth + 1)?;
+                frame.insert(assignment.symbol_name.name.to_owned(), value);
+            },
+            BlockStatement::Expression(ref expression) => {
+                evaluate_in(expression, functions, &frame, depth + 1)?;
+            },
+            BlockStatement::Return(ref return_statement) => return evaluate_in(&return_statement.expression, functions, &frame, depth + 1),
+            _ => return Err(not_const()),
+        }
+    }
+
+    Err(not_const())
+}
+
+/// folded values of the module constants in `items` with an initializer, in declaration order so initializers may use earlier constants
+/// initializers which don't fold are left out, the type checker reports them
+pub fn evaluate_constants(items: &[ItemKind]) -> HashMap<String, ConstValue> {
+    let functions = ConstFunctions::from_ast(items);
+    let mut values = HashMap::new();
+    for item in items.iter() {
+        if let ItemKind::Constant(ConstantDefinition { ref constant_name, expression: Some(ref expression), .. }) = *item {
+            if let Ok(value) = evaluate_with(expression, &functions, &values) {
+                values.insert(constant_name.name.to_owned(), value);
+            }
+        }
+    }
+
+    values
+}
+
+/// parses and folds a standalone constant expression, e.g. to compute buffer sizes in build scripts
+/// the expression may refer to `constants`, e.g. the module constants from `evaluate_constants`
+pub fn eval_const(code: &str, constants: &HashMap<String, ConstValue>) -> TypeCheckResult<ConstValue> {
+    match parse_expression_str(code) {
+        Ok(expression) => evaluate_with(&expression, &ConstFunctions::new(), constants),
+        Err(error) => Err(TypeError::new(error.get_span(), ErrorKind::NotAConstantExpression)),
+    }
+}
+
+fn evaluate_literal(literal: &LiteralExpression) -> TypeCheckResult<ConstValue> {
+    let value = match literal.literal_expression_type {
+        LiteralType::Int => literal.value.parse::<i64>().ok().map(ConstValue::Int),
+        LiteralType::Float => literal.value.parse::<f64>().ok().map(ConstValue::Float),
+        LiteralType::Bool => literal.value.parse::<bool>().ok().map(ConstValue::Bool),
+        LiteralType::String => Some(ConstValue::String(literal.value.to_owned())),
+    };
+
+    value.ok_or_else(|| TypeError::new(literal.span, ErrorKind::NotAConstantExpression))
+}
+
+fn evaluate_infix(infix: &InfixExpression, left: ConstValue, right: ConstValue) -> TypeCheckResult<ConstValue> {
+    let mismatch = || TypeError::new(infix.span, ErrorKind::IncompatibleTypes(infix.left_hand.get_span(), infix.right_hand.get_span(), left.type_name().to_owned(), right.type_name().to_owned()));
+
+    // `+` with a string operand concatenates, the other operand is converted to its text
+    if let (Operator::Plus, true) = (infix.operator, is_string(&left) || is_string(&right)) {
+        return Ok(ConstValue::String(format!("{}{}", to_text(&left), to_text(&right))));
+    }
+
+    match (left.clone(), right.clone()) {
+        (ConstValue::Int(l), ConstValue::Int(r)) => evaluate_int(infix, l, r),
+        (ConstValue::Float(l), ConstValue::Float(r)) => evaluate_float(infix, l, r),
+        (ConstValue::Int(l), ConstValue::Float(r)) => evaluate_float(infix, l as f64, r),
+        (ConstValue::Float(l), ConstValue::Int(r)) => evaluate_float(infix, l, r as f64),
+        (ConstValue::Bool(l), ConstValue::Bool(r)) => match infix.operator {
+            Operator::Equal => Ok(ConstValue::Bool(l == r)),
+            Operator::NotEqual => Ok(ConstValue::Bool(l != r)),
+            _ => Err(mismatch()),
+        },
+        (ConstValue::String(l), ConstValue::String(r)) => match infix.operator {
+            Operator::Equal => Ok(ConstValue::Bool(l == r)),
+            Operator::NotEqual => Ok(ConstValue::Bool(l != r)),
+            _ => Err(mismatch()),
+        },
+        _ => Err(mismatch()),
+    }
+}
+
+fn is_string(value: &ConstValue) -> bool {
+    matches!(*value, ConstValue::String(_))
+}
+
+/// floats keep their fractional part, e.g. `1.0` instead of `1`
+fn to_text(value: &ConstValue) -> String {
+    match *value {
+        ConstValue::Int(v) => v.to_string(),
+        ConstValue::Float(v) => format!("{:?}", v),
+        ConstValue::Bool(v) => v.to_string(),
+        ConstValue::String(ref v) => v.to_owned(),
+    }
+}
+
+fn evaluate_prefix(prefix: &PrefixExpression, value: ConstValue) -> TypeCheckResult<ConstValue> {
+    match (prefix.operator, value) {
+        // negating the smallest integer overflows
+        (PrefixOperator::Negate, ConstValue::Int(v)) => v.checked_neg().map(ConstValue::Int).ok_or_else(|| TypeError::new(prefix.span, ErrorKind::NotAConstantExpression)),
+        (PrefixOperator::Negate, ConstValue::Float(v)) => Ok(ConstValue::Float(-v)),
+        (PrefixOperator::Not, ConstValue::Bool(v)) => Ok(ConstValue::Bool(!v)),
+        (operator, value) => Err(TypeError::new(prefix.span, ErrorKind::UnaryOperatorNotDefined(operator.get_symbol().to_owned(), value.type_name().to_owned()))),
+    }
+}
+
+fn evaluate_int(infix: &InfixExpression, left: i64, right: i64) -> TypeCheckResult<ConstValue> {
+    let value = match infix.operator {
+        Operator::Plus => left.checked_add(right).map(ConstValue::Int),
+        Operator::Minus => left.checked_sub(right).map(ConstValue::Int),
+        Operator::Multiply => left.checked_mul(right).map(ConstValue::Int),
+        Operator::Divide => {
+            if right == 0 {
+                return Err(TypeError::new(infix.span, ErrorKind::DivisionByZero));
+            }
+            left.checked_div(right).map(ConstValue::Int)
+        },
+        operator => Some(ConstValue::Bool(compare(operator, &left, &right))),
+    };
+
+    // overflowing arithmetic has no defined value
+    value.ok_or_else(|| TypeError::new(infix.span, ErrorKind::NotAConstantExpression))
+}
+
+fn evaluate_float(infix: &InfixExpression, left: f64, right: f64) -> TypeCheckResult<ConstValue> {
+    let value = match infix.operator {
+        Operator::Plus => ConstValue::Float(left + right),
+        Operator::Minus => ConstValue::Float(left - right),
+        Operator::Multiply => ConstValue::Float(left * right),
+        Operator::Divide => ConstValue::Float(left / right),
+        operator => ConstValue::Bool(compare(operator, &left, &right)),
+    };
+
+    Ok(value)
+}
+
+fn compare<T: PartialOrd>(operator: Operator, left: &T, right: &T) -> bool {
+    match operator {
+        Operator::Equal => left == right,
+        Operator::NotEqual => left != right,
+        Operator::Less => left < right,
+        Operator::LessEqual => left <= right,
+        Operator::Greater => left > right,
+        Operator::GreaterEqual => left >= right,
+        _ => unreachable!("arithmetic operator {:?} is not a comparison", operator),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::testing::compile_ast;
+
+    fn evaluate_return(code: &str) -> TypeCheckResult<ConstValue> {
+        let ast = compile_ast(code);
+        match ast[0] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => evaluate(&r.expression),
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn fold_arithmetic() {
+        assert_eq!(evaluate_return("fn main() -> i32 { return (1 + 2) * 3 - 4 / 2; }"), Ok(ConstValue::Int(7)));
+        assert_eq!(evaluate_return("fn main() -> f32 { return 1 + 0.5; }"), Ok(ConstValue::Float(1.5)));
+    }
+
+    #[test]
+    fn fold_comparison() {
+        assert_eq!(evaluate_return("fn main() -> bool { return 16 >= 16; }"), Ok(ConstValue::Bool(true)));
+        assert_eq!(evaluate_return("fn main() -> bool { return 1.0 < 2.0; }"), Ok(ConstValue::Bool(true)));
+        assert_eq!(evaluate_return("fn main() -> bool { return 3 != 1 + 2; }"), Ok(ConstValue::Bool(false)));
+    }
+
+    #[test]
+    fn fold_prefix_operators() {
+        assert_eq!(evaluate_return("fn main() -> i32 { return -(1 + 2) * 2; }"), Ok(ConstValue::Int(-6)));
+        assert_eq!(evaluate_return("fn main() -> bool { return !(1.0 < -2.0); }"), Ok(ConstValue::Bool(true)));
+    }
+
+    #[test]
+    fn comparing_booleans_by_order_produces_an_error() {
+        let error = evaluate_return("fn main() -> bool { return (1 < 2) < (2 < 3); }").unwrap_err();
+
+        match *error.get_kind() {
+            ErrorKind::IncompatibleTypes(_, _, _, _) => (),
+            ref kind => panic!("unexpected error {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn variable_is_not_a_constant_expression() {
+        let error = evaluate_return("fn main() -> bool { return a >= 16; }").unwrap_err();
+
+        assert_eq!(*error.get_kind(), ErrorKind::NotAConstantExpression);
+    }
+
+    #[test]
+    fn fold_ternary() {
+        assert_eq!(eval_const("true ? 1 : 2", &HashMap::new()), Ok(ConstValue::Int(1)));
+        assert_eq!(eval_const("1 > 2 ? 1 : 2 > 1 ? 2 : 3", &HashMap::new()), Ok(ConstValue::Int(2)));
+        // the branch not taken isn't evaluated
+        assert_eq!(eval_const("false ? 1 / 0 : 3", &HashMap::new()), Ok(ConstValue::Int(3)));
+    }
+
+    #[test]
+    fn ternary_with_variable_condition_is_not_a_constant_expression() {
+        let error = eval_const("a ? 1 : 2", &HashMap::new()).unwrap_err();
+
+        assert_eq!(*error.get_kind(), ErrorKind::NotAConstantExpression);
+    }
+
+    #[test]
+    fn integer_division_by_zero_produces_an_error() {
+        let error = evaluate_return("fn main() -> i32 { return 1 / (1 - 1); }").unwrap_err();
+
+        assert_eq!(*error.get_kind(), ErrorKind::DivisionByZero);
+    }
+
+    #[test]
+    fn eval_const_expression() {
+        assert_eq!(eval_const("2 * (3 + 4)", &HashMap::new()), Ok(ConstValue::Int(14)));
+    }
+
+    #[test]
+    fn eval_const_rejects_variables() {
+        let error = eval_const("someVar + 1", &HashMap::new()).unwrap_err();
+
+        assert_eq!(*error.get_kind(), ErrorKind::NotAConstantExpression);
+    }
+
+    #[test]
+    fn eval_const_with_module_constants() {
+        let constants = evaluate_constants(&compile_ast("const count: i32 = 4; const stride: i32 = count * 4;"));
+
+        assert_eq!(eval_const("count * stride", &constants), Ok(ConstValue::Int(64)));
+        assert!(eval_const("size + 1", &constants).is_err());
+    }
+
+    fn evaluate_last_return(code: &str) -> TypeCheckResult<ConstValue> {
+        let ast = compile_ast(code);
+        let functions = ConstFunctions::from_ast(&ast);
+        match ast[ast.len() - 1] {
+            ItemKind::Function(ref f) => match f.block.statements[0] {
+                BlockStatement::Return(ref r) => evaluate_with(&r.expression, &functions, &HashMap::new()),
+                _ => panic!("expected return statement"),
+            },
+            _ => panic!("expected function"),
+        }
+    }
+
+    #[test]
+    fn fold_const_fn_call() {
+        let code = "const fn area(w: i32, h: i32) -> i32 { let a = w * h; return a + 1; } fn main() -> i32 { return area(2, 3) * 2; }";
+
+        assert_eq!(evaluate_last_return(code), Ok(ConstValue::Int(14)));
+    }
+
+    #[test]
+    fn non_const_fn_call_is_not_a_constant_expression() {
+        let error = evaluate_last_return("fn area(w: i32) -> i32 { return w; } fn main() -> i32 { return area(2); }").unwrap_err();
+
+        assert_eq!(*error.get_kind(), ErrorKind::NotAConstantExpression);
+    }
+
+    #[test]
+    fn recursive_const_fn_call_is_not_a_constant_expression() {
+        let error = evaluate_last_return("const fn f(a: i32) -> i32 { return f(a); } fn main() -> i32 { return f(1); }").unwrap_err();
+
+        assert_eq!(*error.get_kind(), ErrorKind::NotAConstantExpression);
+    }
+
+    #[test]
+    fn fold_string_concatenation() {
+        assert_eq!(eval_const("\"mesh_\" + 2 * 3 + \"_\" + (1 < 2)", &HashMap::new()), Ok(ConstValue::String("mesh_6_true".to_owned())));
+        assert_eq!(eval_const("\"a\" == \"a\"", &HashMap::new()), Ok(ConstValue::Bool(true)));
+    }
+}
@@ -1,10 +1,11 @@
 use ::std::error::Error;
 use ::std::fmt;
 use ::ast::Span;
+use ::type_system::const_eval::ConstValue;
 
 pub type TypeCheckResult<T> = Result<T, TypeError>;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub enum ErrorKind {
     TypeNotFound(String),
     SymbolNameAlreadyUsed(String),
@@ -12,7 +13,7 @@ pub enum ErrorKind {
     CastAlreadyDeclared(String, String),
     VariableNotFound(String),
     CannotInfer(String),
-    IncompatibleTypes(Span, Span),
+    IncompatibleTypes(Span, Span, String /* Left type name */, String /* Right type name */),
     CannotMakeCallable,
     NotCallable,
     IncompatibleArguments,
@@ -23,6 +24,83 @@ pub enum ErrorKind {
     ProgramStageTooManyArguments(String, String),
     ProgramStageSignatureMismatch(String /* Source Stage */, String /* Target Stage */, String /* Source Stage Output */, String /* Target Stage Input */),
     InvalidExport(String /* Type name */),
+    InvalidPrimitive(String /* Type name */, String /* Reason */),
+    TooManyStructMembers(String /* Struct name */, usize /* Member count */, usize /* Limit */),
+    UnresolvedReference(String /* Node description */),
+    FloatEquality,
+    UsedBeforeInitialization(String /* Variable name */),
+    OperatorAlreadyDeclared(String /* Operator */, String /* Left hand type */, String /* Right hand type */),
+    OperatorNotFound(String /* Operator */, String /* Left hand type */, String /* Right hand type */),
+    IntegerDivisionInFloatContext,
+    UnknownAttribute(String /* Attribute name */),
+    ConflictingInlineHints(String /* Function name */),
+    CyclicDependency(Vec<String> /* Declaration names forming the cycle */),
+    InvalidAttributeArguments(String /* Attribute name */),
+    NonContiguousOutputLocations(Vec<usize> /* Missing locations */),
+    BuiltinArgumentMismatch(String /* Builtin name */),
+    UnexpectedStringLiteral,
+    UnknownSemantic(String /* Semantic name */),
+    NonBooleanCondition(String /* Condition type */),
+    LoopControlOutsideLoop(String /* Keyword */),
+    LocalShadowsType(String /* Local name */),
+    NotAConstantExpression,
+    DivisionByZero,
+    BitcastSizeMismatch(String /* Source type */, String /* Target type */),
+    InterpolationNotApplicable(String /* Qualifier */),
+    InvalidWorkgroupSize,
+    OutParamReadBeforeAssignment(String /* Parameter name */),
+    OutParamNotAssigned(String /* Parameter name */),
+    ModuleNotFound(String /* Module path */),
+    UnresolvedImport(String /* Module path */),
+    UnknownImportedSymbol(String /* Symbol name */, String /* Module path */),
+    UnresolvedPath(String /* Symbol name */),
+    SymbolNotVisible(String /* Symbol name */),
+    TypeUsedAsValue(String /* Type name */),
+    LocalEscapes(String /* Local name */),
+    LiteralOutOfRange(String /* Literal value */, String /* Type name */),
+    ConflictingOperatorPrecedence(String /* Operator symbol */),
+    RedundantCast(String /* Type name */),
+    UnknownEnumVariant(String /* Enum name */, String /* Variant name */),
+    InvalidMatchScrutinee(String /* Type name */),
+    NonExhaustiveMatch,
+    UnsizedTypeInSizeof(String /* Type name */),
+    NoDefaultConstructor(String /* Struct name */),
+    InvalidLoopRange(String /* From */, String /* To */),
+    ReturnTypeMismatch(String /* Expected type name */, String /* Found type name */),
+    MissingReturn(String /* Function name */),
+    MissingReturnOnPath(String /* Function name */),
+    StructFieldTypeMismatch(String /* Field name */, String /* Expected type name */, String /* Found type name */),
+    UnknownStructField(String /* Struct name */, String /* Field name */),
+    DuplicateFieldInitializer(String /* Field name */),
+    PossiblyUninitializedField(String /* Field name */),
+    MissingStructFields(String /* Struct name */, Vec<String> /* Field names */),
+    FunctionTooComplex(String /* Function name */, usize /* Expression count */, usize /* Limit */),
+    DuplicateStructMember(String /* Struct name */, String /* Member name */),
+    AliasTargetNotFound(String /* Target name */),
+    RecursiveStruct(Vec<String> /* Struct names forming the cycle */),
+    DuplicateArgumentName(String /* Function name */, String /* Argument name */),
+    ConstantType(String /* Constant name */, String /* Type name */, Option<ConstValue> /* Folded value */),
+    ArgumentCountMismatch(String /* Function name */, usize /* Expected count */, usize /* Found count */),
+    ArgumentTypeMismatch(String /* Function name */, usize /* Argument index */, String /* Expected type name */, String /* Found type name */),
+    NonConstInConstFn(String /* Function name */),
+    FieldAccessOnNonStruct(String /* Type name */),
+    InvalidSwizzle(String /* Component */),
+    IndexOnNonArray(String /* Type name */),
+    NonIntegerIndex(String /* Index type name */),
+    PushConstantTooLarge(usize /* Size in bytes */, usize /* Limit in bytes */),
+    AssignToConstant(String /* Constant name */),
+    UnaryOperatorNotDefined(String /* Operator */, String /* Operand type name */),
+    CastNotDeclared(String /* Source type name */, String /* Target type name */),
+    AttributeNotApplicable(String /* Attribute name */, String /* Stage name */),
+    InheritedMemberCollision(String /* Struct name */, String /* Base struct name */, String /* Member name */),
+    InvalidBaseStruct(String /* Type name */),
+    DeadStore(String /* Variable name */),
+    InvalidRepr(String /* Struct name */),
+    GlobalNotAvailableInStage(String /* Global name */, String /* Stage name */),
+    ConstantTypeMismatch(String /* Constant name */, String /* Expected type name */, String /* Found type name */),
+    NonConstantInitializer(String /* Constant name */),
+    UnusedLocal(String /* Local name */),
+    UnreachableStatement,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -48,8 +126,19 @@ impl TypeError {
     }
 }
 
+/// renders `line:column: message`, errors raised without a location like most symbol table errors render the message only
 impl fmt::Display for TypeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.span.length > 0 {
+            write!(f, "{}:{}: ", self.span.line, self.span.column)?;
+        }
+
+        self.fmt_message(f)
+    }
+}
+
+impl TypeError {
+    fn fmt_message(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.kind {
             ErrorKind::TypeNotFound(ref type_name) => {
                 write!(f, "Unknown type \"{}\".", type_name)
@@ -69,8 +158,8 @@ impl fmt::Display for TypeError {
             ErrorKind::CannotInfer(ref variable_name) => {
                 write!(f, "Cannot infer type for variable \"{}\".", variable_name)
             },
-            ErrorKind::IncompatibleTypes(_, _) => {
-                write!(f, "Incompatible types.")
+            ErrorKind::IncompatibleTypes(_, _, ref left_name, ref right_name) => {
+                write!(f, "Incompatible types \"{}\" and \"{}\".", left_name, right_name)
             },
             ErrorKind::CannotMakeCallable => {
                 write!(f, "Cannot make type callable.")
@@ -109,7 +198,85 @@ impl fmt::Display for TypeError {
                  target_stage_input_type_name,
                  target_stage_name)
             },
-            ErrorKind::InvalidExport(ref type_name) => write!(f, "Invalid export type named \"{}\"", type_name)
+            ErrorKind::InvalidExport(ref type_name) => write!(f, "Invalid export type named \"{}\"", type_name),
+            ErrorKind::InvalidPrimitive(ref type_name, ref reason) => write!(f, "Invalid primitive type \"{}\": {}", type_name, reason),
+            ErrorKind::TooManyStructMembers(ref struct_name, count, limit) => write!(f, "Struct \"{}\" has {} members, at most {} are allowed.", struct_name, count, limit),
+            ErrorKind::UnresolvedReference(ref node_description) => write!(f, "Type of {} was not resolved during type checking.", node_description),
+            ErrorKind::FloatEquality => write!(f, "Comparing floating point values for equality is error-prone."),
+            ErrorKind::UsedBeforeInitialization(ref variable_name) => write!(f, "Variable \"{}\" is used before it is initialized.", variable_name),
+            ErrorKind::OperatorAlreadyDeclared(ref operator, ref lhs, ref rhs) => write!(f, "Operator \"{} {} {}\" already declared.", lhs, operator, rhs),
+            ErrorKind::OperatorNotFound(ref operator, ref lhs, ref rhs) => write!(f, "No operator \"{} {} {}\" declared.", lhs, operator, rhs),
+            ErrorKind::IntegerDivisionInFloatContext => write!(f, "Integer division is truncated before the result is cast to a floating point type."),
+            ErrorKind::UnknownAttribute(ref attribute_name) => write!(f, "Unknown attribute \"{}\".", attribute_name),
+            ErrorKind::ConflictingInlineHints(ref function_name) => write!(f, "Function \"{}\" is marked both inline and noinline.", function_name),
+            ErrorKind::CyclicDependency(ref cycle) => write!(f, "Cyclic dependency between declarations: {}.", cycle.join(" -> ")),
+            ErrorKind::InvalidAttributeArguments(ref attribute_name) => write!(f, "Invalid arguments for attribute \"{}\".", attribute_name),
+            ErrorKind::NonContiguousOutputLocations(ref gaps) => write!(f, "Output locations are not contiguous, missing locations: {:?}.", gaps),
+            ErrorKind::BuiltinArgumentMismatch(ref builtin_name) => write!(f, "Arguments do not match builtin \"{}\".", builtin_name),
+            ErrorKind::UnexpectedStringLiteral => write!(f, "String literals are only valid as attribute arguments."),
+            ErrorKind::UnknownSemantic(ref semantic_name) => write!(f, "Unknown semantic \"{}\".", semantic_name),
+            ErrorKind::NonBooleanCondition(ref type_name) => write!(f, "Condition must be of type bool, found \"{}\".", type_name),
+            ErrorKind::LoopControlOutsideLoop(ref keyword) => write!(f, "\"{}\" is only valid inside a loop.", keyword),
+            ErrorKind::LocalShadowsType(ref local_name) => write!(f, "Local \"{}\" shadows the type of the same name.", local_name),
+            ErrorKind::NotAConstantExpression => write!(f, "Expression cannot be evaluated at compile time."),
+            ErrorKind::DivisionByZero => write!(f, "Division by zero in constant expression."),
+            ErrorKind::BitcastSizeMismatch(ref source_name, ref target_name) => write!(f, "Cannot bitcast \"{}\" to \"{}\", their sizes differ.", source_name, target_name),
+            ErrorKind::InterpolationNotApplicable(ref qualifier) => write!(f, "Interpolation qualifier \"{}\" is only valid on fragment stage inputs.", qualifier),
+            ErrorKind::InvalidWorkgroupSize => write!(f, "Workgroup size must be three positive integers."),
+            ErrorKind::OutParamReadBeforeAssignment(ref parameter_name) => write!(f, "Out parameter \"{}\" is read before it is assigned.", parameter_name),
+            ErrorKind::OutParamNotAssigned(ref parameter_name) => write!(f, "Out parameter \"{}\" is not assigned on every return.", parameter_name),
+            ErrorKind::ModuleNotFound(ref module_path) => write!(f, "Module \"{}\" not found.", module_path),
+            ErrorKind::UnresolvedImport(ref module_path) => write!(f, "Imported module \"{}\" not found.", module_path),
+            ErrorKind::UnknownImportedSymbol(ref symbol_name, ref module_path) => write!(f, "\"{}\" is not exported by module \"{}\".", symbol_name, module_path),
+            ErrorKind::UnresolvedPath(ref symbol_name) => write!(f, "Imported module does not declare \"{}\".", symbol_name),
+            ErrorKind::SymbolNotVisible(ref symbol_name) => write!(f, "\"{}\" is not exported by the imported module.", symbol_name),
+            ErrorKind::TypeUsedAsValue(ref type_name) => write!(f, "Type \"{}\" used as a value, types have to be instantiated.", type_name),
+            ErrorKind::LocalEscapes(ref local_name) => write!(f, "Resource handle \"{}\" created in this function must not be returned.", local_name),
+            ErrorKind::LiteralOutOfRange(ref value, ref type_name) => write!(f, "Literal {} is out of range for type \"{}\".", value, type_name),
+            ErrorKind::ConflictingOperatorPrecedence(ref operator) => write!(f, "Operator \"{}\" is declared with conflicting precedences.", operator),
+            ErrorKind::RedundantCast(ref type_name) => write!(f, "Cast to \"{}\" is redundant, the expression already has this type.", type_name),
+            ErrorKind::UnknownEnumVariant(ref enum_name, ref variant_name) => write!(f, "Enum \"{}\" has no variant \"{}\".", enum_name, variant_name),
+            ErrorKind::InvalidMatchScrutinee(ref type_name) => write!(f, "Cannot match on type \"{}\", only integers and enums can be matched.", type_name),
+            ErrorKind::NonExhaustiveMatch => write!(f, "Match does not cover all variants, add the missing variants or a wildcard arm."),
+            ErrorKind::UnsizedTypeInSizeof(ref type_name) => write!(f, "Type \"{}\" has no known size or alignment.", type_name),
+            ErrorKind::InvalidLoopRange(ref from, ref to) => write!(f, "Loop range {}..{} is empty, the lower bound has to be smaller than the upper bound.", from, to),
+            ErrorKind::ReturnTypeMismatch(ref expected, ref found) => write!(f, "Expected to return \"{}\", found \"{}\".", expected, found),
+            ErrorKind::MissingReturn(ref function_name) => write!(f, "\"{}\" does not return a value.", function_name),
+            ErrorKind::MissingReturnOnPath(ref function_name) => write!(f, "\"{}\" does not return a value on every path.", function_name),
+            ErrorKind::NoDefaultConstructor(ref struct_name) => write!(f, "\"{}\" has no default constructor, all of its fields need a default value.", struct_name),
+            ErrorKind::StructFieldTypeMismatch(ref field_name, ref expected, ref found) => write!(f, "Field \"{}\" is of type \"{}\", found \"{}\".", field_name, expected, found),
+            ErrorKind::UnknownStructField(ref struct_name, ref field_name) => write!(f, "Struct \"{}\" has no field \"{}\".", struct_name, field_name),
+            ErrorKind::DuplicateFieldInitializer(ref field_name) => write!(f, "Field \"{}\" is initialized more than once.", field_name),
+            ErrorKind::PossiblyUninitializedField(ref field_name) => write!(f, "Field \"{}\" is not set by this update or any of its bases.", field_name),
+            ErrorKind::MissingStructFields(ref struct_name, ref field_names) => write!(f, "Missing fields of struct \"{}\": {}.", struct_name, field_names.join(", ")),
+            ErrorKind::FunctionTooComplex(ref function_name, count, limit) => write!(f, "Function \"{}\" has {} expressions, at most {} are allowed.", function_name, count, limit),
+            ErrorKind::DuplicateStructMember(ref struct_name, ref member_name) => write!(f, "Struct \"{}\" declares member \"{}\" more than once.", struct_name, member_name),
+            ErrorKind::AliasTargetNotFound(ref target_name) => write!(f, "Alias target \"{}\" is not a function.", target_name),
+            ErrorKind::RecursiveStruct(ref cycle) => write!(f, "Structs contain themselves and have no finite size: {}.", cycle.join(" -> ")),
+            ErrorKind::DuplicateArgumentName(ref function_name, ref argument_name) => write!(f, "Function \"{}\" declares argument \"{}\" more than once.", function_name, argument_name),
+            ErrorKind::ConstantType(ref constant_name, ref type_name, None) => write!(f, "Constant \"{}\" has type \"{}\".", constant_name, type_name),
+            ErrorKind::ConstantType(ref constant_name, ref type_name, Some(ref value)) => write!(f, "Constant \"{}\" has type \"{}\" and value {}.", constant_name, type_name, value),
+            ErrorKind::ArgumentCountMismatch(ref function_name, expected, found) => write!(f, "Function \"{}\" takes {} arguments but {} were supplied.", function_name, expected, found),
+            ErrorKind::ArgumentTypeMismatch(ref function_name, index, ref expected, ref found) => write!(f, "Argument {} of function \"{}\" expects type \"{}\" but found \"{}\".", index, function_name, expected, found),
+            ErrorKind::NonConstInConstFn(ref function_name) => write!(f, "Function \"{}\" is declared const but cannot be evaluated at compile time.", function_name),
+            ErrorKind::FieldAccessOnNonStruct(ref type_name) => write!(f, "Type \"{}\" has no fields.", type_name),
+            ErrorKind::InvalidSwizzle(ref component) => write!(f, "Invalid swizzle component \"{}\".", component),
+            ErrorKind::IndexOnNonArray(ref type_name) => write!(f, "Type \"{}\" cannot be indexed.", type_name),
+            ErrorKind::NonIntegerIndex(ref type_name) => write!(f, "Index of type \"{}\" is no integer.", type_name),
+            ErrorKind::PushConstantTooLarge(size, limit) => write!(f, "Push constants take {} bytes, the limit is {} bytes.", size, limit),
+            ErrorKind::AssignToConstant(ref name) => write!(f, "Cannot assign to constant \"{}\".", name),
+            ErrorKind::UnaryOperatorNotDefined(ref operator, ref type_name) => write!(f, "Operator \"{}\" is not defined for type \"{}\".", operator, type_name),
+            ErrorKind::CastNotDeclared(ref source, ref target) => write!(f, "No cast from \"{}\" to \"{}\" is declared.", source, target),
+            ErrorKind::AttributeNotApplicable(ref attribute_name, ref stage_name) => write!(f, "Attribute \"{}\" can't be applied to {} stages.", attribute_name, stage_name),
+            ErrorKind::InheritedMemberCollision(ref struct_name, ref base_name, ref member_name) => write!(f, "Member \"{}\" of struct \"{}\" is already declared by its base \"{}\".", member_name, struct_name, base_name),
+            ErrorKind::InvalidBaseStruct(ref type_name) => write!(f, "Type \"{}\" is not a struct and can't be a base.", type_name),
+            ErrorKind::DeadStore(ref variable_name) => write!(f, "Value assigned to \"{}\" is overwritten before it is read.", variable_name),
+            ErrorKind::InvalidRepr(ref struct_name) => write!(f, "Invalid repr on \"{}\", expected `packed` or `align(n)` with n a power of two.", struct_name),
+            ErrorKind::GlobalNotAvailableInStage(ref global_name, ref stage_name) => write!(f, "\"{}\" is not available in {} stages.", global_name, stage_name),
+            ErrorKind::ConstantTypeMismatch(ref constant_name, ref expected, ref found) => write!(f, "Constant \"{}\" of type \"{}\" can't be initialized with a value of type \"{}\".", constant_name, expected, found),
+            ErrorKind::NonConstantInitializer(ref constant_name) => write!(f, "Initializer of constant \"{}\" cannot be evaluated at compile time.", constant_name),
+            ErrorKind::UnusedLocal(ref local_name) => write!(f, "Local \"{}\" is never read.", local_name),
+            ErrorKind::UnreachableStatement => write!(f, "Statement is unreachable."),
             
         }
     }
@@ -124,7 +291,7 @@ impl Error for TypeError {
             ErrorKind::CastAlreadyDeclared(_, _) => "Cast already declared.",
             ErrorKind::VariableNotFound(_) => "Unknown type.",
             ErrorKind::CannotInfer(_) => "Cannot infer type.",
-            ErrorKind::IncompatibleTypes(_, _) => "Incompatible types.",
+            ErrorKind::IncompatibleTypes(_, _, _, _) => "Incompatible types.",
             ErrorKind::CannotMakeCallable => "Cannot make type callable.",
             ErrorKind::NotCallable => "Not callable.",
             ErrorKind::IncompatibleArguments => "Arguments incompatible.",
@@ -135,6 +302,83 @@ impl Error for TypeError {
             ErrorKind::ProgramStageTooManyArguments(_, _) => "Too many arguments in stage function.",
             ErrorKind::ProgramStageSignatureMismatch(_, _, _, _) => "Incompatible signatures between linked program stages.",
             ErrorKind::InvalidExport(_) => "Invalid export type",
+            ErrorKind::InvalidPrimitive(_, _) => "Invalid primitive type",
+            ErrorKind::TooManyStructMembers(_, _, _) => "Too many struct members.",
+            ErrorKind::UnresolvedReference(_) => "Unresolved type reference.",
+            ErrorKind::FloatEquality => "Floating point equality comparison.",
+            ErrorKind::UsedBeforeInitialization(_) => "Variable used before initialization.",
+            ErrorKind::OperatorAlreadyDeclared(_, _, _) => "Operator already declared.",
+            ErrorKind::OperatorNotFound(_, _, _) => "Operator not found.",
+            ErrorKind::IntegerDivisionInFloatContext => "Integer division in floating point context.",
+            ErrorKind::UnknownAttribute(_) => "Unknown attribute.",
+            ErrorKind::ConflictingInlineHints(_) => "Conflicting inline hints.",
+            ErrorKind::CyclicDependency(_) => "Cyclic dependency.",
+            ErrorKind::InvalidAttributeArguments(_) => "Invalid attribute arguments.",
+            ErrorKind::NonContiguousOutputLocations(_) => "Non-contiguous output locations.",
+            ErrorKind::BuiltinArgumentMismatch(_) => "Builtin argument mismatch.",
+            ErrorKind::UnexpectedStringLiteral => "Unexpected string literal.",
+            ErrorKind::UnknownSemantic(_) => "Unknown semantic.",
+            ErrorKind::NonBooleanCondition(_) => "Non-boolean condition.",
+            ErrorKind::LoopControlOutsideLoop(_) => "Loop control outside of loop.",
+            ErrorKind::LocalShadowsType(_) => "Local shadows type.",
+            ErrorKind::NotAConstantExpression => "Not a constant expression.",
+            ErrorKind::DivisionByZero => "Division by zero.",
+            ErrorKind::BitcastSizeMismatch(_, _) => "Bitcast size mismatch.",
+            ErrorKind::InterpolationNotApplicable(_) => "Interpolation qualifier not applicable.",
+            ErrorKind::InvalidWorkgroupSize => "Invalid workgroup size.",
+            ErrorKind::OutParamReadBeforeAssignment(_) => "Out parameter read before assignment.",
+            ErrorKind::OutParamNotAssigned(_) => "Out parameter not assigned.",
+            ErrorKind::ModuleNotFound(_) => "Module not found.",
+            ErrorKind::UnresolvedImport(_) => "Unresolved import.",
+            ErrorKind::UnknownImportedSymbol(_, _) => "Unknown imported symbol.",
+            ErrorKind::UnresolvedPath(_) => "Unresolved import.",
+            ErrorKind::SymbolNotVisible(_) => "Symbol not visible.",
+            ErrorKind::TypeUsedAsValue(_) => "Type used as value.",
+            ErrorKind::LocalEscapes(_) => "Local escapes function.",
+            ErrorKind::LiteralOutOfRange(_, _) => "Literal out of range.",
+            ErrorKind::ConflictingOperatorPrecedence(_) => "Conflicting operator precedence.",
+            ErrorKind::RedundantCast(_) => "Redundant cast.",
+            ErrorKind::UnknownEnumVariant(_, _) => "Unknown enum variant.",
+            ErrorKind::InvalidMatchScrutinee(_) => "Invalid match scrutinee.",
+            ErrorKind::NonExhaustiveMatch => "Non exhaustive match.",
+            ErrorKind::UnsizedTypeInSizeof(_) => "Unsized type in sizeof.",
+            ErrorKind::NoDefaultConstructor(_) => "No default constructor.",
+            ErrorKind::InvalidLoopRange(_, _) => "Invalid loop range.",
+            ErrorKind::ReturnTypeMismatch(_, _) => "Return type mismatch.",
+            ErrorKind::MissingReturn(_) => "Missing return.",
+            ErrorKind::MissingReturnOnPath(_) => "Missing return on a path.",
+            ErrorKind::StructFieldTypeMismatch(_, _, _) => "Struct field type mismatch.",
+            ErrorKind::UnknownStructField(_, _) => "Unknown struct field.",
+            ErrorKind::DuplicateFieldInitializer(_) => "Duplicate field initializer.",
+            ErrorKind::PossiblyUninitializedField(_) => "Possibly uninitialized field.",
+            ErrorKind::MissingStructFields(_, _) => "Missing struct fields.",
+            ErrorKind::FunctionTooComplex(_, _, _) => "Function too complex.",
+            ErrorKind::DuplicateStructMember(_, _) => "Duplicate struct member.",
+            ErrorKind::AliasTargetNotFound(_) => "Alias target not found.",
+            ErrorKind::RecursiveStruct(_) => "Recursive struct.",
+            ErrorKind::DuplicateArgumentName(_, _) => "Duplicate argument name.",
+            ErrorKind::ConstantType(_, _, _) => "Constant type.",
+            ErrorKind::ArgumentCountMismatch(_, _, _) => "Argument count mismatch.",
+            ErrorKind::ArgumentTypeMismatch(_, _, _, _) => "Argument type mismatch.",
+            ErrorKind::NonConstInConstFn(_) => "Non-const construct in const fn.",
+            ErrorKind::FieldAccessOnNonStruct(_) => "Field access on non-struct type.",
+            ErrorKind::InvalidSwizzle(_) => "Invalid swizzle.",
+            ErrorKind::IndexOnNonArray(_) => "Index on non-array type.",
+            ErrorKind::NonIntegerIndex(_) => "Non-integer index.",
+            ErrorKind::PushConstantTooLarge(_, _) => "Push constants too large.",
+            ErrorKind::AssignToConstant(_) => "Assignment to constant.",
+            ErrorKind::UnaryOperatorNotDefined(_, _) => "Unary operator not defined.",
+            ErrorKind::CastNotDeclared(_, _) => "Cast not declared.",
+            ErrorKind::AttributeNotApplicable(_, _) => "Attribute not applicable.",
+            ErrorKind::InheritedMemberCollision(_, _, _) => "Inherited member collision.",
+            ErrorKind::InvalidBaseStruct(_) => "Invalid base struct.",
+            ErrorKind::DeadStore(_) => "Dead store.",
+            ErrorKind::InvalidRepr(_) => "Invalid repr.",
+            ErrorKind::GlobalNotAvailableInStage(_, _) => "Global not available in stage.",
+            ErrorKind::ConstantTypeMismatch(_, _, _) => "Constant initializer type mismatch.",
+            ErrorKind::NonConstantInitializer(_) => "Non-constant initializer.",
+            ErrorKind::UnusedLocal(_) => "Unused local.",
+            ErrorKind::UnreachableStatement => "Unreachable statement.",
         }
     }
 }
@@ -0,0 +1,158 @@
+use ::type_system::symbol_table::SymbolTable;
+use ::type_system::type_environment::TypeReference;
+
+/// primitive types with their byte size and alignment
+const PRIMITIVE_LAYOUTS: &[(&str, usize, usize)] = &[
+    ("bool", 4, 4), ("f16", 2, 2), ("f32", 4, 4), ("f64", 8, 8), ("i32", 4, 4), ("i64", 8, 8), ("u32", 4, 4), ("u64", 8, 8),
+    ("vec2", 8, 8), ("vec3", 12, 16), ("vec4", 16, 16),
+    ("bvec2", 8, 8), ("bvec3", 12, 16), ("bvec4", 16, 16),
+];
+
+/// Rules for placing struct members in memory
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum LayoutRules {
+    /// members tightly packed without padding
+    Packed,
+    /// uniform buffer layout, struct alignment is rounded up to 16 bytes
+    Std140,
+    /// storage buffer layout, like std140 without rounding up struct alignment
+    Std430,
+}
+
+/// Per struct override of the layout rules, e.g. `[repr(packed)]` or `[repr(align(16))]`
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Repr {
+    /// members are placed without padding and the struct has an alignment of 1
+    Packed,
+    /// raises the struct alignment to at least the given power of two
+    Align(usize),
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Layout {
+    pub size: usize,
+    pub alignment: usize,
+}
+
+/// size and alignment of a type, `None` for types without a known size
+/// enums are stored as `u32`
+pub fn layout_of(symbol_table: &SymbolTable, type_reference: TypeReference, rules: LayoutRules) -> Option<Layout> {
+    nested_layout_of(symbol_table, type_reference, rules, &mut Vec::new())
+}
+
+/// `enclosing` are the structs `type_reference` is a member of, a struct containing itself has no finite size
+fn nested_layout_of(symbol_table: &SymbolTable, type_reference: TypeReference, rules: LayoutRules, enclosing: &mut Vec<TypeReference>) -> Option<Layout> {
+    let type_definition = symbol_table.find_type(type_reference)?;
+    let type_name = if type_definition.is_enum() { "u32" } else { type_definition.get_name() };
+
+    let members = match type_definition.get_member() {
+        Some(members) => members,
+        None => return PRIMITIVE_LAYOUTS.iter().find(|&&(name, _, _)| name == type_name).map(|&(_, size, alignment)| Layout {
+            size: size,
+            alignment: if rules == LayoutRules::Packed { 1 } else { alignment },
+        }),
+    };
+
+    if enclosing.contains(&type_reference) {
+        return None;
+    }
+
+    enclosing.push(type_reference);
+    let member_layouts: Option<Vec<Layout>> = members.iter().map(|member| nested_layout_of(symbol_table, member.member_type, rules, enclosing)).collect();
+    enclosing.pop();
+
+    let repr = type_definition.get_repr();
+    let mut size = 0;
+    let mut alignment = 1;
+    for member_layout in member_layouts? {
+        let member_alignment = if repr == Some(Repr::Packed) { 1 } else { member_layout.alignment };
+        size = round_up(size, member_alignment) + member_layout.size;
+        alignment = alignment.max(member_alignment);
+    }
+
+    if rules == LayoutRules::Std140 && repr != Some(Repr::Packed) {
+        alignment = round_up(alignment, 16);
+    }
+
+    if let Some(Repr::Align(repr_alignment)) = repr {
+        alignment = alignment.max(repr_alignment);
+    }
+
+    Some(Layout {
+        size: round_up(size, alignment),
+        alignment: alignment,
+    })
+}
+
+fn round_up(value: usize, alignment: usize) -> usize {
+    value.div_ceil(alignment) * alignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::type_system::type_environment::TypeEnvironment;
+    use ::type_system::structure_members::{ StructureMember, StructureMembers };
+
+    fn symbol_table() -> SymbolTable {
+        let mut symbol_table = SymbolTable::new(TypeEnvironment::new());
+        for name in &["f32", "vec3"] {
+            symbol_table.create_global_type(name).unwrap();
+        }
+        symbol_table
+    }
+
+    fn add_struct(symbol_table: &mut SymbolTable, name: &str, members: &[(&str, &str)]) -> TypeReference {
+        let members = members.iter().map(|&(member, type_name)| StructureMember::new(member.to_owned(), symbol_table.find_type_ref(type_name).unwrap())).collect();
+        let type_ref = symbol_table.create_global_type(name).unwrap();
+        symbol_table.find_type_mut(type_ref).unwrap().set_members(StructureMembers::new(members)).unwrap();
+        type_ref
+    }
+
+    #[test]
+    fn std140_rounds_struct_alignment() {
+        let mut symbol_table = symbol_table();
+        let vec3 = add_struct(&mut symbol_table, "Vec3", &[("x", "f32"), ("y", "f32"), ("z", "f32")]);
+
+        assert_eq!(layout_of(&symbol_table, vec3, LayoutRules::Std140), Some(Layout { size: 16, alignment: 16 }));
+        assert_eq!(layout_of(&symbol_table, vec3, LayoutRules::Std430), Some(Layout { size: 12, alignment: 4 }));
+        assert_eq!(layout_of(&symbol_table, vec3, LayoutRules::Packed), Some(Layout { size: 12, alignment: 1 }));
+    }
+
+    #[test]
+    fn members_are_aligned() {
+        let mut symbol_table = symbol_table();
+        let light = add_struct(&mut symbol_table, "Light", &[("intensity", "f32"), ("direction", "vec3")]);
+
+        assert_eq!(layout_of(&symbol_table, light, LayoutRules::Std430), Some(Layout { size: 32, alignment: 16 }));
+        assert_eq!(layout_of(&symbol_table, light, LayoutRules::Packed), Some(Layout { size: 16, alignment: 1 }));
+    }
+
+    #[test]
+    fn packed_repr_removes_padding() {
+        let mut symbol_table = symbol_table();
+        let light = add_struct(&mut symbol_table, "Light", &[("intensity", "f32"), ("direction", "vec3")]);
+        symbol_table.find_type_mut(light).unwrap().set_repr(Repr::Packed);
+
+        assert_eq!(layout_of(&symbol_table, light, LayoutRules::Std140), Some(Layout { size: 16, alignment: 1 }));
+        assert_eq!(layout_of(&symbol_table, light, LayoutRules::Std430), Some(Layout { size: 16, alignment: 1 }));
+    }
+
+    #[test]
+    fn unknown_primitive_is_unsized() {
+        let mut symbol_table = symbol_table();
+        let texture = symbol_table.create_global_type("Texture2D").unwrap();
+
+        assert_eq!(layout_of(&symbol_table, texture, LayoutRules::Std140), None);
+    }
+
+    #[test]
+    fn recursive_struct_is_unsized() {
+        let mut symbol_table = symbol_table();
+        let node = symbol_table.create_global_type("Node").unwrap();
+        let members = StructureMembers::new(vec![StructureMember::new("value".to_owned(), symbol_table.find_type_ref("f32").unwrap()), StructureMember::new("next".to_owned(), node)]);
+        symbol_table.find_type_mut(node).unwrap().set_members(members).unwrap();
+
+        assert_eq!(layout_of(&symbol_table, node, LayoutRules::Std430), None);
+    }
+}
@@ -1,5 +1,8 @@
+pub mod builtins;
 pub mod call_signature;
+pub mod const_eval;
 pub mod error;
+pub mod layout;
 pub mod symbol_table;
 pub mod structure_members;
 pub mod type_environment;
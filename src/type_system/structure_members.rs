@@ -27,6 +27,10 @@ impl StructureMembers {
         }
     }
 
+    pub fn iter(&self) -> ::std::slice::Iter<'_, StructureMember> {
+        self.members.iter()
+    }
+
     pub fn find_member_type(&self, member_name: &str) -> Option<TypeReference> {
         for m in self.members.iter() {
             if m.member_name == member_name {
@@ -1,7 +1,7 @@
-use ::std::collections::HashMap;
+use ::std::collections::{ HashMap, HashSet };
 use ::std::rc::Rc;
 use ::std::cell::{ RefCell, Ref, RefMut };
-use ::ast::Span;
+use ::ast::{ Span, Operator };
 use ::type_system::error::{ TypeError, ErrorKind, TypeCheckResult };
 use ::type_system::type_definition::TypeDefinition;
 use ::type_system::type_environment::{ TypeEnvironment, TypeReference };
@@ -50,6 +50,8 @@ impl Symbol {
 struct Scope {
     symbols: HashMap<String, Symbol>,
     types: HashMap<String, TypeReference>,
+    /// symbols of this scope looked up by `find_symbol`
+    read: HashSet<String>,
 }
 
 impl Scope {
@@ -57,6 +59,7 @@ impl Scope {
         Scope {
             symbols: HashMap::new(),
             types: HashMap::new(),
+            read: HashSet::new(),
         }
     }
 }
@@ -75,6 +78,11 @@ impl SymbolTable {
         }
     }
 
+    /// the types, casts and operators declared so far, e.g. to export them with `TypeEnvironment::to_dot`
+    pub fn get_type_environment(&self) -> &TypeEnvironment {
+        &self.types
+    }
+
     pub fn add_global_type(&mut self, name: &str, type_reference: TypeReference) -> TypeCheckResult<()> {
         let root = self.scopes.len() - 1;
         if self.scopes[root].types.contains_key(name) {
@@ -137,6 +145,22 @@ impl SymbolTable {
         self.types.find_type_mut_or_err(type_ref)
     }
 
+    pub fn does_implicit_cast_exist(&self, from: TypeReference, to: TypeReference) -> bool {
+        self.types.does_implicit_cast_exist(from, to)
+    }
+
+    pub fn implicit_cast_path(&self, from: TypeReference, to: TypeReference) -> Option<Vec<TypeReference>> {
+        self.types.implicit_cast_path(from, to)
+    }
+
+    pub fn find_operator(&self, operator: Operator, lhs: TypeReference, rhs: TypeReference) -> Option<TypeReference> {
+        self.types.find_operator(operator, lhs, rhs)
+    }
+
+    pub fn add_operator(&mut self, operator: Operator, lhs: TypeReference, rhs: TypeReference, result: TypeReference) {
+        self.types.add_operator(operator, lhs, rhs, result)
+    }
+
     pub fn find_type_ref(&self, name: &str) -> Option<TypeReference> {
         for scope in &self.scopes {
             if scope.types.contains_key(name) {
@@ -181,16 +205,26 @@ impl SymbolTable {
         Ok(())
     }
 
+    /// looks the symbol up and marks it as read, `find_symbol_mut` doesn't
     pub fn find_symbol(&mut self, name: &str) -> Option<&Symbol> {
-        for scope in &self.scopes {
-            if scope.symbols.contains_key(name) {
-                return scope.symbols.get(name);
+        for scope in &mut self.scopes {
+            if let Some(symbol) = scope.symbols.get(name) {
+                scope.read.insert(name.to_owned());
+                return Some(symbol);
             }
         }
 
         None
     }
 
+    /// whether the visible symbol named `name` was looked up by `find_symbol` since it was added
+    pub fn is_symbol_read(&self, name: &str) -> bool {
+        match self.scopes.iter().find(|s| s.symbols.contains_key(name)) {
+            Some(scope) => scope.read.contains(name),
+            None => false,
+        }
+    }
+
     pub fn find_symbol_mut(&mut self, name: &str) -> Option<&mut Symbol> {
         for scope in &mut self.scopes {
             if scope.symbols.contains_key(name) {
@@ -215,6 +249,38 @@ impl SymbolTable {
         Err(TypeError::new(Span::new(0, 0, 1, 1), ErrorKind::VariableNotFound(name.to_owned())))
     }
 
+    /// whether `name` refers to a type rather than a value from the current scope
+    /// struct declarations add a symbol next to their type, values only shadow a type from an inner scope
+    /// functions are values, even though each has a type of the same name holding its call signature
+    pub fn is_type_name(&self, name: &str) -> bool {
+        for scope in &self.scopes {
+            if let Some(&type_ref) = scope.types.get(name) {
+                return self.find_type(type_ref).map_or(true, |t| t.get_call_signature().is_none());
+            }
+            if scope.symbols.contains_key(name) {
+                return false;
+            }
+        }
+
+        false
+    }
+
+    /// all symbols visible from the current scope, inner scopes shadow outer ones
+    pub fn visible_symbols(&self) -> Vec<(String, Option<TypeReference>)> {
+        let mut visible: Vec<(String, Option<TypeReference>)> = Vec::new();
+
+        for scope in &self.scopes {
+            for (name, symbol) in scope.symbols.iter() {
+                if !visible.iter().any(|&(ref n, _)| n == name) {
+                    visible.push((name.to_owned(), symbol.get_type()));
+                }
+            }
+        }
+
+        visible.sort_by(|a, b| a.0.cmp(&b.0));
+        visible
+    }
+
     pub fn enter_scope(&mut self) {
         self.scopes.insert(0, Scope::new());
     }
@@ -237,6 +303,19 @@ mod tests {
         assert_eq!(symbols.find_symbol("test_symbol"), Some(&Symbol::new("test_symbol", SymbolState::Free)));
     }
 
+    #[test]
+    fn find_symbol_marks_it_read() {
+        let mut symbols = SymbolTable::new(TypeEnvironment::new());
+        symbols.add_symbol("a").unwrap();
+        symbols.add_symbol("b").unwrap();
+
+        symbols.find_symbol("a");
+        symbols.find_symbol_mut("b");
+
+        assert!(symbols.is_symbol_read("a"));
+        assert!(!symbols.is_symbol_read("b"));
+    }
+
     #[test]
     fn enter_and_leave_scope() {
         let mut symbols = SymbolTable::new(TypeEnvironment::new());
@@ -270,10 +349,47 @@ mod tests {
         symbols.leave_scope();
     }
 
+    #[test]
+    fn visible_symbols_respect_shadowing() {
+        let mut symbols = SymbolTable::new(TypeEnvironment::new());
+        symbols.add_symbol_with_type("a", TypeReference::new(0)).unwrap();
+        symbols.add_symbol_with_type("b", TypeReference::new(0)).unwrap();
+        symbols.enter_scope();
+        symbols.add_symbol_with_type("b", TypeReference::new(1)).unwrap();
+        symbols.enter_scope();
+        symbols.add_symbol("c").unwrap();
+
+        assert_eq!(symbols.visible_symbols(), vec![
+            ("a".to_string(), Some(TypeReference::new(0))),
+            ("b".to_string(), Some(TypeReference::new(1))),
+            ("c".to_string(), None),
+        ]);
+
+        symbols.leave_scope();
+        symbols.leave_scope();
+
+        assert_eq!(symbols.visible_symbols(), vec![
+            ("a".to_string(), Some(TypeReference::new(0))),
+            ("b".to_string(), Some(TypeReference::new(0))),
+        ]);
+    }
+
     #[test]
     fn add_type() {
         let reference = TypeReference::new(0);
         let mut symbols = SymbolTable::new(TypeEnvironment::new());
         symbols.add_type("f32", reference).unwrap();
     }
+
+    #[test]
+    fn local_shadows_type_name() {
+        let mut symbols = SymbolTable::new(TypeEnvironment::new());
+        let type_ref = symbols.create_type("Point").unwrap();
+        symbols.add_symbol_with_type("Point", type_ref).unwrap();
+        assert!(symbols.is_type_name("Point"));
+
+        symbols.enter_scope();
+        symbols.add_symbol("Point").unwrap();
+        assert!(!symbols.is_type_name("Point"));
+    }
 }
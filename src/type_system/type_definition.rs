@@ -3,6 +3,7 @@ use ::type_system::call_signature::CallSignature;
 use ::type_system::structure_members::StructureMembers;
 use ::type_system::error::{ TypeError, ErrorKind, TypeCheckResult };
 use ::type_system::type_environment::TypeReference;
+use ::type_system::layout::Repr;
 
 #[derive(Debug, Eq)]
 pub struct TypeDefinition {
@@ -14,6 +15,9 @@ pub struct TypeDefinition {
     call_signature: Option<CallSignature>,
     // member: Option<Vec<TypeReference>>,
     member: Option<StructureMembers>,
+    variants: Option<Vec<String>>,
+    /// layout override of structs, e.g. `[repr(packed)]`
+    repr: Option<Repr>,
 }
 
 impl TypeDefinition {
@@ -25,6 +29,8 @@ impl TypeDefinition {
             explicit_casts: Vec::new(),
             call_signature: None,
             member: None,
+            variants: None,
+            repr: None,
         }
     }
 
@@ -71,6 +77,30 @@ impl TypeDefinition {
         }
     }
 
+    pub fn set_variants(&mut self, variants: Vec<String>) {
+        self.variants = Some(variants);
+    }
+
+    /// variant names in declaration order, `None` if this is no enum
+    pub fn get_variants(&self) -> Option<&[String]> {
+        match self.variants {
+            Some(ref v) => Some(v),
+            None => None,
+        }
+    }
+
+    pub fn is_enum(&self) -> bool {
+        self.variants.is_some()
+    }
+
+    pub fn set_repr(&mut self, repr: Repr) {
+        self.repr = Some(repr);
+    }
+
+    pub fn get_repr(&self) -> Option<Repr> {
+        self.repr
+    }
+
     pub fn make_callable(&mut self, signature: CallSignature) -> TypeCheckResult<()> {
         if self.is_callable() {
             return Err(TypeError::new(Span::empty(), ErrorKind::CannotMakeCallable));
@@ -114,6 +144,14 @@ impl TypeDefinition {
         self.explicit_casts.iter().any(|&t| t == other)
     }
 
+    pub fn get_implicit_casts(&self) -> &[TypeReference] {
+        &self.implicit_casts
+    }
+
+    pub fn get_explicit_casts(&self) -> &[TypeReference] {
+        &self.explicit_casts
+    }
+
     pub fn add_implicit_cast(&mut self, other: TypeReference) {
         self.implicit_casts.push(other);
     }
@@ -1,5 +1,6 @@
-use ::std::collections::HashMap;
-use ::ast::Span;
+use ::std::collections::{ HashMap, VecDeque };
+use ::ast::{ Span, Operator };
+use ::string_builder::StringBuilder;
 use ::type_system::error::{ TypeError, ErrorKind, TypeCheckResult };
 use ::type_system::type_definition::TypeDefinition;
 
@@ -24,6 +25,7 @@ impl TypeReference {
 pub struct TypeEnvironment {
     names_lookup: HashMap<String, TypeReference>,
     types: Vec<TypeDefinition>,
+    operators: HashMap<(Operator, TypeReference, TypeReference), TypeReference>,
 }
 
 impl TypeEnvironment {
@@ -31,6 +33,7 @@ impl TypeEnvironment {
         TypeEnvironment {
             names_lookup: HashMap::new(),
             types: Vec::new(),
+            operators: HashMap::new(),
         }
     }
 
@@ -79,6 +82,61 @@ impl TypeEnvironment {
         Ok(&mut self.types[id])
     }
 
+    pub fn does_implicit_cast_exist(&self, from: TypeReference, to: TypeReference) -> bool {
+        match self.find_type(from) {
+            Some(t) => t.does_implicit_cast_exist(to),
+            None => false,
+        }
+    }
+
+    /// shortest chain of implicit casts from `from` to `to`, including both ends
+    /// breadth-first, every type is visited at most once so cyclic casts terminate
+    pub fn implicit_cast_path(&self, from: TypeReference, to: TypeReference) -> Option<Vec<TypeReference>> {
+        if from == to {
+            return Some(vec![from]);
+        }
+
+        let mut previous: HashMap<TypeReference, TypeReference> = HashMap::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(from);
+
+        while let Some(current) = queue.pop_front() {
+            let casts = match self.find_type(current) {
+                Some(t) => t.get_implicit_casts(),
+                None => continue,
+            };
+
+            for &next in casts {
+                if next == from || previous.contains_key(&next) {
+                    continue;
+                }
+                previous.insert(next, current);
+
+                if next == to {
+                    let mut path = vec![to];
+                    while let Some(&step) = previous.get(path.last().unwrap()) {
+                        path.push(step);
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+
+                queue.push_back(next);
+            }
+        }
+
+        None
+    }
+
+    /// result type of `lhs operator rhs`, if such an operator was declared
+    pub fn find_operator(&self, operator: Operator, lhs: TypeReference, rhs: TypeReference) -> Option<TypeReference> {
+        self.operators.get(&(operator, lhs, rhs)).cloned()
+    }
+
+    pub fn add_operator(&mut self, operator: Operator, lhs: TypeReference, rhs: TypeReference, result: TypeReference) {
+        self.operators.insert((operator, lhs, rhs), result);
+    }
+
     pub fn find_reference_by_name(&self, name: &str) -> Option<TypeReference> {
         if !self.names_lookup.contains_key(name) {
             return None;
@@ -100,6 +158,40 @@ impl TypeEnvironment {
             None => None,
         }
     }
+
+    /// Graphviz graph of all types, with edges for casts and struct members
+    /// implicit casts are solid, explicit casts dashed and members dotted and labeled with the member name
+    pub fn to_dot(&self) -> String {
+        let mut sb = StringBuilder::new(1024);
+        sb.append("digraph types {\n");
+
+        for (id, t) in self.types.iter().enumerate() {
+            sb.append(&format!("    t{} [label=\"{}\"];\n", id, t.get_name()));
+        }
+
+        for (id, t) in self.types.iter().enumerate() {
+            for target in t.get_implicit_casts() {
+                sb.append(&format!("    t{} -> t{} [style=solid];\n", id, target.get_id()));
+            }
+            for target in t.get_explicit_casts() {
+                sb.append(&format!("    t{} -> t{} [style=dashed];\n", id, target.get_id()));
+            }
+            if let Some(members) = t.get_member() {
+                for member in members.iter() {
+                    sb.append(&format!("    t{} -> t{} [style=dotted, label=\"{}\"];\n", id, member.member_type.get_id(), member.member_name));
+                }
+            }
+        }
+
+        sb.append("}\n");
+        sb.to_string().unwrap_or_default()
+    }
+}
+
+impl Default for TypeEnvironment {
+    fn default() -> TypeEnvironment {
+        TypeEnvironment::new()
+    }
 }
 
 #[cfg(test)]
@@ -114,4 +206,47 @@ mod tests {
 
         assert_eq!(type_environment.find_type(reference), Some(&TypeDefinition::new(reference.get_id(), "f32")));
     }
+
+    #[test]
+    fn two_hop_implicit_cast_path() {
+        let mut type_environment = TypeEnvironment::new();
+        let i32_type = type_environment.create_type("i32").unwrap();
+        let f32_type = type_environment.create_type("f32").unwrap();
+        let f64_type = type_environment.create_type("f64").unwrap();
+        type_environment.find_type_mut(i32_type).unwrap().add_implicit_cast(f32_type);
+        type_environment.find_type_mut(f32_type).unwrap().add_implicit_cast(f64_type);
+
+        assert_eq!(type_environment.implicit_cast_path(i32_type, f64_type), Some(vec![i32_type, f32_type, f64_type]));
+        assert_eq!(type_environment.implicit_cast_path(f64_type, i32_type), None);
+    }
+
+    #[test]
+    fn implicit_cast_path_terminates_on_cycles() {
+        let mut type_environment = TypeEnvironment::new();
+        let a = type_environment.create_type("A").unwrap();
+        let b = type_environment.create_type("B").unwrap();
+        let c = type_environment.create_type("C").unwrap();
+        type_environment.find_type_mut(a).unwrap().add_implicit_cast(b);
+        type_environment.find_type_mut(b).unwrap().add_implicit_cast(a);
+
+        assert_eq!(type_environment.implicit_cast_path(a, c), None);
+        assert_eq!(type_environment.implicit_cast_path(b, a), Some(vec![b, a]));
+    }
+
+    #[test]
+    fn dot_graph_contains_types_and_casts() {
+        let mut type_environment = TypeEnvironment::new();
+        let f32_type = type_environment.create_type("f32").unwrap();
+        let f64_type = type_environment.create_type("f64").unwrap();
+        type_environment.find_type_mut(f32_type).unwrap().add_implicit_cast(f64_type);
+        type_environment.find_type_mut(f64_type).unwrap().add_explicit_cast(f32_type);
+
+        let dot = type_environment.to_dot();
+
+        assert!(dot.starts_with("digraph types {"));
+        assert!(dot.contains("t0 [label=\"f32\"];"));
+        assert!(dot.contains("t1 [label=\"f64\"];"));
+        assert!(dot.contains("t0 -> t1 [style=solid];"));
+        assert!(dot.contains("t1 -> t0 [style=dashed];"));
+    }
 }
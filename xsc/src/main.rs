@@ -93,7 +93,7 @@ pub fn main() {
                                 format!("error: Type has no members:"),
                                 format!("Type `{{}}` has no members."));
                             }
-                            &TypeErrorKind::IncompatibleTypes(left, right) => {
+                            &TypeErrorKind::IncompatibleTypes(left, right, ref left_name, ref right_name) => {
                                 let left_line = get_span_line(&lines, left);
                                 let right_line = get_span_line(&lines, right);
 
@@ -109,7 +109,7 @@ pub fn main() {
                                 println!("{}", prefix(&left_marker, indent, ""));
                                 println!("{}", prefix(&right_line, indent, &right.line.to_string()));
                                 println!("{}", prefix(&right_marker, indent, ""));
-                                println!("Cannot use operator `{{}}` on type `{{}}` and `{{}}`.");
+                                println!("Cannot combine type `{}` and `{}`.", left_name, right_name);
                             }
                             _ => println!("{:#?}", error),
                         }